@@ -0,0 +1,448 @@
+//! `omne-nexus backup` and `omne-nexus restore`
+//!
+//! A backup is a gzipped tar archive of the node database, the slashing
+//! protection database, the keystores under `keys/` and `config.toml`. Its
+//! first entry is `manifest.json`, naming the network, genesis hash, schema
+//! version and slashing protection watermark along with a SHA-256 checksum
+//! of every file. Both databases are held open while the archive is written,
+//! so the node must be stopped and the snapshot is consistent.
+//!
+//! Restore checks every checksum before touching the data dir, refuses an
+//! archive from another network or one whose slashing protection history is
+//! behind the one it would replace, and moves the replaced files aside
+//! instead of deleting them. Restored files get their archived permissions,
+//! except keystores, which are always private; the data dir's databases stay
+//! locked until the restored files are in place.
+
+use crate::config::ValidatorConfig;
+use crate::slashing::{SlashingProtection, Watermark};
+use crate::store::{Store, SCHEMA_VERSION};
+use crate::utils::format_bytes;
+
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::{Component, Path, PathBuf};
+
+/// Archive layout written by this build
+const FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const CONFIG_FILE: &str = "config.toml";
+const KEYS_DIR: &str = "keys";
+const DB_DIR: &str = "db";
+const SLASHING_PROTECTION_DIR: &str = "slashing_protection";
+
+/// Permissions of restored keystores, whatever the archive says
+const KEY_FILE_MODE: u32 = 0o600;
+
+/// Everything under the data dir that a backup covers
+const COMPONENTS: &[&str] = &[CONFIG_FILE, KEYS_DIR, DB_DIR, SLASHING_PROTECTION_DIR];
+
+/// Description of a backup, stored as its first entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    pub format_version: u32,
+    pub created_at: String,
+    pub node_version: String,
+    pub network_name: String,
+    pub network_id: u64,
+    pub genesis_hash: String,
+    pub schema_version: u32,
+    pub slashing_watermark: Watermark,
+    pub files: Vec<FileEntry>,
+}
+
+/// A file in the archive, by path relative to the data dir
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileEntry {
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// Outcome of a restore
+#[derive(Debug)]
+pub struct Restored {
+    pub manifest: Manifest,
+    /// Where the files that were replaced now live, if there were any
+    pub previous: Option<PathBuf>,
+}
+
+/// Write a backup of `data_dir` to `out`
+pub fn create(data_dir: &Path, out: &Path) -> Result<Manifest> {
+    let config = ValidatorConfig::load_from_file(&data_dir.join(CONFIG_FILE))
+        .with_context(|| format!("No usable {} in {}", CONFIG_FILE, data_dir.display()))?;
+
+    // Held until the archive is complete; each fails if the node is running
+    let store = Store::open_unmigrated(&data_dir.join(DB_DIR))?;
+    let protection = SlashingProtection::open(&data_dir.join(SLASHING_PROTECTION_DIR))?;
+    store.db().flush()?;
+    protection.flush()?;
+
+    let mut files = Vec::new();
+    for component in COMPONENTS {
+        collect_files(data_dir, Path::new(component), &mut files)?;
+    }
+    let files = files
+        .into_iter()
+        .map(|path| {
+            let (size, sha256) = checksum(File::open(data_dir.join(&path))?)?;
+            Ok(FileEntry {
+                path: archive_path(&path),
+                size,
+                sha256,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let manifest = Manifest {
+        format_version: FORMAT_VERSION,
+        created_at: chrono::Utc::now().to_rfc3339(),
+        node_version: env!("CARGO_PKG_VERSION").to_string(),
+        network_name: config.network.name.clone(),
+        network_id: config.network.id,
+        genesis_hash: config.network.genesis_hash.clone(),
+        schema_version: store.schema_version()?,
+        slashing_watermark: protection.watermark()?,
+        files,
+    };
+
+    // Written beside the target and renamed, so `out` is never left truncated
+    let partial = out.with_extension("partial");
+    let file = File::create(&partial).with_context(|| format!("Failed to create {}", partial.display()))?;
+    let mut archive = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    append_bytes(&mut archive, MANIFEST_FILE, &serde_json::to_vec_pretty(&manifest)?)?;
+    for entry in &manifest.files {
+        archive.append_path_with_name(data_dir.join(&entry.path), &entry.path)?;
+    }
+    archive.into_inner()?.finish()?.sync_all()?;
+    fs::rename(&partial, out).with_context(|| format!("Failed to write {}", out.display()))?;
+
+    drop((store, protection));
+    Ok(manifest)
+}
+
+/// Restore the backup at `from` into `data_dir`
+pub fn restore(from: &Path, data_dir: &Path) -> Result<Restored> {
+    let file = File::open(from).with_context(|| format!("Failed to open {}", from.display()))?;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+    let mut entries = archive.entries()?;
+
+    let manifest: Manifest = match entries.next() {
+        Some(entry) => {
+            let entry = entry?;
+            if entry.path()?.as_ref() != Path::new(MANIFEST_FILE) {
+                return Err(anyhow::anyhow!("{} is not an omne-nexus backup", from.display()));
+            }
+            serde_json::from_reader(entry).context("Malformed backup manifest")?
+        }
+        None => return Err(anyhow::anyhow!("{} is empty", from.display())),
+    };
+    // Held until the restored files are in place, so the node cannot start
+    // on a half-restored data dir
+    let locks = check_compatible(&manifest, data_dir)?;
+
+    // Unpack and verify everything before the data dir is modified
+    let staging = data_dir.join(".restore");
+    let _ = fs::remove_dir_all(&staging);
+    fs::create_dir_all(&staging)?;
+    let mut expected: HashMap<&str, &FileEntry> =
+        manifest.files.iter().map(|entry| (entry.path.as_str(), entry)).collect();
+
+    for entry in entries {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        let name = archive_path(&path);
+        if !path.components().all(|c| matches!(c, Component::Normal(_))) {
+            return Err(anyhow::anyhow!("Backup contains unsafe path {}", name));
+        }
+        let Some(file) = expected.remove(name.as_str()) else {
+            return Err(anyhow::anyhow!("Backup contains {} which is not in its manifest", name));
+        };
+
+        let target = staging.join(&path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Private until written, then given the archived permissions
+        let output = File::options().write(true).create_new(true).mode(KEY_FILE_MODE).open(&target)?;
+        let mut output = HashingWriter::new(output);
+        io::copy(&mut entry, &mut output)?;
+        let (size, sha256) = output.finish()?;
+        if size != file.size || sha256 != file.sha256 {
+            return Err(anyhow::anyhow!("Checksum mismatch for {}; the backup is corrupt", name));
+        }
+        let mode = if path.starts_with(KEYS_DIR) {
+            KEY_FILE_MODE
+        } else {
+            entry.header().mode()? & 0o777
+        };
+        fs::set_permissions(&target, fs::Permissions::from_mode(mode))?;
+    }
+    if let Some(missing) = expected.keys().next() {
+        return Err(anyhow::anyhow!("Backup is missing {}", missing));
+    }
+
+    // Move the current files aside, then the restored ones into place
+    let previous = data_dir.join(format!("pre-restore-{}", chrono::Utc::now().format("%Y%m%dT%H%M%S")));
+    let mut moved_aside = false;
+    for component in COMPONENTS {
+        let current = data_dir.join(component);
+        if current.exists() {
+            fs::create_dir_all(&previous)?;
+            fs::rename(&current, previous.join(component))?;
+            moved_aside = true;
+        }
+        let restored = staging.join(component);
+        if restored.exists() {
+            fs::rename(&restored, &current)?;
+        }
+    }
+    fs::remove_dir_all(&staging)?;
+    drop(locks);
+
+    Ok(Restored {
+        manifest,
+        previous: moved_aside.then_some(previous),
+    })
+}
+
+/// Databases of a data dir held open, which keeps a node from starting on it
+struct Locks {
+    _db: Option<sled::Db>,
+    _protection: Option<SlashingProtection>,
+}
+
+/// Refuse restoring `manifest` into `data_dir` if it belongs to another
+/// network, needs a newer build, or would roll back slashing protection;
+/// returns the data dir's databases, locked
+fn check_compatible(manifest: &Manifest, data_dir: &Path) -> Result<Locks> {
+    if manifest.format_version > FORMAT_VERSION {
+        return Err(anyhow::anyhow!(
+            "Backup format {} is newer than this build supports; upgrade omne-nexus",
+            manifest.format_version
+        ));
+    }
+    if manifest.schema_version > SCHEMA_VERSION {
+        return Err(anyhow::anyhow!(
+            "Backup database schema version {} is newer than version {} supported by this build; upgrade omne-nexus",
+            manifest.schema_version,
+            SCHEMA_VERSION
+        ));
+    }
+
+    let config_path = data_dir.join(CONFIG_FILE);
+    if config_path.exists() {
+        let config = ValidatorConfig::load_from_file(&config_path)?;
+        if config.network.id != manifest.network_id || config.network.genesis_hash != manifest.genesis_hash {
+            return Err(anyhow::anyhow!(
+                "Backup is for network {} (ID {}, genesis {}) but {} is for {} (ID {}, genesis {})",
+                manifest.network_name,
+                manifest.network_id,
+                manifest.genesis_hash,
+                data_dir.display(),
+                config.network.name,
+                config.network.id,
+                config.network.genesis_hash
+            ));
+        }
+    }
+
+    // Opening takes the lock, which fails while the node is running
+    let db_path = data_dir.join(DB_DIR);
+    let db = if db_path.exists() {
+        Some(sled::open(&db_path).with_context(|| format!("Failed to open {}; stop the node first", db_path.display()))?)
+    } else {
+        None
+    };
+
+    let protection_path = data_dir.join(SLASHING_PROTECTION_DIR);
+    let protection = if protection_path.exists() {
+        Some(SlashingProtection::open(&protection_path)?)
+    } else {
+        None
+    };
+    if let Some(protection) = &protection {
+        let current = protection.watermark()?;
+        if current.is_ahead_of(&manifest.slashing_watermark) {
+            return Err(anyhow::anyhow!(
+                "Slashing protection in {} ({:?}) is newer than the backup's ({:?}); restoring it could lead to signing slashable messages",
+                data_dir.display(),
+                current,
+                manifest.slashing_watermark
+            ));
+        }
+    }
+    Ok(Locks {
+        _db: db,
+        _protection: protection,
+    })
+}
+
+/// Files under `data_dir/relative`, as paths relative to `data_dir`
+fn collect_files(data_dir: &Path, relative: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    let path = data_dir.join(relative);
+    if path.is_dir() {
+        let mut children = fs::read_dir(&path)?.collect::<io::Result<Vec<_>>>()?;
+        children.sort_by_key(|child| child.file_name());
+        for child in children {
+            collect_files(data_dir, &relative.join(child.file_name()), files)?;
+        }
+    } else if path.is_file() {
+        files.push(relative.to_path_buf());
+    }
+    Ok(())
+}
+
+/// `/`-separated form of a relative path, as stored in the archive
+fn archive_path(path: &Path) -> String {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn append_bytes<W: Write>(archive: &mut tar::Builder<W>, name: &str, data: &[u8]) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o600);
+    header.set_mtime(chrono::Utc::now().timestamp() as u64);
+    header.set_cksum();
+    archive.append_data(&mut header, name, data)?;
+    Ok(())
+}
+
+/// Size and hex SHA-256 of everything read from `reader`
+fn checksum(mut reader: impl Read) -> Result<(u64, String)> {
+    let mut writer = HashingWriter::new(io::sink());
+    io::copy(&mut reader, &mut writer)?;
+    writer.finish()
+}
+
+/// Passes writes through while hashing them
+struct HashingWriter<W> {
+    inner: W,
+    hasher: Sha256,
+    size: u64,
+}
+
+impl<W: Write> HashingWriter<W> {
+    fn new(inner: W) -> Self {
+        Self {
+            inner,
+            hasher: Sha256::new(),
+            size: 0,
+        }
+    }
+
+    fn finish(mut self) -> Result<(u64, String)> {
+        self.inner.flush()?;
+        Ok((self.size, hex::encode(self.hasher.finalize())))
+    }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// `omne-nexus backup`
+pub fn run_backup(data_dir: &Path, out: &Path) -> Result<()> {
+    let manifest = create(data_dir, out)?;
+    let total: u64 = manifest.files.iter().map(|file| file.size).sum();
+    println!("✅ Backed up {} to {}", data_dir.display(), out.display());
+    println!("   Network:  {} (ID {})", manifest.network_name, manifest.network_id);
+    println!("   Files:    {} ({})", manifest.files.len(), format_bytes(total));
+    println!("   Archive:  {}", format_bytes(fs::metadata(out)?.len()));
+    Ok(())
+}
+
+/// `omne-nexus restore`
+pub fn run_restore(from: &Path, data_dir: &Path) -> Result<()> {
+    let restored = restore(from, data_dir)?;
+    println!(
+        "✅ Restored backup of {} from {} into {}",
+        restored.manifest.network_name,
+        restored.manifest.created_at,
+        data_dir.display()
+    );
+    if let Some(previous) = restored.previous {
+        println!("   Replaced files moved to {}", previous.display());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data_dir(network: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("omne-backup-{}", rand::random::<u32>()));
+        let config = ValidatorConfig::new_for_network(network).unwrap();
+        config.init_directories(&dir).unwrap();
+        config.save_to_file(&dir.join(CONFIG_FILE)).unwrap();
+        fs::write(dir.join(KEYS_DIR).join("validator.key"), "keystore").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_backup_and_restore() {
+        let source = data_dir("testnet");
+        SlashingProtection::open(&source.join(SLASHING_PROTECTION_DIR))
+            .unwrap()
+            .sign_block(5, b"root")
+            .unwrap();
+        let key = source.join(KEYS_DIR).join("validator.key");
+        fs::set_permissions(&key, fs::Permissions::from_mode(0o644)).unwrap();
+        fs::set_permissions(source.join(CONFIG_FILE), fs::Permissions::from_mode(0o640)).unwrap();
+        let archive = source.with_extension("tar.gz");
+        let manifest = create(&source, &archive).unwrap();
+        assert!(manifest.files.iter().any(|file| file.path == "keys/validator.key"));
+
+        // Into an empty data dir
+        let target = std::env::temp_dir().join(format!("omne-restore-{}", rand::random::<u32>()));
+        let restored = restore(&archive, &target).unwrap();
+        assert!(restored.previous.is_none());
+        assert_eq!(fs::read_to_string(target.join("keys/validator.key")).unwrap(), "keystore");
+        let mode = |path: PathBuf| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(target.join("keys/validator.key")), KEY_FILE_MODE);
+        assert_eq!(mode(target.join(CONFIG_FILE)), 0o640);
+        let watermark = SlashingProtection::open(&target.join(SLASHING_PROTECTION_DIR))
+            .unwrap()
+            .watermark()
+            .unwrap();
+        assert_eq!(watermark.block_slot, Some(5));
+
+        // Not over newer slashing protection history
+        SlashingProtection::open(&target.join(SLASHING_PROTECTION_DIR))
+            .unwrap()
+            .sign_block(6, b"root")
+            .unwrap();
+        assert!(restore(&archive, &target).is_err());
+
+        // Not into another network
+        let devnet = data_dir("devnet");
+        assert!(restore(&archive, &devnet).unwrap_err().to_string().contains("network"));
+
+        for dir in [source, target, devnet, archive] {
+            let _ = fs::remove_dir_all(&dir);
+            let _ = fs::remove_file(&dir);
+        }
+    }
+}
@@ -0,0 +1,477 @@
+//! Block store
+//!
+//! Blocks of both layers, the attestation pool, finalized checkpoints,
+//! proposer schedules and the validator registry with per-epoch snapshots,
+//! kept in tables of the node database. Consensus writes here as it imports
+//! blocks and restores its state from here on restart; RPC queries read from
+//! it and report missing entries instead of inventing them.
+
+use crate::events::Layer;
+use crate::store::{Store, Table, WriteBatch};
+
+use anyhow::{Context, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Names of the tables holding chain data
+const BLOCKS_TABLE: &str = "blocks";
+const BLOCK_INDEX_TABLE: &str = "block_index";
+const ATTESTATIONS_TABLE: &str = "attestations";
+const CHECKPOINTS_TABLE: &str = "finalized_checkpoints";
+const SCHEDULES_TABLE: &str = "proposer_schedules";
+const VALIDATORS_TABLE: &str = "validators";
+const VALIDATOR_SNAPSHOTS_TABLE: &str = "validator_snapshots";
+
+/// Length of block hashes in bytes
+pub const HASH_LEN: usize = 32;
+
+/// Length of BLS validator public keys in bytes
+pub const PUBKEY_LEN: usize = 48;
+
+/// Block header shared by both layers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct BlockHeader {
+    pub layer: Layer,
+    pub height: u64,
+    /// `0x`-prefixed block hash
+    pub hash: String,
+    pub parent_hash: String,
+    pub epoch: u64,
+    pub slot: u64,
+    /// Public key of the proposing validator
+    pub proposer: String,
+    pub state_root: String,
+    pub timestamp: i64,
+}
+
+/// Stored block: header plus the hashes of included transactions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Block {
+    pub header: BlockHeader,
+    pub transactions: Vec<String>,
+}
+
+/// A validator's vote for a block
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Attestation {
+    pub block_hash: String,
+    pub validator: String,
+    pub epoch: u64,
+    pub signature: String,
+}
+
+/// Latest finalized security checkpoint
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Checkpoint {
+    pub epoch: u64,
+    pub height: u64,
+    pub block_hash: String,
+}
+
+/// Proposer assigned to one slot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ProposerDuty {
+    pub slot: u64,
+    pub layer: Layer,
+    pub proposer: String,
+}
+
+/// Proposers of every slot in an epoch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ProposerSchedule {
+    pub epoch: u64,
+    pub duties: Vec<ProposerDuty>,
+}
+
+/// Registry entry of a validator
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ValidatorRecord {
+    pub pubkey: String,
+    pub stake: u64,
+    pub is_active: bool,
+    pub activation_epoch: u64,
+}
+
+/// Chain data in the node database
+pub struct ChainStore {
+    store: Store,
+    /// Blocks by root (hash)
+    blocks: Table<Block>,
+    /// Canonical `layer || height` → block hash
+    block_index: Table<String>,
+    /// Attestation pool, `block hash || validator` → attestation
+    attestations: Table<Attestation>,
+    /// Every finalized checkpoint by epoch; the last one is current
+    checkpoints: Table<Checkpoint>,
+    schedules: Table<ProposerSchedule>,
+    /// Current validator registry by public key
+    validators: Table<ValidatorRecord>,
+    /// Registry as of the start of each epoch
+    validator_snapshots: Table<Vec<ValidatorRecord>>,
+}
+
+impl ChainStore {
+    /// Open the chain tables in `store`
+    pub fn open(store: &Store) -> Result<Self> {
+        Ok(Self {
+            store: store.clone(),
+            blocks: store.table(BLOCKS_TABLE)?,
+            block_index: store.table(BLOCK_INDEX_TABLE)?,
+            attestations: store.table(ATTESTATIONS_TABLE)?,
+            checkpoints: store.table(CHECKPOINTS_TABLE)?,
+            schedules: store.table(SCHEDULES_TABLE)?,
+            validators: store.table(VALIDATORS_TABLE)?,
+            validator_snapshots: store.table(VALIDATOR_SNAPSHOTS_TABLE)?,
+        })
+    }
+
+    /// Store `block` as canonical at its height, with the attestations it
+    /// carries, in one atomic write
+    pub fn import_block(&self, block: &Block, attestations: &[Attestation]) -> Result<()> {
+        let header = &block.header;
+        let mut batch = WriteBatch::default();
+        batch.insert(&self.blocks, parse_hex(&header.hash, HASH_LEN)?, block)?;
+        batch.insert(&self.block_index, index_key(header.layer, header.height), &header.hash)?;
+        for attestation in attestations {
+            batch.insert(&self.attestations, attestation_key(attestation)?, attestation)?;
+        }
+        self.store.commit(batch)
+    }
+
+    /// Store `block` and index it by height on its layer
+    #[cfg(test)]
+    pub fn insert_block(&self, block: &Block) -> Result<()> {
+        self.import_block(block, &[])
+    }
+
+    /// Block with `hash`, on any layer
+    pub fn block_by_hash(&self, hash: &str) -> Result<Option<Block>> {
+        self.blocks.get(parse_hex(hash, HASH_LEN)?)
+    }
+
+    /// Block at `height` on `layer`
+    pub fn block_by_height(&self, layer: Layer, height: u64) -> Result<Option<Block>> {
+        match self.block_index.get(index_key(layer, height))? {
+            Some(hash) => self.block_by_hash(&hash),
+            None => Ok(None),
+        }
+    }
+
+    /// Highest block on `layer`
+    pub fn latest_block(&self, layer: Layer) -> Result<Option<Block>> {
+        match self.block_index.last_with_prefix([layer_byte(layer)])? {
+            Some(hash) => self.block_by_hash(&hash),
+            None => Ok(None),
+        }
+    }
+
+    /// Store `attestation`, replacing an earlier one by the same validator
+    pub fn insert_attestation(&self, attestation: &Attestation) -> Result<()> {
+        self.attestations.insert(attestation_key(attestation)?, attestation)
+    }
+
+    /// All attestations for the block with `hash`
+    pub fn attestations_for_block(&self, hash: &str) -> Result<Vec<Attestation>> {
+        self.attestations.values_with_prefix(parse_hex(hash, HASH_LEN)?)
+    }
+
+    /// Record a newly finalized checkpoint
+    pub fn set_finalized_checkpoint(&self, checkpoint: &Checkpoint) -> Result<()> {
+        self.checkpoints.insert(checkpoint.epoch.to_be_bytes(), checkpoint)
+    }
+
+    /// Latest finalized checkpoint
+    pub fn finalized_checkpoint(&self) -> Result<Option<Checkpoint>> {
+        self.checkpoints.last()
+    }
+
+    /// Checkpoint finalized for `epoch`
+    pub fn checkpoint_at(&self, epoch: u64) -> Result<Option<Checkpoint>> {
+        self.checkpoints.get(epoch.to_be_bytes())
+    }
+
+    /// Store the proposer schedule of `schedule.epoch`, replacing any earlier
+    /// one. TODO: Sync schedules and the registry from the security layer;
+    /// until then only tests write them and the RPC reports them unavailable.
+    #[cfg(test)]
+    pub fn insert_proposer_schedule(&self, schedule: &ProposerSchedule) -> Result<()> {
+        self.schedules.insert(schedule.epoch.to_be_bytes(), schedule)
+    }
+
+    /// Proposer schedule of `epoch`
+    pub fn proposer_schedule(&self, epoch: u64) -> Result<Option<ProposerSchedule>> {
+        self.schedules.get(epoch.to_be_bytes())
+    }
+
+    /// Add or update `validator` in the current registry, without
+    /// snapshotting it
+    #[cfg(test)]
+    pub fn insert_validator(&self, validator: &ValidatorRecord) -> Result<()> {
+        self.validators.insert(parse_hex(&validator.pubkey, PUBKEY_LEN)?, validator)
+    }
+
+    /// Current registry entry of the validator with `pubkey`
+    pub fn validator_by_pubkey(&self, pubkey: &str) -> Result<Option<ValidatorRecord>> {
+        self.validators.get(parse_hex(pubkey, PUBKEY_LEN)?)
+    }
+
+    /// Replace the registry with `validators` and keep a snapshot of it as
+    /// of `epoch`, atomically
+    #[cfg(test)]
+    pub fn snapshot_validators(&self, epoch: u64, validators: &[ValidatorRecord]) -> Result<()> {
+        let mut batch = WriteBatch::default();
+        for validator in validators {
+            batch.insert(&self.validators, parse_hex(&validator.pubkey, PUBKEY_LEN)?, validator)?;
+        }
+        batch.insert(&self.validator_snapshots, epoch.to_be_bytes(), &validators.to_vec())?;
+        self.store.commit(batch)
+    }
+
+    /// Registry as snapshotted at `epoch`
+    pub fn validator_snapshot(&self, epoch: u64) -> Result<Option<Vec<ValidatorRecord>>> {
+        self.validator_snapshots.get(epoch.to_be_bytes())
+    }
+
+    /// Highest commerce block no later than the block of the latest
+    /// finalized security checkpoint, by timestamp. Security epochs and
+    /// commerce epochs advance at different rates, so this is what commerce
+    /// finality is measured against.
+    pub fn finalized_commerce_block(&self) -> Result<Option<Block>> {
+        let Some(checkpoint) = self.finalized_checkpoint()? else {
+            return Ok(None);
+        };
+        let Some(finalized) = self.block_by_hash(&checkpoint.block_hash)? else {
+            return Ok(None);
+        };
+        let Some(oldest) = self.oldest_height(Layer::Commerce)? else {
+            return Ok(None);
+        };
+        let Some(latest) = self.latest_block(Layer::Commerce)? else {
+            return Ok(None);
+        };
+
+        // Timestamps grow with height; find the last one not after finality
+        let (mut low, mut high) = (oldest, latest.header.height);
+        let mut found = None;
+        while low <= high {
+            let mid = low + (high - low) / 2;
+            match self.block_by_height(Layer::Commerce, mid)? {
+                Some(block) if block.header.timestamp <= finalized.header.timestamp => {
+                    low = mid + 1;
+                    found = Some(block);
+                }
+                // A gap in the index is treated as not finalized
+                _ => match mid.checked_sub(1) {
+                    Some(below) => high = below,
+                    None => break,
+                },
+            }
+        }
+        Ok(found)
+    }
+
+    /// Lowest height still indexed on `layer`
+    pub fn oldest_height(&self, layer: Layer) -> Result<Option<u64>> {
+        match self.block_index.keys_with_prefix([layer_byte(layer)])?.first() {
+            Some(key) => Ok(parse_index_key(key).map(|(_, height)| height)),
+            None => Ok(None),
+        }
+    }
+
+    /// Delete up to `limit` of the oldest commerce blocks from commerce
+    /// epochs before `before_epoch`, with their index entries and attestations, in one
+    /// atomic write; returns how many blocks were deleted
+    pub fn prune_commerce(&self, before_epoch: u64, limit: usize) -> Result<usize> {
+        let mut batch = WriteBatch::default();
+        let mut pruned = 0;
+
+        for entry in self.block_index.iter_prefix([layer_byte(Layer::Commerce)]) {
+            if pruned == limit {
+                break;
+            }
+            let (key, hash) = entry?;
+            let hash_bytes = parse_hex(&hash, HASH_LEN)?;
+            if let Some(block) = self.blocks.get(&hash_bytes)? {
+                // Heights are indexed in order, so epochs only grow from here
+                if block.header.epoch >= before_epoch {
+                    break;
+                }
+                batch.remove(&self.blocks, &hash_bytes);
+            }
+            for attestation in self.attestations.keys_with_prefix(&hash_bytes)? {
+                batch.remove(&self.attestations, attestation);
+            }
+            batch.remove(&self.block_index, key);
+            pruned += 1;
+        }
+
+        self.store.commit(batch)?;
+        Ok(pruned)
+    }
+
+    /// Bytes the node database occupies on disk
+    pub fn size_on_disk(&self) -> Result<u64> {
+        self.store.size_on_disk()
+    }
+
+    /// Check that the height index and block bodies agree: every index entry
+    /// names a stored block at that layer and height, and every stored block
+    /// has a canonical block indexed at its height
+    pub fn verify(&self) -> Result<Vec<String>> {
+        let mut problems = Vec::new();
+
+        for entry in self.block_index.iter() {
+            let (key, hash) = entry?;
+            let Some((layer, height)) = parse_index_key(&key) else {
+                problems.push(format!("Malformed block index key {}", hex::encode(&key)));
+                continue;
+            };
+            match self.block_by_hash(&hash) {
+                Ok(Some(block)) if block.header.layer == layer && block.header.height == height => {}
+                Ok(Some(block)) => problems.push(format!(
+                    "Index entry {:?} {} points to block {} at {:?} {}",
+                    layer, height, hash, block.header.layer, block.header.height
+                )),
+                Ok(None) => problems.push(format!(
+                    "Index entry {:?} {} points to missing block {}",
+                    layer, height, hash
+                )),
+                Err(e) => problems.push(format!("Index entry {:?} {} is unreadable: {}", layer, height, e)),
+            }
+        }
+
+        for entry in self.blocks.iter() {
+            let header = entry?.1.header;
+            if self.block_index.get(index_key(header.layer, header.height))?.is_none() {
+                problems.push(format!(
+                    "Block {} at {:?} {} has no canonical index entry",
+                    header.hash, header.layer, header.height
+                ));
+            }
+        }
+
+        Ok(problems)
+    }
+}
+
+/// Decode a `0x`-prefixed hex string of exactly `len` bytes
+pub fn parse_hex(value: &str, len: usize) -> Result<Vec<u8>> {
+    let digits = value.strip_prefix("0x").unwrap_or(value);
+    let bytes = hex::decode(digits).with_context(|| format!("Invalid hex string {:?}", value))?;
+    if bytes.len() != len {
+        return Err(anyhow::anyhow!(
+            "Expected {} bytes, got {} in {:?}",
+            len,
+            bytes.len(),
+            value
+        ));
+    }
+    Ok(bytes)
+}
+
+fn layer_byte(layer: Layer) -> u8 {
+    match layer {
+        Layer::Commerce => 0,
+        Layer::Security => 1,
+    }
+}
+
+/// Big-endian height keeps the index ordered by height within a layer
+fn index_key(layer: Layer, height: u64) -> [u8; 9] {
+    let mut key = [0; 9];
+    key[0] = layer_byte(layer);
+    key[1..].copy_from_slice(&height.to_be_bytes());
+    key
+}
+
+fn parse_index_key(key: &[u8]) -> Option<(Layer, u64)> {
+    let layer = match key.first()? {
+        0 => Layer::Commerce,
+        1 => Layer::Security,
+        _ => return None,
+    };
+    let height = u64::from_be_bytes(key.get(1..)?.try_into().ok()?);
+    Some((layer, height))
+}
+
+fn attestation_key(attestation: &Attestation) -> Result<Vec<u8>> {
+    let mut key = parse_hex(&attestation.block_hash, HASH_LEN)?;
+    key.extend(parse_hex(&attestation.validator, PUBKEY_LEN)?);
+    Ok(key)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn hash(n: u8) -> String {
+        format!("0x{}", hex::encode([n; HASH_LEN]))
+    }
+
+    pub(crate) fn pubkey(n: u8) -> String {
+        format!("0x{}", hex::encode([n; PUBKEY_LEN]))
+    }
+
+    pub(crate) fn block(layer: Layer, height: u64, n: u8) -> Block {
+        Block {
+            header: BlockHeader {
+                layer,
+                height,
+                hash: hash(n),
+                parent_hash: hash(n.wrapping_sub(1)),
+                epoch: height / 32,
+                slot: height,
+                proposer: pubkey(1),
+                state_root: hash(0xff),
+                timestamp: 1_700_000_000 + height as i64,
+            },
+            transactions: vec![hash(0xee)],
+        }
+    }
+
+    #[test]
+    fn test_block_index() {
+        let store = ChainStore::open(&Store::temporary()).unwrap();
+        assert!(store.latest_block(Layer::Commerce).unwrap().is_none());
+
+        store.insert_block(&block(Layer::Commerce, 1, 1)).unwrap();
+        store.insert_block(&block(Layer::Commerce, 256, 2)).unwrap();
+        store.insert_block(&block(Layer::Security, 1, 3)).unwrap();
+
+        assert_eq!(store.latest_block(Layer::Commerce).unwrap().unwrap().header.height, 256);
+        assert_eq!(store.latest_block(Layer::Security).unwrap().unwrap().header.hash, hash(3));
+        assert_eq!(store.block_by_height(Layer::Security, 1).unwrap().unwrap().header.hash, hash(3));
+        assert!(store.block_by_height(Layer::Security, 256).unwrap().is_none());
+        assert_eq!(store.block_by_hash(&hash(2)).unwrap().unwrap().header.height, 256);
+        assert!(store.block_by_hash("0x1234").is_err());
+
+        for validator in [pubkey(1), pubkey(2)] {
+            store
+                .insert_attestation(&Attestation {
+                    block_hash: hash(2),
+                    validator,
+                    epoch: 8,
+                    signature: "0x00".to_string(),
+                })
+                .unwrap();
+        }
+        assert_eq!(store.attestations_for_block(&hash(2)).unwrap().len(), 2);
+        assert!(store.attestations_for_block(&hash(1)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_verify() {
+        let store = ChainStore::open(&Store::temporary()).unwrap();
+        store.insert_block(&block(Layer::Commerce, 1, 1)).unwrap();
+        store.insert_block(&block(Layer::Commerce, 2, 2)).unwrap();
+        assert!(store.verify().unwrap().is_empty());
+
+        // An index entry without its body, and a body without an index entry
+        store.blocks.remove(parse_hex(&hash(2), HASH_LEN).unwrap()).unwrap();
+        store.blocks.insert(parse_hex(&hash(3), HASH_LEN).unwrap(), &block(Layer::Security, 9, 3)).unwrap();
+        let problems = store.verify().unwrap();
+        assert_eq!(problems.len(), 2, "{:?}", problems);
+        assert!(problems[0].contains("missing block"));
+        assert!(problems[1].contains("no canonical index entry"));
+    }
+}
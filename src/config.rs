@@ -1,11 +1,29 @@
 //! Validator configuration management
 
 use anyhow::{Result, Context};
+use libp2p::{multiaddr::Protocol, Multiaddr, PeerId};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
+mod amount_serde;
+mod layers;
+mod networks;
+mod validation;
+
+pub use layers::{data_dir, CliOverride, ConfigSource};
+pub use networks::NetworkSpec;
+
+/// Infrastructure services, by the names `init --services` accepts
+pub const SERVICES: [&str; 4] = ["oon", "omp", "orc20-relayer", "paymaster"];
+
+/// Key file of an infrastructure service, relative to `keys/`
+fn service_key_file(name: &str) -> String {
+    format!("{}.key", name.replace('-', "_"))
+}
+
 /// Validator node configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidatorConfig {
@@ -35,6 +53,21 @@ pub struct ValidatorConfig {
     
     /// OEC-4337 paymaster settings
     pub paymaster: PaymasterConfig,
+
+    /// Readiness thresholds for the health endpoints
+    #[serde(default)]
+    pub health: HealthConfig,
+
+    /// Prometheus metrics endpoint
+    #[serde(default)]
+    pub metrics: MetricsConfig,
+
+    /// History retention of the node database
+    #[serde(default)]
+    pub storage: StorageConfig,
+    /// Log output
+    #[serde(default)]
+    pub logging: LoggingConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -76,6 +109,60 @@ pub struct P2PConfig {
     pub enable_mdns: bool,
     /// Enable Kademlia DHT
     pub enable_kad: bool,
+    /// Enable the TCP transport
+    #[serde(default = "default_true")]
+    pub enable_tcp: bool,
+    /// Enable the QUIC transport
+    #[serde(default = "default_true")]
+    pub enable_quic: bool,
+    /// QUIC (UDP) listening port, defaults to the P2P port
+    #[serde(default)]
+    pub quic_port: Option<u16>,
+    /// Externally reachable addresses to announce to peers
+    #[serde(default)]
+    pub external_addresses: Vec<String>,
+    /// Enable AutoNAT reachability probing
+    #[serde(default = "default_true")]
+    pub enable_autonat: bool,
+    /// Circuit relay v2 nodes (with `/p2p/<peer id>`) to reserve a slot on when not publicly reachable
+    #[serde(default)]
+    pub relay_addresses: Vec<String>,
+    /// Serve as a circuit relay v2 for peers behind NAT
+    #[serde(default)]
+    pub enable_relay_server: bool,
+    /// Sentry node topology
+    #[serde(default)]
+    pub sentry: SentryConfig,
+}
+
+/// Sentry node topology settings
+///
+/// A private validator only peers with its trusted sentries, which relay
+/// gossip to and from it and never advertise its address.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SentryConfig {
+    /// Role of this node in the sentry topology
+    #[serde(default)]
+    pub mode: SentryMode,
+    /// Trusted sentry addresses ending in `/p2p/<peer id>` (validator mode)
+    #[serde(default)]
+    pub sentry_addresses: Vec<String>,
+    /// Peer IDs of the private validators behind this sentry (sentry mode)
+    #[serde(default)]
+    pub private_peer_ids: Vec<String>,
+}
+
+/// Role of a node in a sentry topology
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SentryMode {
+    /// Regular node with open peering
+    #[default]
+    Disabled,
+    /// Private validator reachable only through its sentries
+    Validator,
+    /// Public node shielding one or more private validators
+    Sentry,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -90,6 +177,140 @@ pub struct RpcConfig {
     pub enable_ws: bool,
     /// Maximum number of concurrent RPC connections
     pub max_connections: usize,
+    /// Maximum HTTP request body size in bytes
+    #[serde(default = "default_max_request_body_size")]
+    pub max_request_body_size: usize,
+    /// Maximum number of calls in one JSON-RPC batch
+    #[serde(default = "default_max_batch_size")]
+    pub max_batch_size: usize,
+    /// Serve JSON-RPC on a Unix socket under `data_dir`
+    #[serde(default = "default_true")]
+    pub enable_ipc: bool,
+    /// IPC socket path, defaults to `<data_dir>/omne-nexus.ipc`
+    #[serde(default)]
+    pub ipc_path: Option<PathBuf>,
+    /// Allow authenticated `admin_*` calls over HTTP and WebSocket
+    /// (always allowed over IPC)
+    #[serde(default = "default_true")]
+    pub enable_admin_http: bool,
+    /// Hex-encoded HS256 JWT secret, defaults to `<data_dir>/jwt.hex`
+    #[serde(default)]
+    pub jwt_secret_path: Option<PathBuf>,
+    /// Static bearer token also accepted for admin calls
+    #[serde(default)]
+    pub admin_token: Option<String>,
+    /// Requests per second allowed from one IP over HTTP and WebSocket;
+    /// 0 disables rate limiting
+    #[serde(default = "default_rate_limit_per_second")]
+    pub rate_limit_per_second: u32,
+    /// Requests one IP may make in a burst above the steady rate
+    #[serde(default = "default_rate_limit_burst")]
+    pub rate_limit_burst: u32,
+    /// Browser origins allowed to call the API (`"*"` for any); requests
+    /// from other origins are rejected
+    #[serde(default)]
+    pub cors_allowed_origins: Vec<String>,
+    /// Accepted `Host` header values (`"*"` for any), guarding against DNS
+    /// rebinding; add the public hostname when exposing the API
+    #[serde(default = "default_allowed_hosts")]
+    pub allowed_hosts: Vec<String>,
+}
+
+/// Thresholds of the readiness checks behind `/health/ready` and `health`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthConfig {
+    /// Connected peers required to be ready
+    #[serde(default = "default_min_peers")]
+    pub min_peers: usize,
+    /// Consecutive slots a validator may miss before it is not ready
+    #[serde(default = "default_max_missed_slots")]
+    pub max_consecutive_missed_slots: u64,
+    /// Blocks behind the best known head that still count as synced
+    #[serde(default = "default_sync_tolerance_blocks")]
+    pub sync_tolerance_blocks: u64,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            min_peers: default_min_peers(),
+            max_consecutive_missed_slots: default_max_missed_slots(),
+            sync_tolerance_blocks: default_sync_tolerance_blocks(),
+        }
+    }
+}
+
+/// Prometheus `/metrics` endpoint, served on its own port
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsConfig {
+    /// Serve metrics
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Bind address for the metrics server
+    #[serde(default = "default_metrics_bind_address")]
+    pub bind_address: String,
+    /// Metrics server listening port
+    #[serde(default = "default_metrics_port")]
+    pub port: u16,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            bind_address: default_metrics_bind_address(),
+            port: default_metrics_port(),
+        }
+    }
+}
+
+/// Log output configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LoggingConfig {
+    /// `tracing` filter directives, e.g. `"info,libp2p=warn"`; unset keeps
+    /// the default, or the debug filter with `--verbose`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub level: Option<String>,
+}
+
+/// How much chain history the node keeps
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageConfig {
+    /// Keep everything, or prune behind finality
+    #[serde(default)]
+    pub pruning: PruningMode,
+    /// Epochs of commerce history kept before the finalized checkpoint
+    #[serde(default = "default_retention_epochs")]
+    pub retention_epochs: u64,
+    /// Seconds between pruning passes
+    #[serde(default = "default_prune_interval")]
+    pub prune_interval_secs: u64,
+    /// Blocks deleted per write, bounding the work done at once
+    #[serde(default = "default_prune_batch_size")]
+    pub prune_batch_size: usize,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            pruning: PruningMode::default(),
+            retention_epochs: default_retention_epochs(),
+            prune_interval_secs: default_prune_interval(),
+            prune_batch_size: default_prune_batch_size(),
+        }
+    }
+}
+
+/// History retention mode
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PruningMode {
+    /// Keep the full history
+    #[default]
+    Archive,
+    /// Delete commerce blocks and their attestations once they fall out of
+    /// the retention window behind the finalized checkpoint
+    Pruned,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -142,6 +363,7 @@ pub struct OMPConfig {
     /// Maximum storage allocation per validator (GB)
     pub max_storage_gb: u64,
     /// Pricing per GB per month (in quar)
+    #[serde(with = "amount_serde")]
     pub pricing_per_gb_quar: u128,
     /// IPFS node configuration
     pub ipfs_config: IPFSConfig,
@@ -157,6 +379,7 @@ pub struct ORC20RelayerConfig {
     /// Gas price multiplier for relaying (1.1 = 10% markup)
     pub gas_price_multiplier: f64,
     /// Minimum balance threshold for relaying (in OMC)
+    #[serde(with = "amount_serde")]
     pub min_balance_threshold: u128,
     /// Supported token contracts for relaying
     pub supported_tokens: Vec<String>,
@@ -168,6 +391,7 @@ pub struct PaymasterConfig {
     /// Enable OEC-4337 paymaster services
     pub enable_paymaster: bool,
     /// Sponsorship budget per day (in OMC)
+    #[serde(with = "amount_serde")]
     pub daily_sponsorship_budget: u128,
     /// Sponsorship policies to apply
     pub sponsorship_policies: Vec<String>,
@@ -203,6 +427,68 @@ pub struct PinningServiceConfig {
     pub priority: u8,
 }
 
+/// IPC socket file name under `data_dir`
+const DEFAULT_IPC_FILE: &str = "omne-nexus.ipc";
+
+/// Admin RPC JWT secret file name under `data_dir`
+const DEFAULT_JWT_SECRET_FILE: &str = "jwt.hex";
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_max_request_body_size() -> usize {
+    5 * 1024 * 1024
+}
+
+fn default_max_batch_size() -> usize {
+    100
+}
+
+fn default_rate_limit_per_second() -> u32 {
+    50
+}
+
+fn default_rate_limit_burst() -> u32 {
+    100
+}
+
+fn default_min_peers() -> usize {
+    1
+}
+
+fn default_max_missed_slots() -> u64 {
+    3
+}
+
+fn default_sync_tolerance_blocks() -> u64 {
+    2
+}
+
+fn default_metrics_bind_address() -> String {
+    "127.0.0.1".to_string()
+}
+
+fn default_metrics_port() -> u16 {
+    9615
+}
+
+fn default_retention_epochs() -> u64 {
+    1024
+}
+
+fn default_prune_interval() -> u64 {
+    60
+}
+
+fn default_prune_batch_size() -> usize {
+    256
+}
+
+fn default_allowed_hosts() -> Vec<String> {
+    vec!["localhost".to_string(), "127.0.0.1".to_string(), "::1".to_string()]
+}
+
 mod duration_serde {
     use serde::{Deserialize, Deserializer, Serializer};
     use std::time::Duration;
@@ -224,68 +510,31 @@ mod duration_serde {
 }
 
 impl ValidatorConfig {
-    /// Create a new configuration for the specified network
+    /// Create a new configuration for the specified built-in network
     pub fn new_for_network(network_name: &str) -> Result<Self> {
-        let (network_id, genesis_hash, chain_spec, bootstrap_peers) = match network_name {
-            "mainnet" => (
-                1,
-                "0x0000000000000000000000000000000000000000000000000000000000000000".to_string(),
-                ChainSpec {
-                    commerce_block_time: 3,
-                    security_block_time: 540, // 9 minutes
-                    min_validator_stake: 20,
-                    max_validators: 1000,
-                },
-                vec![
-                    "/dns4/mainnet-boot1.omne.network/tcp/30303".to_string(),
-                    "/dns4/mainnet-boot2.omne.network/tcp/30303".to_string(),
-                ]
-            ),
-            "testnet" => (
-                2,
-                "0x1111111111111111111111111111111111111111111111111111111111111111".to_string(),
-                ChainSpec {
-                    commerce_block_time: 3,
-                    security_block_time: 540,
-                    min_validator_stake: 10, // Lower for testing
-                    max_validators: 100,
-                },
-                vec![
-                    "/dns4/testnet-boot1.omne.network/tcp/30303".to_string(),
-                    "/dns4/testnet-boot2.omne.network/tcp/30303".to_string(),
-                ]
-            ),
-            "devnet" => (
-                3,
-                "0x2222222222222222222222222222222222222222222222222222222222222222".to_string(),
-                ChainSpec {
-                    commerce_block_time: 3,
-                    security_block_time: 60, // 1 minute for faster testing
-                    min_validator_stake: 1,
-                    max_validators: 10,
-                },
-                vec![
-                    "/ip4/127.0.0.1/tcp/30303".to_string(),
-                ]
-            ),
-            _ => return Err(anyhow::anyhow!("Unknown network: {}", network_name)),
-        };
-
-        Ok(Self {
+        Ok(Self::from_spec(&NetworkSpec::builtin(network_name)?))
+    }
+
+    /// Create a new configuration for the network described by `spec`
+    pub fn from_spec(spec: &NetworkSpec) -> Self {
+        Self {
             data_dir: PathBuf::from("~/.omne-nexus"),
-            network: NetworkConfig {
-                name: network_name.to_string(),
-                id: network_id,
-                genesis_hash,
-                chain_spec,
-            },
+            network: spec.network_config(),
             p2p: P2PConfig {
                 port: 30303,
                 max_peers: 50,
-                bootstrap_peers,
+                bootstrap_peers: spec.bootstrap_peers.clone(),
                 connection_timeout: Duration::from_secs(10),
-                enable_mdns: network_name == "devnet",
+                enable_mdns: spec.name == "devnet",
                 enable_kad: true,
+                enable_tcp: true,
+                enable_quic: true,
+                quic_port: None,
+                external_addresses: vec![],
+                enable_autonat: true,
+                relay_addresses: vec![],
+                enable_relay_server: false,
+                sentry: SentryConfig::default(),
             },
             rpc: RpcConfig {
                 port: 9944,
@@ -293,6 +542,17 @@ impl ValidatorConfig {
                 enable_http: true,
                 enable_ws: true,
                 max_connections: 100,
+                max_request_body_size: default_max_request_body_size(),
+                max_batch_size: default_max_batch_size(),
+                enable_ipc: true,
+                ipc_path: None,
+                enable_admin_http: true,
+                jwt_secret_path: None,
+                admin_token: None,
+                rate_limit_per_second: default_rate_limit_per_second(),
+                rate_limit_burst: default_rate_limit_burst(),
+                cors_allowed_origins: Vec::new(),
+                allowed_hosts: default_allowed_hosts(),
             },
             validator: ValidatorSettings {
                 is_validator: false,
@@ -346,7 +606,15 @@ impl ValidatorConfig {
                 max_gas_per_tx: 500_000,
                 max_tx_per_user_per_hour: 10,
             },
-        })
+            health: HealthConfig {
+                // A lone devnet node has nobody to peer with
+                min_peers: if spec.name == "devnet" { 0 } else { default_min_peers() },
+                ..HealthConfig::default()
+            },
+            metrics: MetricsConfig::default(),
+            storage: StorageConfig::default(),
+            logging: LoggingConfig::default(),
+        }
     }
 
     /// Initialize validator directories
@@ -359,29 +627,76 @@ impl ValidatorConfig {
     }
 
     /// Generate validator keys
-    pub fn generate_validator_keys(&self, data_dir: &PathBuf) -> Result<()> {
+    pub fn generate_validator_keys(&self, data_dir: &Path) -> Result<()> {
         let keys_dir = data_dir.join("keys");
         
         // TODO: Generate actual cryptographic keys
         // For now, create placeholder files
         fs::write(keys_dir.join("validator.key"), "placeholder_validator_key")?;
-        fs::write(keys_dir.join("network.key"), "placeholder_network_key")?;
         fs::write(keys_dir.join("oon.key"), "placeholder_oon_key")?;
         
-        // Infrastructure service keys
-        if self.omp.enable_omp {
-            fs::write(keys_dir.join("omp.key"), "placeholder_omp_key")?;
-        }
-        if self.orc20_relayer.enable_relayer {
-            fs::write(keys_dir.join("orc20_relayer.key"), "placeholder_orc20_relayer_key")?;
+        self.generate_service_keys(data_dir)?;
+        Ok(())
+    }
+
+    /// Create keys for the enabled infrastructure services, keeping any
+    /// that already exist; returns the paths of the keys created
+    pub fn generate_service_keys(&self, data_dir: &Path) -> Result<Vec<PathBuf>> {
+        let mut created = Vec::new();
+        for service in self.enabled_services() {
+            let path = data_dir.join("keys").join(service_key_file(service));
+            if !path.exists() {
+                fs::create_dir_all(data_dir.join("keys"))?;
+                // TODO: Generate actual cryptographic keys
+                fs::write(&path, format!("placeholder_{}_key", service.replace('-', "_")))?;
+                created.push(path);
+            }
         }
-        if self.paymaster.enable_paymaster {
-            fs::write(keys_dir.join("paymaster.key"), "placeholder_paymaster_key")?;
+        Ok(created)
+    }
+
+    /// Enable the infrastructure service called `name`
+    pub fn enable_service(&mut self, name: &str) -> Result<()> {
+        match name {
+            "oon" => self.oon.enable_oon = true,
+            "omp" => self.omp.enable_omp = true,
+            "orc20-relayer" => self.orc20_relayer.enable_relayer = true,
+            "paymaster" => self.paymaster.enable_paymaster = true,
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Unknown service: {} (expected one of {})",
+                    name,
+                    SERVICES.join(", ")
+                ))
+            }
         }
-        
         Ok(())
     }
 
+    /// Names of the enabled infrastructure services
+    pub fn enabled_services(&self) -> Vec<&'static str> {
+        let enabled = [
+            self.oon.enable_oon,
+            self.omp.enable_omp,
+            self.orc20_relayer.enable_relayer,
+            self.paymaster.enable_paymaster,
+        ];
+        SERVICES.into_iter().zip(enabled).filter(|(_, on)| *on).map(|(name, _)| name).collect()
+    }
+
+    /// Path of the Ed25519 key that gives the node its peer ID
+    pub fn network_key_path(&self) -> PathBuf {
+        self.validator
+            .network_key_path
+            .clone()
+            .unwrap_or_else(|| self.data_dir.join("keys").join("network.key"))
+    }
+
+    /// Path of the key used by the infrastructure service called `name`
+    pub fn service_key_path(&self, name: &str) -> PathBuf {
+        self.data_dir.join("keys").join(service_key_file(name))
+    }
+
     /// Save configuration to file
     pub fn save_to_file(&self, path: &PathBuf) -> Result<()> {
         let toml_content = toml::to_string_pretty(self)
@@ -400,3 +715,119 @@ impl ValidatorConfig {
         Ok(config)
     }
 }
+
+impl RpcConfig {
+    /// Resolved IPC socket path for a node using `data_dir`
+    pub fn ipc_socket_path(&self, data_dir: &Path) -> PathBuf {
+        self.ipc_path
+            .clone()
+            .unwrap_or_else(|| data_dir.join(DEFAULT_IPC_FILE))
+    }
+
+    /// Resolved JWT secret path for a node using `data_dir`
+    pub fn jwt_secret_path(&self, data_dir: &Path) -> PathBuf {
+        self.jwt_secret_path
+            .clone()
+            .unwrap_or_else(|| data_dir.join(DEFAULT_JWT_SECRET_FILE))
+    }
+}
+
+impl P2PConfig {
+    /// Whether this node is a private validator hidden behind sentries
+    pub fn is_private_validator(&self) -> bool {
+        self.sentry.mode == SentryMode::Validator
+    }
+
+    /// Validate the sentry topology settings
+    pub fn validate_sentry(&self) -> Result<()> {
+        match self.sentry.mode {
+            SentryMode::Disabled => {}
+            SentryMode::Validator => {
+                if self.sentry.sentry_addresses.is_empty() {
+                    return Err(anyhow::anyhow!(
+                        "p2p.sentry.sentry_addresses must list at least one sentry in validator mode"
+                    ));
+                }
+                if self.enable_relay_server {
+                    return Err(anyhow::anyhow!(
+                        "p2p.enable_relay_server cannot be used by a private validator"
+                    ));
+                }
+                self.sentry_peers()?;
+            }
+            SentryMode::Sentry => {
+                if self.sentry.private_peer_ids.is_empty() {
+                    return Err(anyhow::anyhow!(
+                        "p2p.sentry.private_peer_ids must list at least one validator in sentry mode"
+                    ));
+                }
+                self.private_peers()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Trusted sentries with their peer IDs (validator mode)
+    pub fn sentry_peers(&self) -> Result<Vec<(PeerId, Multiaddr)>> {
+        self.sentry
+            .sentry_addresses
+            .iter()
+            .map(|addr| {
+                let multiaddr: Multiaddr = addr
+                    .parse()
+                    .with_context(|| format!("Invalid sentry address: {}", addr))?;
+                let peer_id = multiaddr
+                    .iter()
+                    .find_map(|p| match p {
+                        Protocol::P2p(peer_id) => Some(peer_id),
+                        _ => None,
+                    })
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("Sentry address {} must end with /p2p/<peer id>", addr)
+                    })?;
+                Ok((peer_id, multiaddr))
+            })
+            .collect()
+    }
+
+    /// Private validators shielded by this sentry (sentry mode)
+    pub fn private_peers(&self) -> Result<Vec<PeerId>> {
+        self.sentry
+            .private_peer_ids
+            .iter()
+            .map(|id| {
+                id.parse::<PeerId>()
+                    .with_context(|| format!("Invalid private peer ID: {}", id))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_sentry() {
+        let peer_id = PeerId::random();
+        let mut p2p = ValidatorConfig::new_for_network("testnet").unwrap().p2p;
+        assert!(p2p.validate_sentry().is_ok());
+
+        p2p.sentry.mode = SentryMode::Validator;
+        assert!(p2p.validate_sentry().is_err());
+
+        p2p.sentry.sentry_addresses = vec!["/ip4/10.0.0.1/tcp/30303".to_string()];
+        assert!(p2p.validate_sentry().is_err());
+
+        p2p.sentry.sentry_addresses = vec![format!("/ip4/10.0.0.1/tcp/30303/p2p/{}", peer_id)];
+        assert_eq!(p2p.sentry_peers().unwrap()[0].0, peer_id);
+        assert!(p2p.validate_sentry().is_ok());
+
+        p2p.sentry.mode = SentryMode::Sentry;
+        p2p.sentry.private_peer_ids = vec!["not-a-peer-id".to_string()];
+        assert!(p2p.validate_sentry().is_err());
+
+        p2p.sentry.private_peer_ids = vec![peer_id.to_string()];
+        assert!(p2p.validate_sentry().is_ok());
+    }
+}
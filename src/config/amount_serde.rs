@@ -0,0 +1,52 @@
+//! Token amounts in the smallest unit, which can exceed TOML's 64-bit
+//! integers: written as an integer when they fit and as a decimal string
+//! otherwise; either form is read back
+
+use serde::{Deserialize, Deserializer, Serializer};
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Amount {
+    Integer(u64),
+    Decimal(String),
+}
+
+pub fn serialize<S>(amount: &u128, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match i64::try_from(*amount) {
+        Ok(amount) => serializer.serialize_i64(amount),
+        Err(_) => serializer.serialize_str(&amount.to_string()),
+    }
+}
+
+pub fn deserialize<'de, D>(deserializer: D) -> Result<u128, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Amount::deserialize(deserializer)? {
+        Amount::Integer(amount) => Ok(amount.into()),
+        Amount::Decimal(amount) => amount.parse().map_err(serde::de::Error::custom),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Price {
+        #[serde(with = "super")]
+        amount: u128,
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for (amount, written) in [(5u128, "amount = 5\n"), (u128::MAX, "amount = \"340282366920938463463374607431768211455\"\n")] {
+            let price = Price { amount };
+            assert_eq!(toml::to_string(&price).unwrap(), written);
+            assert_eq!(toml::from_str::<Price>(written).unwrap(), price);
+        }
+    }
+}
@@ -0,0 +1,401 @@
+//! Layered configuration
+//!
+//! The effective configuration is built from, in increasing precedence:
+//! the network's built-in defaults, `config.toml`, `OMNE_*` environment
+//! variables and command-line flags the user actually passed. A chain spec
+//! given with `--chain-spec` replaces the built-in network preset and counts
+//! as a flag for the network section and bootstrap peers. Layers are
+//! merged as TOML values, recording which layer set each field, and the
+//! result is deserialized once at the end.
+//!
+//! Environment variables name a field by its path, upper-cased, with `__`
+//! between sections: `OMNE_RPC__PORT`, `OMNE_P2P__BOOTSTRAP_PEERS`,
+//! `OMNE_DATA_DIR`. Lists are comma-separated. Setting `network.name` from
+//! the environment or a flag switches to that network's preset.
+
+use super::{NetworkSpec, ValidatorConfig};
+use crate::utils::expand_tilde;
+
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::Value;
+
+/// Prefix of configuration environment variables
+pub const ENV_PREFIX: &str = "OMNE_";
+
+/// Data directory used when neither a flag nor `OMNE_DATA_DIR` names one
+pub const DEFAULT_DATA_DIR: &str = "~/.omne-nexus";
+
+/// Network used when no layer names one
+const DEFAULT_NETWORK: &str = "testnet";
+
+const NETWORK_NAME: &str = "network.name";
+
+const CHAIN_SPEC_FLAG: &str = "--chain-spec";
+
+/// Layer an effective setting came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Default,
+    File(PathBuf),
+    Env(String),
+    Cli(String),
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::File(path) => write!(f, "{}", path.display()),
+            Source::Env(var) => write!(f, "env {}", var),
+            Source::Cli(flag) => write!(f, "flag {}", flag),
+        }
+    }
+}
+
+/// A field set by an explicitly passed command-line flag
+#[derive(Clone)]
+pub struct CliOverride {
+    path: String,
+    flag: &'static str,
+    value: Value,
+}
+
+impl CliOverride {
+    pub fn new(path: impl Into<String>, flag: &'static str, value: impl Into<Value>) -> Self {
+        Self {
+            path: path.into(),
+            flag,
+            value: value.into(),
+        }
+    }
+}
+
+/// Merged configuration with the source of every field
+pub struct EffectiveConfig {
+    pub config: ValidatorConfig,
+    sources: BTreeMap<String, Source>,
+}
+
+impl EffectiveConfig {
+    /// Merge the defaults, the file at `config_path` if it exists, the
+    /// `OMNE_*` variables in `env`, `chain_spec` and `overrides`
+    pub fn load(
+        config_path: &Path,
+        chain_spec: Option<&NetworkSpec>,
+        env: impl IntoIterator<Item = (String, String)>,
+        overrides: &[CliOverride],
+    ) -> Result<Self> {
+        let file = if config_path.exists() {
+            let content = fs::read_to_string(config_path)
+                .with_context(|| format!("Failed to read {}", config_path.display()))?;
+            let value: Value = content
+                .parse()
+                .with_context(|| format!("Failed to parse {}", config_path.display()))?;
+            Some(value)
+        } else {
+            None
+        };
+
+        let mut explicit: Vec<(String, Value, Source)> = env
+            .into_iter()
+            .filter_map(|(var, value)| {
+                let path = var.strip_prefix(ENV_PREFIX)?.to_lowercase().replace("__", ".");
+                Some((path, Value::String(value), Source::Env(var)))
+            })
+            .collect();
+        explicit.sort_by(|a, b| a.0.cmp(&b.0));
+        let env_count = explicit.len();
+        explicit.extend(
+            overrides
+                .iter()
+                .map(|o| (o.path.clone(), o.value.clone(), Source::Cli(o.flag.to_string()))),
+        );
+
+        // Later layers win, so the preset is that of the last network named.
+        // A custom network saved in config.toml has no preset; its file
+        // section supplies the network and the default preset the rest.
+        let preset = match chain_spec {
+            Some(spec) => spec.clone(),
+            None => {
+                let named = explicit
+                    .iter()
+                    .rev()
+                    .find(|(path, _, _)| path == NETWORK_NAME)
+                    .and_then(|(_, value, _)| value.as_str());
+                match named {
+                    Some(name) => NetworkSpec::builtin(name)?,
+                    None => file
+                        .as_ref()
+                        .and_then(|file| lookup(file, NETWORK_NAME)?.as_str())
+                        .and_then(|name| NetworkSpec::builtin(name).ok())
+                        .map_or_else(|| NetworkSpec::builtin(DEFAULT_NETWORK), Ok)?,
+                }
+            }
+        };
+
+        let mut merged = Merged {
+            value: Value::try_from(ValidatorConfig::from_spec(&preset))?,
+            sources: BTreeMap::new(),
+        };
+        if let Some(file) = &file {
+            merged.merge(file, "", &Source::File(config_path.to_path_buf()));
+        }
+        for (path, value, source) in &explicit[..env_count] {
+            merged.set(path, value.clone(), source)?;
+        }
+        if let Some(spec) = chain_spec {
+            let source = Source::Cli(CHAIN_SPEC_FLAG.to_string());
+            merged.merge(&Value::try_from(spec.network_config())?, "network", &source);
+            merged.merge(&Value::try_from(&spec.bootstrap_peers)?, "p2p.bootstrap_peers", &source);
+        }
+        for (path, value, source) in &explicit[env_count..] {
+            merged.set(path, value.clone(), source)?;
+        }
+
+        let mut config: ValidatorConfig = merged
+            .value
+            .clone()
+            .try_into()
+            .context("Invalid configuration after applying config.toml, environment and flags")?;
+        config.data_dir = expand_tilde(&config.data_dir);
+
+        // Names that deserialized into nothing are typos, not settings
+        let resolved = Value::try_from(&config)?;
+        for (path, _, source) in &explicit {
+            if lookup(&resolved, path).is_none() {
+                return Err(anyhow::anyhow!("{} does not name a configuration field ({})", source, path));
+            }
+        }
+
+        Ok(Self {
+            config,
+            sources: merged.sources,
+        })
+    }
+
+    /// Layer that set the field at dotted `path`
+    pub fn source(&self, path: &str) -> &Source {
+        self.sources.get(path).unwrap_or(&Source::Default)
+    }
+
+    /// The configuration as TOML, each field annotated with its source
+    pub fn render(&self) -> Result<String> {
+        let mut out = String::from(
+            "# Effective configuration: defaults < config.toml < OMNE_* environment < flags\n",
+        );
+        let value = Value::try_from(&self.config)?;
+        self.render_table(&mut out, &value, "")?;
+        Ok(out)
+    }
+
+    fn render_table(&self, out: &mut String, value: &Value, prefix: &str) -> Result<()> {
+        let Some(table) = value.as_table() else {
+            return Ok(());
+        };
+
+        let leaves: Vec<_> = table.iter().filter(|(_, v)| !v.is_table()).collect();
+        if !leaves.is_empty() && !prefix.is_empty() {
+            out.push_str(&format!("\n[{}]\n", prefix));
+        }
+        for (key, leaf) in leaves {
+            let path = join(prefix, key);
+            out.push_str(&format!("{} = {}  # {}\n", key, leaf, self.source(&path)));
+        }
+        for (key, child) in table.iter().filter(|(_, v)| v.is_table()) {
+            self.render_table(out, child, &join(prefix, key))?;
+        }
+        Ok(())
+    }
+}
+
+/// Everything the effective configuration is loaded from besides the
+/// environment, kept so a running node can load it again
+#[derive(Clone)]
+pub struct ConfigSource {
+    pub path: PathBuf,
+    pub chain_spec: Option<NetworkSpec>,
+    pub overrides: Vec<CliOverride>,
+}
+
+impl ConfigSource {
+    /// Merge the layers with the current `OMNE_*` environment
+    pub fn load(&self) -> Result<EffectiveConfig> {
+        EffectiveConfig::load(&self.path, self.chain_spec.as_ref(), std::env::vars(), &self.overrides)
+    }
+}
+
+/// Directory holding `config.toml`: the flag, else `OMNE_DATA_DIR`, else
+/// the default, with `~` expanded
+pub fn data_dir(flag: Option<&Path>) -> PathBuf {
+    let path = match flag {
+        Some(path) => path.to_path_buf(),
+        None => std::env::var_os(format!("{}DATA_DIR", ENV_PREFIX))
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_DATA_DIR)),
+    };
+    expand_tilde(&path)
+}
+
+/// Value tree being merged, with the source of each leaf
+struct Merged {
+    value: Value,
+    sources: BTreeMap<String, Source>,
+}
+
+impl Merged {
+    /// Overlay every leaf of `layer` below `prefix`
+    fn merge(&mut self, layer: &Value, prefix: &str, source: &Source) {
+        match layer.as_table() {
+            Some(table) => {
+                for (key, value) in table {
+                    self.merge(value, &join(prefix, key), source);
+                }
+            }
+            None => {
+                insert(&mut self.value, prefix, layer.clone());
+                self.sources.insert(prefix.to_string(), source.clone());
+            }
+        }
+    }
+
+    /// Set the field at `path` from a flag or an environment variable,
+    /// converting strings to the type of the value being replaced
+    fn set(&mut self, path: &str, value: Value, source: &Source) -> Result<()> {
+        if path == NETWORK_NAME {
+            let name = value.as_str().unwrap_or_default();
+            let preset = Value::try_from(NetworkSpec::builtin(name)?.network_config())?;
+            self.merge(&preset, "network", source);
+            return Ok(());
+        }
+
+        let value = match (value, lookup(&self.value, path)) {
+            (Value::String(raw), Some(current)) => {
+                convert(&raw, current).with_context(|| format!("Invalid value {:?} for {}", raw, source))?
+            }
+            (value, _) => value,
+        };
+        insert(&mut self.value, path, value);
+        self.sources.insert(path.to_string(), source.clone());
+        Ok(())
+    }
+}
+
+/// Parse `raw` as the same TOML type as `current`
+fn convert(raw: &str, current: &Value) -> Result<Value> {
+    Ok(match current {
+        Value::Integer(_) => match raw.parse::<i64>() {
+            Ok(value) => Value::Integer(value),
+            // Token amounts beyond i64 are stored as decimal strings
+            Err(_) => {
+                raw.parse::<u128>()?;
+                Value::String(raw.to_string())
+            }
+        },
+        Value::Float(_) => Value::Float(raw.parse()?),
+        Value::Boolean(_) => Value::Boolean(raw.parse()?),
+        Value::Array(items) => {
+            let item = items.first().cloned().unwrap_or(Value::String(String::new()));
+            Value::Array(
+                raw.split(',')
+                    .map(str::trim)
+                    .filter(|part| !part.is_empty())
+                    .map(|part| convert(part, &item))
+                    .collect::<Result<_>>()?,
+            )
+        }
+        _ => Value::String(raw.to_string()),
+    })
+}
+
+fn lookup<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(value, |value, key| value.get(key))
+}
+
+/// Set `path` in `root`, creating intermediate tables
+fn insert(root: &mut Value, path: &str, value: Value) {
+    let mut keys: Vec<&str> = path.split('.').collect();
+    let Some(last) = keys.pop() else {
+        return;
+    };
+    let mut table = root;
+    for key in keys {
+        let Some(map) = table.as_table_mut() else {
+            return;
+        };
+        table = map
+            .entry(key.to_string())
+            .or_insert_with(|| Value::Table(Default::default()));
+    }
+    if let Some(map) = table.as_table_mut() {
+        map.insert(last.to_string(), value);
+    }
+}
+
+fn join(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_precedence() {
+        let path = std::env::temp_dir().join(format!("omne-config-{}.toml", rand::random::<u32>()));
+        fs::write(&path, "[rpc]\nport = 9000\nrate_limit_burst = 10\n\n[validator]\nvalidator_stake = 500\n").unwrap();
+
+        let env = [
+            ("OMNE_RPC__RATE_LIMIT_BURST".to_string(), "20".to_string()),
+            ("OMNE_P2P__BOOTSTRAP_PEERS".to_string(), "/dns4/a/tcp/1, /dns4/b/tcp/2".to_string()),
+            ("OMNE_P2P__MAX_PEERS".to_string(), "7".to_string()),
+            ("HOME".to_string(), "/root".to_string()),
+        ];
+        let flags = [CliOverride::new("p2p.max_peers", "--max-peers", 9)];
+        let effective = EffectiveConfig::load(&path, None, env.clone(), &flags).unwrap();
+        let config = &effective.config;
+
+        assert_eq!(config.network.name, DEFAULT_NETWORK);
+        assert_eq!(config.rpc.port, 9000);
+        assert_eq!(config.validator.validator_stake, 500);
+        assert_eq!(config.rpc.rate_limit_burst, 20);
+        assert_eq!(config.p2p.bootstrap_peers, ["/dns4/a/tcp/1", "/dns4/b/tcp/2"]);
+        assert_eq!(config.p2p.max_peers, 9);
+        assert!(!config.data_dir.starts_with("~"));
+
+        assert_eq!(effective.source("rpc.port"), &Source::File(path.clone()));
+        assert_eq!(effective.source("rpc.rate_limit_burst"), &Source::Env("OMNE_RPC__RATE_LIMIT_BURST".into()));
+        assert_eq!(effective.source("p2p.max_peers"), &Source::Cli("--max-peers".into()));
+        assert_eq!(effective.source("rpc.bind_address"), &Source::Default);
+        assert!(effective.render().unwrap().contains("port = 9000  # "));
+
+        // A flag naming another network switches to its preset
+        let devnet = [CliOverride::new("network.name", "--network", "devnet")];
+        let config = EffectiveConfig::load(&path, None, env.clone(), &devnet).unwrap().config;
+        assert_eq!(config.network.id, ValidatorConfig::new_for_network("devnet").unwrap().network.id);
+
+        // A chain spec replaces the preset and outranks the environment
+        let spec: NetworkSpec = toml::from_str(
+            "name = \"private\"\nid = 77\ngenesis_hash = \"0xabcd\"\ncommerce_block_time = 2\n\
+             security_block_time = 120\nmin_validator_stake = 5\nmax_validators = 4\n\
+             bootstrap_peers = [\"/ip4/10.0.0.1/tcp/30303\"]\n",
+        )
+        .unwrap();
+        let effective = EffectiveConfig::load(&path, Some(&spec), env, &[]).unwrap();
+        assert_eq!(effective.config.network.id, 77);
+        assert_eq!(effective.config.network.chain_spec.min_validator_stake, 5);
+        assert_eq!(effective.config.p2p.bootstrap_peers, ["/ip4/10.0.0.1/tcp/30303"]);
+        assert_eq!(effective.source("network.genesis_hash"), &Source::Cli("--chain-spec".into()));
+
+        let typo = [("OMNE_RPC__PROT".to_string(), "1".to_string())];
+        assert!(EffectiveConfig::load(&path, None, typo, &[]).is_err());
+        let _ = fs::remove_file(path);
+    }
+}
@@ -0,0 +1,94 @@
+//! Network registry
+//!
+//! Built-in networks are defined in `config/networks.toml`, embedded at
+//! compile time, and are the only source of their parameters. Custom or
+//! private networks are described by a chain spec file with the same fields
+//! at the top level, passed with `--chain-spec`.
+
+use super::{ChainSpec, NetworkConfig};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Registry of built-in networks
+const BUILTIN_NETWORKS: &str = include_str!("../../config/networks.toml");
+
+/// Identity, parameters and entry points of one network
+#[derive(Debug, Clone, Deserialize)]
+pub struct NetworkSpec {
+    pub name: String,
+    pub id: u64,
+    pub genesis_hash: String,
+    pub commerce_block_time: u64,
+    pub security_block_time: u64,
+    pub min_validator_stake: u64,
+    pub max_validators: usize,
+    #[serde(default)]
+    pub bootstrap_peers: Vec<String>,
+}
+
+impl NetworkSpec {
+    /// Built-in network called `name`
+    pub fn builtin(name: &str) -> Result<Self> {
+        registry()?.remove(name).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Unknown network: {} (built-in networks: {}; use --chain-spec for others)",
+                name,
+                builtin_names().join(", ")
+            )
+        })
+    }
+
+    /// Custom network described by the chain spec file at `path`
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read chain spec {}", path.display()))?;
+        toml::from_str(&content).with_context(|| format!("Failed to parse chain spec {}", path.display()))
+    }
+
+    pub fn network_config(&self) -> NetworkConfig {
+        NetworkConfig {
+            name: self.name.clone(),
+            id: self.id,
+            genesis_hash: self.genesis_hash.clone(),
+            chain_spec: ChainSpec {
+                commerce_block_time: self.commerce_block_time,
+                security_block_time: self.security_block_time,
+                min_validator_stake: self.min_validator_stake,
+                max_validators: self.max_validators,
+            },
+        }
+    }
+}
+
+/// Names of the built-in networks
+pub fn builtin_names() -> Vec<String> {
+    registry().map(|networks| networks.into_keys().collect()).unwrap_or_default()
+}
+
+fn registry() -> Result<BTreeMap<String, NetworkSpec>> {
+    #[derive(Deserialize)]
+    struct Registry {
+        networks: BTreeMap<String, NetworkSpec>,
+    }
+
+    let registry: Registry = toml::from_str(BUILTIN_NETWORKS).context("Malformed built-in network registry")?;
+    Ok(registry.networks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry() {
+        assert_eq!(builtin_names(), ["devnet", "mainnet", "testnet"]);
+        for name in builtin_names() {
+            assert_eq!(NetworkSpec::builtin(&name).unwrap().name, name);
+        }
+        assert_eq!(NetworkSpec::builtin("mainnet").unwrap().min_validator_stake, 100);
+        assert!(NetworkSpec::builtin("nonet").unwrap_err().to_string().contains("--chain-spec"));
+    }
+}
@@ -0,0 +1,253 @@
+//! Semantic validation of `ValidatorConfig`
+//!
+//! Deserialization only checks types; these checks catch values that parse
+//! but would fail later, or silently misbehave, once the node runs. Every
+//! problem is reported with the dotted path of its field so one pass over
+//! the output fixes them all.
+
+use super::{PruningMode, ValidatorConfig};
+
+use anyhow::Result;
+use libp2p::Multiaddr;
+use std::fmt;
+use std::net::IpAddr;
+
+/// One invalid field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigProblem {
+    /// Dotted path of the field, e.g. `oon.resource_allocation`
+    pub field: String,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// Valid OMP storage tiers (1=on-chain, 2=IPFS pinned, 3=IPFS best-effort)
+const OMP_TIERS: std::ops::RangeInclusive<u8> = 1..=3;
+
+#[derive(Default)]
+struct Problems(Vec<ConfigProblem>);
+
+impl Problems {
+    fn push(&mut self, field: impl Into<String>, message: impl Into<String>) {
+        self.0.push(ConfigProblem {
+            field: field.into(),
+            message: message.into(),
+        });
+    }
+
+    fn check(&mut self, ok: bool, field: &str, message: impl FnOnce() -> String) {
+        if !ok {
+            self.push(field, message());
+        }
+    }
+
+    fn fraction(&mut self, field: &str, value: f64) {
+        self.check((0.0..=1.0).contains(&value), field, || {
+            format!("{} must be between 0.0 and 1.0", value)
+        });
+    }
+
+    fn multiaddrs(&mut self, field: &str, addresses: &[String]) {
+        for (i, address) in addresses.iter().enumerate() {
+            if let Err(e) = address.parse::<Multiaddr>() {
+                self.push(format!("{}[{}]", field, i), format!("invalid multiaddr {:?}: {}", address, e));
+            }
+        }
+    }
+
+    fn ip(&mut self, field: &str, address: &str) {
+        self.check(address.parse::<IpAddr>().is_ok(), field, || {
+            format!("{:?} is not an IP address", address)
+        });
+    }
+}
+
+impl ValidatorConfig {
+    /// Every semantic problem in the configuration
+    pub fn problems(&self) -> Vec<ConfigProblem> {
+        let mut problems = Problems::default();
+        let p = &mut problems;
+
+        // Network
+        let genesis = self.network.genesis_hash.strip_prefix("0x").unwrap_or_default();
+        p.check(
+            genesis.len() == 64 && genesis.chars().all(|c| c.is_ascii_hexdigit()),
+            "network.genesis_hash",
+            || format!("{:?} is not a 0x-prefixed 32-byte hex hash", self.network.genesis_hash),
+        );
+        let spec = &self.network.chain_spec;
+        p.check(spec.commerce_block_time > 0, "network.chain_spec.commerce_block_time", || {
+            "must be at least 1 second".into()
+        });
+        p.check(
+            spec.security_block_time >= spec.commerce_block_time,
+            "network.chain_spec.security_block_time",
+            || format!("must not be shorter than the commerce block time ({}s)", spec.commerce_block_time),
+        );
+        p.check(spec.max_validators > 0, "network.chain_spec.max_validators", || "must be at least 1".into());
+
+        // Validator
+        if self.validator.is_validator {
+            p.check(
+                self.validator.validator_stake >= spec.min_validator_stake,
+                "validator.validator_stake",
+                || {
+                    format!(
+                        "{} OGT is below the {} minimum of {} OGT",
+                        self.validator.validator_stake, self.network.name, spec.min_validator_stake
+                    )
+                },
+            );
+        }
+
+        // Ports: P2P (TCP and QUIC), RPC and metrics must not collide
+        let quic_port = self.p2p.quic_port.unwrap_or(self.p2p.port);
+        p.check(self.p2p.port != self.rpc.port, "rpc.port", || {
+            format!("{} is also the P2P port", self.rpc.port)
+        });
+        if self.p2p.enable_quic && self.p2p.quic_port.is_some() {
+            p.check(quic_port != self.rpc.port, "p2p.quic_port", || {
+                format!("{} is also the RPC port", quic_port)
+            });
+        }
+        if self.metrics.enabled {
+            for (field, port) in [("p2p.port", self.p2p.port), ("rpc.port", self.rpc.port)] {
+                p.check(port != self.metrics.port, "metrics.port", || {
+                    format!("{} is also used by {}", self.metrics.port, field)
+                });
+            }
+            p.ip("metrics.bind_address", &self.metrics.bind_address);
+        }
+
+        // P2P
+        p.check(self.p2p.max_peers > 0, "p2p.max_peers", || "must be at least 1".into());
+        p.check(self.p2p.enable_tcp || self.p2p.enable_quic, "p2p.enable_tcp", || {
+            "at least one of enable_tcp and enable_quic must be set".into()
+        });
+        p.multiaddrs("p2p.bootstrap_peers", &self.p2p.bootstrap_peers);
+        p.multiaddrs("p2p.external_addresses", &self.p2p.external_addresses);
+        p.multiaddrs("p2p.relay_addresses", &self.p2p.relay_addresses);
+        if let Err(e) = self.p2p.validate_sentry() {
+            p.push("p2p.sentry", format!("{:#}", e));
+        }
+
+        // RPC
+        p.ip("rpc.bind_address", &self.rpc.bind_address);
+        p.check(self.rpc.max_connections > 0, "rpc.max_connections", || "must be at least 1".into());
+        p.check(self.rpc.max_request_body_size > 0, "rpc.max_request_body_size", || {
+            "must be at least 1 byte".into()
+        });
+        p.check(self.rpc.max_batch_size > 0, "rpc.max_batch_size", || "must be at least 1".into());
+
+        // Infrastructure services
+        p.fraction("oon.resource_allocation", self.oon.resource_allocation);
+        p.fraction("oon.revenue_share_percentage", self.oon.revenue_share_percentage);
+        if self.oon.enable_oon {
+            p.check(self.oon.max_concurrent_jobs > 0, "oon.max_concurrent_jobs", || "must be at least 1".into());
+        }
+
+        for (i, tier) in self.omp.preferred_tiers.iter().enumerate() {
+            p.check(OMP_TIERS.contains(tier), &format!("omp.preferred_tiers[{}]", i), || {
+                format!("unknown storage tier {} (expected 1, 2 or 3)", tier)
+            });
+        }
+        if self.omp.enable_omp {
+            p.check(!self.omp.preferred_tiers.is_empty(), "omp.preferred_tiers", || {
+                "must list at least one tier".into()
+            });
+            p.check(self.omp.max_storage_gb > 0, "omp.max_storage_gb", || "must be at least 1".into());
+        }
+
+        let multiplier = self.orc20_relayer.gas_price_multiplier;
+        p.check(multiplier.is_finite() && multiplier > 0.0, "orc20_relayer.gas_price_multiplier", || {
+            format!("{} must be greater than 0", multiplier)
+        });
+        if self.orc20_relayer.enable_relayer {
+            p.check(self.orc20_relayer.max_concurrent_tx > 0, "orc20_relayer.max_concurrent_tx", || {
+                "must be at least 1".into()
+            });
+        }
+
+        if self.paymaster.enable_paymaster {
+            p.check(self.paymaster.max_gas_per_tx > 0, "paymaster.max_gas_per_tx", || "must be at least 1".into());
+            p.check(
+                self.paymaster.max_tx_per_user_per_hour > 0,
+                "paymaster.max_tx_per_user_per_hour",
+                || "must be at least 1".into(),
+            );
+        }
+
+        // Storage
+        if self.storage.pruning == PruningMode::Pruned {
+            p.check(self.storage.prune_batch_size > 0, "storage.prune_batch_size", || "must be at least 1".into());
+            p.check(self.storage.prune_interval_secs > 0, "storage.prune_interval_secs", || {
+                "must be at least 1 second".into()
+            });
+        }
+
+        // Logging
+        if let Some(level) = &self.logging.level {
+            if let Err(e) = tracing_subscriber::EnvFilter::try_new(level) {
+                p.push("logging.level", format!("invalid filter {:?}: {}", level, e));
+            }
+        }
+
+        problems.0
+    }
+
+    /// Fail with every problem listed if the configuration is invalid
+    pub fn validate(&self) -> Result<()> {
+        let problems = self.problems();
+        if problems.is_empty() {
+            return Ok(());
+        }
+
+        let list: Vec<String> = problems.iter().map(|problem| format!("  - {}", problem)).collect();
+        Err(anyhow::anyhow!(
+            "Invalid configuration ({} problems):\n{}",
+            problems.len(),
+            list.join("\n")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collects_problems_with_field_paths() {
+        for network in ["mainnet", "testnet", "devnet"] {
+            let config = ValidatorConfig::new_for_network(network).unwrap();
+            assert_eq!(config.problems(), [], "{} preset", network);
+        }
+
+        let mut config = ValidatorConfig::new_for_network("mainnet").unwrap();
+        config.validator.is_validator = true;
+        config.validator.validator_stake = 5;
+        config.oon.resource_allocation = 5.0;
+        config.oon.revenue_share_percentage = 1.5;
+        config.omp.preferred_tiers = vec![2, 7];
+        config.rpc.port = config.p2p.port;
+        config.p2p.bootstrap_peers.push("not-a-multiaddr".to_string());
+
+        let fields: Vec<String> = config.problems().into_iter().map(|problem| problem.field).collect();
+        assert_eq!(
+            fields,
+            [
+                "validator.validator_stake",
+                "rpc.port",
+                "p2p.bootstrap_peers[3]",
+                "oon.resource_allocation",
+                "oon.revenue_share_percentage",
+                "omp.preferred_tiers[1]",
+            ]
+        );
+        assert!(config.validate().unwrap_err().to_string().contains("6 problems"));
+    }
+}
@@ -0,0 +1,107 @@
+//! `omne-nexus db` maintenance subcommands
+//!
+//! Operate on the database under `<data_dir>/db` while the node is stopped
+//! (sled holds an exclusive lock while it runs). None of them migrate as a
+//! side effect except `migrate` itself, and all refuse a database written
+//! by a newer version.
+
+use crate::chain::ChainStore;
+use crate::store::{Store, SCHEMA_VERSION};
+use crate::utils::format_bytes;
+
+use anyhow::Result;
+use clap::Subcommand;
+use std::path::Path;
+
+#[derive(Subcommand)]
+pub enum DbCommand {
+    /// Print the schema version, pending migrations and table sizes
+    Inspect,
+    /// Rewrite the database to reclaim space
+    Compact,
+    /// Check the canonical height index against the stored blocks
+    Verify,
+    /// Apply pending schema migrations
+    Migrate {
+        /// Only list the migrations that would run
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+pub fn run(command: DbCommand, data_dir: &Path) -> Result<()> {
+    let path = data_dir.join("db");
+    if !path.exists() {
+        return Err(anyhow::anyhow!("No database at {}", path.display()));
+    }
+
+    match command {
+        DbCommand::Inspect => inspect(&path),
+        DbCommand::Compact => {
+            let (before, after) = Store::compact(&path)?;
+            println!(
+                "Compacted {}: {} → {}",
+                path.display(),
+                format_bytes(before),
+                format_bytes(after)
+            );
+            Ok(())
+        }
+        DbCommand::Verify => verify(&path),
+        DbCommand::Migrate { dry_run } => migrate(&path, dry_run),
+    }
+}
+
+fn inspect(path: &Path) -> Result<()> {
+    let store = Store::open_unmigrated(path)?;
+    let pending = store.pending_migrations()?;
+
+    println!("Database:        {}", path.display());
+    println!("Schema version:  {} (this build: {})", store.schema_version()?, SCHEMA_VERSION);
+    println!("Pending:         {} migrations", pending.len());
+    println!("Size on disk:    {}", format_bytes(store.size_on_disk()?));
+    println!();
+    println!("{:<24} {:>12} {:>12}", "TABLE", "ENTRIES", "SIZE");
+    for (name, entries, bytes) in store.table_sizes()? {
+        println!("{:<24} {:>12} {:>12}", name, entries, format_bytes(bytes));
+    }
+    Ok(())
+}
+
+fn verify(path: &Path) -> Result<()> {
+    let store = Store::open_unmigrated(path)?;
+    if !store.pending_migrations()?.is_empty() {
+        return Err(anyhow::anyhow!(
+            "Database schema version {} is outdated; run `omne-nexus db migrate` first",
+            store.schema_version()?
+        ));
+    }
+
+    let problems = ChainStore::open(&store)?.verify()?;
+    if problems.is_empty() {
+        println!("✅ Block index and block bodies are consistent");
+        return Ok(());
+    }
+
+    for problem in &problems {
+        println!("❌ {}", problem);
+    }
+    Err(anyhow::anyhow!("Found {} inconsistencies", problems.len()))
+}
+
+fn migrate(path: &Path, dry_run: bool) -> Result<()> {
+    let store = Store::open_unmigrated(path)?;
+    let from = store.schema_version()?;
+    let migrations = store.migrate(dry_run)?;
+
+    if migrations.is_empty() {
+        println!("Schema version {} is up to date", from);
+        return Ok(());
+    }
+
+    let verb = if dry_run { "Would apply" } else { "Applied" };
+    for migration in &migrations {
+        println!("{} {} → {}: {}", verb, migration.from, migration.to(), migration.description);
+    }
+    Ok(())
+}
@@ -0,0 +1,98 @@
+//! Node event bus
+//!
+//! Components publish notable events (new heads, finality, attestations,
+//! peer changes, missed slots) to a shared broadcast channel that RPC
+//! subscriptions and other observers consume.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+/// Events buffered per subscriber before slow ones start lagging
+const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+/// Consensus layer an event refers to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Layer {
+    #[default]
+    Commerce,
+    Security,
+}
+
+/// Event published on the node event bus
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NodeEvent {
+    /// A new commerce layer block became head
+    NewCommerceHead { height: u64, hash: String, timestamp: i64 },
+    /// A new security layer block became head
+    NewSecurityHead { height: u64, hash: String, timestamp: i64 },
+    /// A security checkpoint was finalized
+    Finalized { epoch: u64, height: u64, hash: String },
+    /// Our validator attested to a block
+    Attestation { layer: Layer, height: u64, block_hash: String },
+    /// A peer connected
+    PeerConnected { peer_id: String },
+    /// A peer disconnected
+    PeerDisconnected { peer_id: String },
+    /// Our validator missed its slot
+    MissedSlot { layer: Layer, slot: u64 },
+}
+
+/// Subscription topics, each matching a family of events
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum Topic {
+    NewCommerceHeads,
+    NewSecurityHeads,
+    Finality,
+    Attestations,
+    Peers,
+    MissedSlots,
+}
+
+impl Topic {
+    /// Whether `event` belongs to this topic
+    pub fn matches(&self, event: &NodeEvent) -> bool {
+        matches!(
+            (self, event),
+            (Topic::NewCommerceHeads, NodeEvent::NewCommerceHead { .. })
+                | (Topic::NewSecurityHeads, NodeEvent::NewSecurityHead { .. })
+                | (Topic::Finality, NodeEvent::Finalized { .. })
+                | (Topic::Attestations, NodeEvent::Attestation { .. })
+                | (Topic::Peers, NodeEvent::PeerConnected { .. })
+                | (Topic::Peers, NodeEvent::PeerDisconnected { .. })
+                | (Topic::MissedSlots, NodeEvent::MissedSlot { .. })
+        )
+    }
+}
+
+/// Cloneable handle to the node event bus
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<NodeEvent>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        Self { sender }
+    }
+
+    /// Publish an event; it is dropped when nobody is subscribed
+    pub fn publish(&self, event: NodeEvent) {
+        let _ = self.sender.send(event);
+    }
+
+    /// Receive every event published from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<NodeEvent> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
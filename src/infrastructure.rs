@@ -1,24 +1,40 @@
 //! Infrastructure Services Manager
 //!
 //! Manages all infrastructure services for the validator including
-//! OMP, Enhanced ORC-20, and OEC-4337 services.
+//! OON, OMP, Enhanced ORC-20, and OEC-4337 services. Each service is built
+//! from its section of the effective configuration and signs with its key
+//! under `keys/`; keys missing for enabled services are generated at
+//! startup, leaving `config.toml` untouched.
 
 use crate::config::ValidatorConfig;
 use anyhow::Result;
-use tokio::sync::broadcast;
-use tracing::{info, debug, error};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, Mutex};
+use tracing::{info, debug};
 
 /// Infrastructure services manager
 pub struct InfrastructureServices {
+    #[allow(dead_code)] // The configuration the services started with; reloads pass the new one in
     config: ValidatorConfig,
-    omp_service: Option<OMPService>,
-    orc20_relayer: Option<ORC20RelayerService>,
-    paymaster: Option<PaymasterService>,
+    oon_service: Mutex<Option<OONService>>,
+    omp_service: Mutex<Option<OMPService>>,
+    orc20_relayer: Mutex<Option<ORC20RelayerService>>,
+    paymaster: Mutex<Option<PaymasterService>>,
+}
+
+/// OON (Omne Orchestration Network) computational service implementation
+pub struct OONService {
+    max_concurrent_jobs: usize,
+    resource_allocation: f64,
+    #[allow(dead_code)] // Not reported yet
+    jobs_completed: u64,
 }
 
 /// OMP (Omne Media Protocol) service implementation
 pub struct OMPService {
     storage_quota_gb: u64,
+    preferred_tiers: Vec<u8>,
     requests_served: u64,
     revenue_earned: u128,
 }
@@ -26,6 +42,7 @@ pub struct OMPService {
 /// Enhanced ORC-20 relayer service implementation  
 pub struct ORC20RelayerService {
     concurrent_tx_limit: usize,
+    gas_price_multiplier: f64,
     transactions_relayed: u64,
     gas_fees_earned: u128,
 }
@@ -33,49 +50,78 @@ pub struct ORC20RelayerService {
 /// OEC-4337 paymaster service implementation
 pub struct PaymasterService {
     daily_budget: u128,
+    max_gas_per_tx: u64,
+    max_tx_per_user_per_hour: u32,
     transactions_sponsored: u64,
     sponsorship_fees_earned: u128,
 }
 
 impl InfrastructureServices {
     /// Create new infrastructure services manager
-    pub fn new(config: ValidatorConfig) -> Self {
-        Self {
-            omp_service: if config.omp.enable_omp {
-                Some(OMPService::new(&config))
+    pub async fn new(config: &ValidatorConfig) -> Result<Self> {
+        for path in config.generate_service_keys(&config.data_dir)? {
+            info!("🔑 Generated missing service key {}", path.display());
+        }
+
+        Ok(Self {
+            oon_service: Mutex::new(if config.oon.enable_oon {
+                Some(OONService::new(config)?)
+            } else {
+                None
+            }),
+            omp_service: Mutex::new(if config.omp.enable_omp {
+                Some(OMPService::new(config)?)
             } else {
                 None
-            },
-            orc20_relayer: if config.orc20_relayer.enable_relayer {
-                Some(ORC20RelayerService::new(&config))
+            }),
+            orc20_relayer: Mutex::new(if config.orc20_relayer.enable_relayer {
+                Some(ORC20RelayerService::new(config)?)
             } else {
                 None  
-            },
-            paymaster: if config.paymaster.enable_paymaster {
-                Some(PaymasterService::new(&config))
+            }),
+            paymaster: Mutex::new(if config.paymaster.enable_paymaster {
+                Some(PaymasterService::new(config)?)
             } else {
                 None
-            },
-            config,
-        }
+            }),
+            config: config.clone(),
+        })
     }
 
     /// Start all enabled infrastructure services
-    pub async fn start(&mut self, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+    pub async fn start(&self, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
         info!("🏗️  Starting infrastructure services");
 
-        if let Some(omp) = &mut self.omp_service {
-            info!("📁 Starting OMP media storage service");
+        if let Some(oon) = self.oon_service.lock().await.as_ref() {
+            info!(
+                "🧮 Starting OON computational service ({} max jobs, {:.0}% of resources)",
+                oon.max_concurrent_jobs,
+                oon.resource_allocation * 100.0
+            );
+            // TODO: Start OON service
+        }
+
+        if let Some(omp) = self.omp_service.lock().await.as_ref() {
+            info!(
+                "📁 Starting OMP media storage service ({} GB quota, tiers {:?})",
+                omp.storage_quota_gb, omp.preferred_tiers
+            );
             // TODO: Start OMP service
         }
 
-        if let Some(relayer) = &mut self.orc20_relayer {
-            info!("🔄 Starting Enhanced ORC-20 relayer service");
+        if let Some(relayer) = self.orc20_relayer.lock().await.as_ref() {
+            info!(
+                "🔄 Starting Enhanced ORC-20 relayer service ({} concurrent txs, {}x gas price)",
+                relayer.concurrent_tx_limit, relayer.gas_price_multiplier
+            );
             // TODO: Start relayer service
         }
 
-        if let Some(paymaster) = &mut self.paymaster {
-            info!("💰 Starting OEC-4337 paymaster service");
+        if let Some(paymaster) = self.paymaster.lock().await.as_ref() {
+            info!(
+                "💰 Starting OEC-4337 paymaster service (daily budget {}, {} max gas per tx, {} txs per user per hour)",
+                paymaster.daily_budget, paymaster.max_gas_per_tx, paymaster.max_tx_per_user_per_hour
+            );
             // TODO: Start paymaster service
         }
 
@@ -95,19 +141,57 @@ impl InfrastructureServices {
         Ok(())
     }
 
+    /// Apply reloaded limits and budgets to the running services. Enabling
+    /// or disabling a service needs a restart.
+    pub async fn reconfigure(&self, config: &ValidatorConfig) {
+        if let Some(oon) = self.oon_service.lock().await.as_mut() {
+            oon.max_concurrent_jobs = config.oon.max_concurrent_jobs;
+            oon.resource_allocation = config.oon.resource_allocation;
+        }
+
+        if let Some(omp) = self.omp_service.lock().await.as_mut() {
+            omp.storage_quota_gb = config.omp.max_storage_gb;
+            omp.preferred_tiers = config.omp.preferred_tiers.clone();
+        }
+
+        if let Some(relayer) = self.orc20_relayer.lock().await.as_mut() {
+            relayer.concurrent_tx_limit = config.orc20_relayer.max_concurrent_tx;
+            relayer.gas_price_multiplier = config.orc20_relayer.gas_price_multiplier;
+        }
+
+        if let Some(paymaster) = self.paymaster.lock().await.as_mut() {
+            paymaster.daily_budget = config.paymaster.daily_sponsorship_budget;
+            paymaster.max_gas_per_tx = config.paymaster.max_gas_per_tx;
+            paymaster.max_tx_per_user_per_hour = config.paymaster.max_tx_per_user_per_hour;
+        }
+    }
+
+    /// OMP storage quota and paymaster gas limit in effect, for tests
+    #[cfg(test)]
+    pub(crate) async fn limits(&self) -> (Option<u64>, Option<u64>) {
+        (
+            self.omp_service.lock().await.as_ref().map(|omp| omp.storage_quota_gb),
+            self.paymaster.lock().await.as_ref().map(|paymaster| paymaster.max_gas_per_tx),
+        )
+    }
+
     /// Update service metrics
-    async fn update_metrics(&mut self) -> Result<()> {
+    async fn update_metrics(&self) -> Result<()> {
         debug!("📊 Updating infrastructure service metrics");
 
-        if let Some(omp) = &mut self.omp_service {
+        if let Some(oon) = self.oon_service.lock().await.as_mut() {
+            oon.update_metrics().await?;
+        }
+
+        if let Some(omp) = self.omp_service.lock().await.as_mut() {
             omp.update_metrics().await?;
         }
 
-        if let Some(relayer) = &mut self.orc20_relayer {
+        if let Some(relayer) = self.orc20_relayer.lock().await.as_mut() {
             relayer.update_metrics().await?;
         }
 
-        if let Some(paymaster) = &mut self.paymaster {
+        if let Some(paymaster) = self.paymaster.lock().await.as_mut() {
             paymaster.update_metrics().await?;
         }
 
@@ -115,18 +199,19 @@ impl InfrastructureServices {
     }
 
     /// Get total revenue from all services
-    pub fn get_total_revenue(&self) -> u128 {
+    #[allow(dead_code)] // No revenue endpoint exists yet
+    pub async fn get_total_revenue(&self) -> u128 {
         let mut total = 0;
 
-        if let Some(omp) = &self.omp_service {
+        if let Some(omp) = self.omp_service.lock().await.as_ref() {
             total += omp.revenue_earned;
         }
 
-        if let Some(relayer) = &self.orc20_relayer {
+        if let Some(relayer) = self.orc20_relayer.lock().await.as_ref() {
             total += relayer.gas_fees_earned;
         }
 
-        if let Some(paymaster) = &self.paymaster {
+        if let Some(paymaster) = self.paymaster.lock().await.as_ref() {
             total += paymaster.sponsorship_fees_earned;
         }
 
@@ -134,26 +219,56 @@ impl InfrastructureServices {
     }
 
     /// Get service statistics
-    pub fn get_statistics(&self) -> InfrastructureServiceStats {
+    pub async fn get_statistics(&self) -> InfrastructureServiceStats {
+        let omp = self.omp_service.lock().await;
+        let relayer = self.orc20_relayer.lock().await;
+        let paymaster = self.paymaster.lock().await;
+
         InfrastructureServiceStats {
-            omp_enabled: self.omp_service.is_some(),
-            omp_requests_served: self.omp_service.as_ref().map(|s| s.requests_served).unwrap_or(0),
-            orc20_relayer_enabled: self.orc20_relayer.is_some(),
-            orc20_txs_relayed: self.orc20_relayer.as_ref().map(|s| s.transactions_relayed).unwrap_or(0),
-            paymaster_enabled: self.paymaster.is_some(),
-            paymaster_txs_sponsored: self.paymaster.as_ref().map(|s| s.transactions_sponsored).unwrap_or(0),
-            total_revenue: self.get_total_revenue(),
+            omp_enabled: omp.is_some(),
+            omp_requests_served: omp.as_ref().map(|s| s.requests_served).unwrap_or(0),
+            orc20_relayer_enabled: relayer.is_some(),
+            orc20_txs_relayed: relayer.as_ref().map(|s| s.transactions_relayed).unwrap_or(0),
+            paymaster_enabled: paymaster.is_some(),
+            paymaster_txs_sponsored: paymaster.as_ref().map(|s| s.transactions_sponsored).unwrap_or(0),
+            total_revenue: omp.as_ref().map(|s| s.revenue_earned).unwrap_or(0)
+                + relayer.as_ref().map(|s| s.gas_fees_earned).unwrap_or(0)
+                + paymaster.as_ref().map(|s| s.sponsorship_fees_earned).unwrap_or(0),
         }
     }
+
+    /// Get infrastructure services status
+    pub async fn status(&self) -> Result<InfrastructureStatus> {
+        Ok(InfrastructureStatus {
+            oon_enabled: self.oon_service.lock().await.is_some(),
+            services: self.get_statistics().await,
+        })
+    }
+}
+
+impl OONService {
+    fn new(config: &ValidatorConfig) -> Result<Self> {
+        Ok(Self {
+            max_concurrent_jobs: config.oon.max_concurrent_jobs,
+            resource_allocation: config.oon.resource_allocation,
+            jobs_completed: 0,
+        })
+    }
+
+    async fn update_metrics(&mut self) -> Result<()> {
+        // TODO: Implement actual OON metrics collection
+        Ok(())
+    }
 }
 
 impl OMPService {
-    fn new(config: &ValidatorConfig) -> Self {
-        Self {
+    fn new(config: &ValidatorConfig) -> Result<Self> {
+        Ok(Self {
             storage_quota_gb: config.omp.max_storage_gb,
+            preferred_tiers: config.omp.preferred_tiers.clone(),
             requests_served: 0,
             revenue_earned: 0,
-        }
+        })
     }
 
     async fn update_metrics(&mut self) -> Result<()> {
@@ -163,12 +278,13 @@ impl OMPService {
 }
 
 impl ORC20RelayerService {
-    fn new(config: &ValidatorConfig) -> Self {
-        Self {
+    fn new(config: &ValidatorConfig) -> Result<Self> {
+        Ok(Self {
             concurrent_tx_limit: config.orc20_relayer.max_concurrent_tx,
+            gas_price_multiplier: config.orc20_relayer.gas_price_multiplier,
             transactions_relayed: 0,
             gas_fees_earned: 0,
-        }
+        })
     }
 
     async fn update_metrics(&mut self) -> Result<()> {
@@ -178,12 +294,14 @@ impl ORC20RelayerService {
 }
 
 impl PaymasterService {
-    fn new(config: &ValidatorConfig) -> Self {
-        Self {
+    fn new(config: &ValidatorConfig) -> Result<Self> {
+        Ok(Self {
             daily_budget: config.paymaster.daily_sponsorship_budget,
+            max_gas_per_tx: config.paymaster.max_gas_per_tx,
+            max_tx_per_user_per_hour: config.paymaster.max_tx_per_user_per_hour,
             transactions_sponsored: 0,
             sponsorship_fees_earned: 0,
-        }
+        })
     }
 
     async fn update_metrics(&mut self) -> Result<()> {
@@ -193,7 +311,7 @@ impl PaymasterService {
 }
 
 /// Infrastructure service statistics
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct InfrastructureServiceStats {
     pub omp_enabled: bool,
     pub omp_requests_served: u64,
@@ -202,4 +320,37 @@ pub struct InfrastructureServiceStats {
     pub paymaster_enabled: bool,
     pub paymaster_txs_sponsored: u64,
     pub total_revenue: u128,
-}
\ No newline at end of file
+}
+/// Infrastructure services status
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct InfrastructureStatus {
+    pub oon_enabled: bool,
+    pub services: InfrastructureServiceStats,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_enabled_services_get_keys() {
+        let dir = std::env::temp_dir().join(format!("omne-infra-{}", rand::random::<u32>()));
+        let mut config = ValidatorConfig::new_for_network("devnet").unwrap();
+        config.data_dir = dir.clone();
+        config.init_directories(&dir).unwrap();
+        config.enable_service("omp").unwrap();
+        config.enable_service("paymaster").unwrap();
+        config.omp.max_storage_gb = 500;
+
+        std::fs::write(dir.join("keys/omp.key"), "existing_omp_key").unwrap();
+        let services = InfrastructureServices::new(&config).await.unwrap();
+        assert_eq!(std::fs::read_to_string(dir.join("keys/omp.key")).unwrap(), "existing_omp_key");
+        assert!(config.service_key_path("paymaster").exists());
+        assert!(!dir.join("config.toml").exists());
+        assert_eq!(services.omp_service.lock().await.as_ref().unwrap().storage_quota_gb, 500);
+        let status = services.status().await.unwrap();
+        assert!(status.services.omp_enabled && status.services.paymaster_enabled);
+        assert!(!status.oon_enabled && !status.services.orc20_relayer_enabled);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,40 @@
+//! Logging setup with a filter that can be changed at runtime
+
+use anyhow::Result;
+use std::sync::OnceLock;
+use tracing_subscriber::{fmt, prelude::*, reload, EnvFilter, Registry};
+
+/// Handle used to swap the active filter
+static FILTER_HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// Filter installed at startup, restored when a configured one is removed
+static DEFAULT_FILTER: OnceLock<String> = OnceLock::new();
+
+/// Install the global subscriber with `directives` as the initial filter
+pub fn init(directives: &str) {
+    let (filter, handle) = reload::Layer::new(EnvFilter::new(directives));
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_target(false))
+        .init();
+
+    let _ = FILTER_HANDLE.set(handle);
+    let _ = DEFAULT_FILTER.set(directives.to_string());
+}
+
+/// Directives the subscriber was installed with
+pub fn default_filter() -> &'static str {
+    DEFAULT_FILTER.get().map(String::as_str).unwrap_or("info")
+}
+
+/// Replace the active filter, e.g. `"debug,libp2p=info"`
+pub fn set_filter(directives: &str) -> Result<()> {
+    let filter = EnvFilter::try_new(directives)
+        .map_err(|e| anyhow::anyhow!("Invalid log filter {:?}: {}", directives, e))?;
+    let handle = FILTER_HANDLE
+        .get()
+        .ok_or_else(|| anyhow::anyhow!("Logging is not initialized"))?;
+    handle.reload(filter)?;
+    Ok(())
+}
@@ -9,15 +9,26 @@ use tracing::{info};
 use std::path::PathBuf;
 
 mod validator;
+mod chain;
 mod consensus;
+mod events;
 mod p2p;
 mod rpc;
 mod config;
+mod db;
 mod utils;
 mod infrastructure;
+mod logging;
+mod metrics;
+mod store;
+mod pruner;
+mod status;
+mod slashing;
+mod backup;
+mod reload;
 
 use validator::ValidatorNode;
-use config::ValidatorConfig;
+use config::{CliOverride, ConfigSource, NetworkSpec, ValidatorConfig};
 
 /// Omne Nexus - The definitive validator node for Omne blockchain
 #[derive(Parser)]
@@ -50,19 +61,31 @@ enum Commands {
         /// Network to initialize for (mainnet, testnet, devnet)
         #[arg(long, default_value = "testnet")]
         network: String,
+
+        /// Chain spec file describing a custom or private network
+        #[arg(long, conflicts_with = "network")]
+        chain_spec: Option<PathBuf>,
         
         /// Generate new validator keys
         #[arg(long)]
         generate_keys: bool,
+
+        /// Infrastructure services to enable, with their keys
+        /// (comma-separated: oon, omp, orc20-relayer, paymaster)
+        #[arg(long, value_delimiter = ',')]
+        services: Vec<String>,
     },
     
     /// Start the validator node
+    ///
+    /// Settings come from built-in defaults, then config.toml, then OMNE_*
+    /// environment variables, then the flags given here.
     Start {
-        /// Data directory for validator storage
-        #[arg(long, default_value = "~/.omne-nexus")]
-        data_dir: PathBuf,
+        /// Data directory for validator storage [default: ~/.omne-nexus]
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
         
-        /// Configuration file path
+        /// Configuration file path [default: <data-dir>/config.toml]
         #[arg(long)]
         config: Option<PathBuf>,
         
@@ -71,20 +94,24 @@ enum Commands {
         validator: bool,
         
         /// Validator stake amount in OGT
-        #[arg(long, default_value = "100")]
-        stake: u64,
+        #[arg(long)]
+        stake: Option<u64>,
         
-        /// Network to connect to
-        #[arg(long, default_value = "testnet")]
-        network: String,
+        /// Network to connect to (mainnet, testnet, devnet)
+        #[arg(long)]
+        network: Option<String>,
+
+        /// Chain spec file describing a custom or private network
+        #[arg(long, conflicts_with = "network")]
+        chain_spec: Option<PathBuf>,
         
         /// P2P listening port
-        #[arg(long, default_value = "30303")]
-        p2p_port: u16,
+        #[arg(long)]
+        p2p_port: Option<u16>,
         
         /// RPC server port
-        #[arg(long, default_value = "9944")]
-        rpc_port: u16,
+        #[arg(long)]
+        rpc_port: Option<u16>,
         
         /// Bootstrap peers (comma-separated)
         #[arg(long)]
@@ -105,6 +132,16 @@ enum Commands {
         /// Enable OEC-4337 paymaster services
         #[arg(long)]
         enable_paymaster: bool,
+
+        /// Set any configuration field, e.g. `--set omp.max_storage_gb=500`
+        /// (repeatable; same paths as OMNE_* variables, lists comma-separated)
+        #[arg(long = "set", value_name = "PATH=VALUE", value_parser = parse_setting)]
+        settings: Vec<(String, String)>,
+
+        /// List the database migrations startup would apply, then exit
+        /// without migrating or starting the node
+        #[arg(long)]
+        migrate_dry_run: bool,
     },
     
     /// Show validator status
@@ -112,8 +149,61 @@ enum Commands {
         /// RPC endpoint to query
         #[arg(long, default_value = "http://127.0.0.1:9944")]
         rpc_endpoint: String,
+
+        /// Query the node over its IPC socket instead of HTTP
+        #[arg(long)]
+        ipc_path: Option<PathBuf>,
+
+        /// Print the status as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Refresh every SECONDS (default 5) until interrupted
+        #[arg(long, value_name = "SECONDS", num_args = 0..=1, default_missing_value = "5")]
+        watch: Option<u64>,
     },
     
+    /// Print the OpenRPC document describing the JSON-RPC API
+    RpcSchema,
+
+    /// Inspect the node configuration
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+
+    /// Inspect, verify, compact or migrate the node database (node must be stopped)
+    Db {
+        #[command(subcommand)]
+        command: db::DbCommand,
+
+        /// Data directory of the node
+        #[arg(long, default_value = "~/.omne-nexus", global = true)]
+        data_dir: PathBuf,
+    },
+
+    /// Back up the database, slashing protection, keystores and config (node must be stopped)
+    Backup {
+        /// Archive to write
+        #[arg(long)]
+        out: PathBuf,
+
+        /// Data directory of the node
+        #[arg(long, default_value = "~/.omne-nexus")]
+        data_dir: PathBuf,
+    },
+
+    /// Restore a backup into a data directory (node must be stopped)
+    Restore {
+        /// Archive written by `backup`
+        #[arg(long)]
+        from: PathBuf,
+
+        /// Data directory of the node
+        #[arg(long, default_value = "~/.omne-nexus")]
+        data_dir: PathBuf,
+    },
+
     /// Generate validator keys
     Keys {
         /// Output directory for keys
@@ -126,6 +216,52 @@ enum Commands {
     },
 }
 
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Print config.toml, or with --effective the merged configuration
+    Show {
+        /// Merge defaults, config.toml, OMNE_* environment variables and
+        /// the flags below, and show where each value came from
+        #[arg(long)]
+        effective: bool,
+
+        /// Data directory of the node [default: ~/.omne-nexus]
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+
+        /// Configuration file path [default: <data-dir>/config.toml]
+        #[arg(long)]
+        config: Option<PathBuf>,
+
+        /// Network whose defaults apply
+        #[arg(long)]
+        network: Option<String>,
+
+        /// Chain spec file describing a custom or private network
+        #[arg(long, conflicts_with = "network")]
+        chain_spec: Option<PathBuf>,
+    },
+
+    /// Check the effective configuration and list every problem
+    Validate {
+        /// Data directory of the node [default: ~/.omne-nexus]
+        #[arg(long)]
+        data_dir: Option<PathBuf>,
+
+        /// Configuration file path [default: <data-dir>/config.toml]
+        #[arg(long)]
+        config: Option<PathBuf>,
+
+        /// Network whose defaults apply
+        #[arg(long)]
+        network: Option<String>,
+
+        /// Chain spec file describing a custom or private network
+        #[arg(long, conflicts_with = "network")]
+        chain_spec: Option<PathBuf>,
+    },
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
@@ -137,47 +273,121 @@ async fn main() -> Result<()> {
         "info,libp2p=warn,sled=warn"
     };
     
-    tracing_subscriber::fmt()
-        .with_env_filter(filter)
-        .with_target(false)
-        .init();
+    logging::init(filter);
     
     match cli.command {
-        Commands::Init { data_dir, network, generate_keys } => {
+        Commands::Init { data_dir, network, chain_spec, generate_keys, services } => {
             info!("🔧 Initializing Omne Nexus validator...");
-            init_validator(data_dir, network, generate_keys).await
+            init_validator(data_dir, network, chain_spec, generate_keys, services).await
         },
         
-        Commands::Start { 
-            data_dir, 
-            config, 
-            validator, 
-            stake, 
-            network, 
-            p2p_port, 
-            rpc_port, 
+        Commands::Start {
+            data_dir,
+            config,
+            validator,
+            stake,
+            network,
+            chain_spec,
+            p2p_port,
+            rpc_port,
             bootstrap_peers,
-            enable_oon 
+            enable_oon,
+            enable_omp,
+            enable_orc20_relayer,
+            enable_paymaster,
+            settings,
+            migrate_dry_run,
         } => {
             info!("🚀 Starting Omne Nexus validator node...");
-            start_validator(
-                data_dir, 
-                config, 
-                validator, 
-                stake, 
-                network, 
-                p2p_port, 
-                rpc_port, 
-                bootstrap_peers,
-                enable_oon
-            ).await
+            let mut overrides = common_overrides(data_dir.as_deref(), network);
+            if validator {
+                overrides.push(CliOverride::new("validator.is_validator", "--validator", true));
+            }
+            if let Some(stake) = stake {
+                overrides.push(CliOverride::new("validator.validator_stake", "--stake", stake as i64));
+            }
+            if let Some(port) = p2p_port {
+                overrides.push(CliOverride::new("p2p.port", "--p2p-port", port as i64));
+            }
+            if let Some(port) = rpc_port {
+                overrides.push(CliOverride::new("rpc.port", "--rpc-port", port as i64));
+            }
+            if let Some(peers) = bootstrap_peers {
+                let peers: Vec<String> = peers.split(',').map(|s| s.trim().to_string()).collect();
+                overrides.push(CliOverride::new("p2p.bootstrap_peers", "--bootstrap-peers", peers));
+            }
+            if enable_oon {
+                overrides.push(CliOverride::new("oon.enable_oon", "--enable-oon", true));
+            }
+            if enable_omp {
+                overrides.push(CliOverride::new("omp.enable_omp", "--enable-omp", true));
+            }
+            if enable_orc20_relayer {
+                overrides.push(CliOverride::new("orc20_relayer.enable_relayer", "--enable-orc20-relayer", true));
+            }
+            if enable_paymaster {
+                overrides.push(CliOverride::new("paymaster.enable_paymaster", "--enable-paymaster", true));
+            }
+            for (path, value) in settings {
+                overrides.push(CliOverride::new(path, "--set", value));
+            }
+
+            if cli.verbose {
+                overrides.push(CliOverride::new("logging.level", "--verbose", filter));
+            }
+
+            let source = config_source(data_dir, config, chain_spec, overrides)?;
+            start_validator(source, migrate_dry_run).await
         },
         
-        Commands::Status { rpc_endpoint } => {
-            info!("📊 Checking validator status...");
-            show_status(rpc_endpoint).await
+        Commands::Status { rpc_endpoint, ipc_path, json, watch } => {
+            show_status(rpc_endpoint, ipc_path, json, watch).await
         },
         
+        Commands::RpcSchema => {
+            println!("{}", serde_json::to_string_pretty(&rpc::openrpc_document())?);
+            Ok(())
+        },
+
+        Commands::Config { command: ConfigCommand::Show { effective, data_dir, config, network, chain_spec } } => {
+            if effective {
+                let overrides = common_overrides(data_dir.as_deref(), network);
+                print!("{}", config_source(data_dir, config, chain_spec, overrides)?.load()?.render()?);
+            } else {
+                let path = config_path(data_dir.as_deref(), config);
+                let content = std::fs::read_to_string(&path)
+                    .with_context(|| format!("No configuration file at {}", path.display()))?;
+                print!("{}", content);
+            }
+            Ok(())
+        },
+
+        Commands::Config { command: ConfigCommand::Validate { data_dir, config, network, chain_spec } } => {
+            let overrides = common_overrides(data_dir.as_deref(), network);
+            let config = config_source(data_dir, config, chain_spec, overrides)?.load()?.config;
+            let problems = config.problems();
+            if problems.is_empty() {
+                println!("✅ Configuration is valid");
+                return Ok(());
+            }
+            for problem in &problems {
+                println!("❌ {}", problem);
+            }
+            Err(anyhow::anyhow!("Found {} configuration problems", problems.len()))
+        },
+
+        Commands::Db { command, data_dir } => {
+            db::run(command, &utils::expand_tilde(&data_dir))
+        },
+
+        Commands::Backup { out, data_dir } => {
+            backup::run_backup(&utils::expand_tilde(&data_dir), &utils::expand_tilde(&out))
+        },
+
+        Commands::Restore { from, data_dir } => {
+            backup::run_restore(&utils::expand_tilde(&from), &utils::expand_tilde(&data_dir))
+        },
+
         Commands::Keys { output_dir, key_type } => {
             info!("🔑 Generating validator keys...");
             generate_keys(output_dir, key_type).await
@@ -188,69 +398,110 @@ async fn main() -> Result<()> {
 async fn init_validator(
     data_dir: PathBuf, 
     network: String, 
-    generate_keys: bool
+    chain_spec: Option<PathBuf>,
+    generate_keys: bool,
+    services: Vec<String>,
 ) -> Result<()> {
-    let config = ValidatorConfig::new_for_network(&network)?;
+    let data_dir = utils::expand_tilde(&data_dir);
+    let mut config = match chain_spec {
+        Some(path) => ValidatorConfig::from_spec(&NetworkSpec::from_file(&utils::expand_tilde(&path))?),
+        None => ValidatorConfig::new_for_network(&network)?,
+    };
+    config.data_dir = data_dir.clone();
+    for service in &services {
+        config.enable_service(service)?;
+    }
     config.init_directories(&data_dir)?;
     
     if generate_keys {
         config.generate_validator_keys(&data_dir)?;
+        p2p::load_or_create_network_key(&config.network_key_path())?;
+    } else {
+        config.generate_service_keys(&data_dir)?;
     }
+
+    let jwt_secret_path = config.rpc.jwt_secret_path(&data_dir);
+    rpc::auth::ensure_secret(&jwt_secret_path)?;
     
     config.save_to_file(&data_dir.join("config.toml"))?;
     
     info!("✅ Nexus validator initialized successfully");
     info!("   Data directory: {}", data_dir.display());
-    info!("   Network: {}", network);
+    info!("   Network: {} (ID: {})", config.network.name, config.network.id);
     info!("   Configuration saved to: {}", data_dir.join("config.toml").display());
+    info!("   Admin RPC secret: {}", jwt_secret_path.display());
+    for service in config.enabled_services() {
+        info!("   {} key: {}", service, config.service_key_path(service).display());
+    }
     
     Ok(())
 }
 
-async fn start_validator(
-    data_dir: PathBuf,
-    config_path: Option<PathBuf>,
-    is_validator: bool,
-    stake: u64,
-    network: String,
-    p2p_port: u16,
-    rpc_port: u16,
-    bootstrap_peers: Option<String>,
-    enable_oon: bool,
-) -> Result<()> {
-    // Load or create configuration
-    let mut config = if let Some(config_path) = config_path {
-        ValidatorConfig::load_from_file(&config_path)?
-    } else {
-        let default_config_path = data_dir.join("config.toml");
-        if default_config_path.exists() {
-            ValidatorConfig::load_from_file(&default_config_path)?
-        } else {
-            ValidatorConfig::new_for_network(&network)?
-        }
-    };
-    
-    // Override config with CLI parameters
-    config.data_dir = data_dir;
-    config.validator.is_validator = is_validator;
-    config.validator.validator_stake = stake;
-    config.p2p.port = p2p_port;
-    config.rpc.port = rpc_port;
-    config.oon.enable_oon = enable_oon;
-    
-    if let Some(peers) = bootstrap_peers {
-        config.p2p.bootstrap_peers = peers.split(',').map(|s| s.trim().to_string()).collect();
+/// Overrides from flags shared by `start` and `config show`
+fn common_overrides(data_dir: Option<&std::path::Path>, network: Option<String>) -> Vec<CliOverride> {
+    let mut overrides = Vec::new();
+    if let Some(dir) = data_dir {
+        overrides.push(CliOverride::new("data_dir", "--data-dir", dir.display().to_string()));
     }
-    
-    // Validate minimum stake for validators
-    if is_validator && stake < 20 {
-        return Err(anyhow::anyhow!(
-            "Minimum validator stake is 20 OGT, provided: {}", stake
-        ));
+    if let Some(network) = network {
+        overrides.push(CliOverride::new("network.name", "--network", network));
+    }
+    overrides
+}
+
+/// Split a `--set` argument into its field path and value
+fn parse_setting(arg: &str) -> std::result::Result<(String, String), String> {
+    match arg.split_once('=') {
+        Some((path, value)) if !path.trim().is_empty() => Ok((path.trim().to_string(), value.to_string())),
+        _ => Err(format!("expected PATH=VALUE, got {:?}", arg)),
+    }
+}
+
+/// `--config`, else `config.toml` in the data directory
+fn config_path(data_dir: Option<&std::path::Path>, config: Option<PathBuf>) -> PathBuf {
+    match config {
+        Some(path) => utils::expand_tilde(&path),
+        None => config::data_dir(data_dir).join("config.toml"),
+    }
+}
+
+/// Layers to merge: defaults, the configuration file, `OMNE_*` variables,
+/// the chain spec and `overrides`
+fn config_source(
+    data_dir: Option<PathBuf>,
+    config: Option<PathBuf>,
+    chain_spec: Option<PathBuf>,
+    overrides: Vec<CliOverride>,
+) -> Result<ConfigSource> {
+    let explicit = config.is_some();
+    let path = config_path(data_dir.as_deref(), config);
+    if explicit && !path.exists() {
+        return Err(anyhow::anyhow!("Configuration file {} does not exist", path.display()));
+    }
+    let chain_spec = chain_spec
+        .map(|path| NetworkSpec::from_file(&utils::expand_tilde(&path)))
+        .transpose()?;
+    Ok(ConfigSource {
+        path,
+        chain_spec,
+        overrides,
+    })
+}
+
+async fn start_validator(source: ConfigSource, migrate_dry_run: bool) -> Result<()> {
+    let config = source.load()?.config;
+
+    // Reject invalid settings before anything starts
+    config.validate()?;
+    if migrate_dry_run {
+        return db::run(db::DbCommand::Migrate { dry_run: true }, &config.data_dir);
+    }
+    if let Some(level) = &config.logging.level {
+        logging::set_filter(level)?;
     }
     
-    // Create and start validator node
-    let validator_node = ValidatorNode::new(config).await
+    // Create and start validator node; `source` is kept for config reloads
+    let validator_node = ValidatorNode::new(config, source).await
         .context("Failed to create validator node")?;
     
     // Start the node and wait for shutdown signal
@@ -260,10 +511,21 @@ async fn start_validator(
     Ok(())
 }
 
-async fn show_status(rpc_endpoint: String) -> Result<()> {
-    // TODO: Implement RPC client to query validator status
-    info!("Querying status from: {}", rpc_endpoint);
-    info!("Status functionality coming soon...");
+async fn show_status(
+    rpc_endpoint: String,
+    ipc_path: Option<PathBuf>,
+    json: bool,
+    watch: Option<u64>,
+) -> Result<()> {
+    let client = match ipc_path {
+        Some(path) => rpc::client::RpcClient::ipc(path),
+        None => rpc::client::RpcClient::http(&rpc_endpoint)?,
+    };
+
+    let healthy = status::run(&client, json, watch.map(std::time::Duration::from_secs)).await?;
+    if !healthy {
+        std::process::exit(1);
+    }
     Ok(())
 }
 
@@ -277,7 +539,7 @@ async fn generate_keys(output_dir: PathBuf, key_type: String) -> Result<()> {
         },
         "network" => {
             info!("Generating network identity keys...");
-            // TODO: Generate Ed25519 network keys
+            p2p::load_or_create_network_key(&output_dir.join("network.key"))?;
         },
         "oon" => {
             info!("Generating OON service keys...");
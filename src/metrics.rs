@@ -0,0 +1,405 @@
+//! Prometheus metrics endpoint
+//!
+//! Serves `GET /metrics` in the Prometheus text exposition format on its own
+//! port (`metrics.bind_address`, `metrics.port`) so scrapers never need
+//! access to the RPC API. Values are read from consensus, P2P and the RPC
+//! server on every scrape; all names carry the `omne_` prefix.
+
+use crate::config::ValidatorConfig;
+use crate::consensus::PoVERAValidator;
+use crate::p2p::P2PNetwork;
+use crate::rpc::RpcServer;
+
+use anyhow::{Context, Result};
+use http_body_util::Full;
+use hyper::body::Bytes;
+use hyper::header::{self, HeaderValue};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use std::convert::Infallible;
+use std::fmt::Write;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio::sync::broadcast;
+use tracing::{debug, info, warn};
+
+/// Prefix of every metric name
+const PREFIX: &str = "omne_";
+
+/// Path of the scrape endpoint
+const METRICS_PATH: &str = "/metrics";
+
+/// Content type of the text exposition format
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Serves node metrics to Prometheus
+pub struct MetricsServer {
+    config: ValidatorConfig,
+    bind_address: SocketAddr,
+    consensus: Arc<PoVERAValidator>,
+    p2p_network: Arc<P2PNetwork>,
+    rpc_server: Arc<RpcServer>,
+}
+
+impl MetricsServer {
+    pub fn new(
+        config: &ValidatorConfig,
+        consensus: Arc<PoVERAValidator>,
+        p2p_network: Arc<P2PNetwork>,
+        rpc_server: Arc<RpcServer>,
+    ) -> Result<Self> {
+        let bind_address = format!("{}:{}", config.metrics.bind_address, config.metrics.port)
+            .parse()
+            .context("Invalid metrics bind address")?;
+
+        Ok(Self {
+            config: config.clone(),
+            bind_address,
+            consensus,
+            p2p_network,
+            rpc_server,
+        })
+    }
+
+    /// Serve scrapes until shutdown
+    pub async fn start(self: Arc<Self>, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+        let listener = TcpListener::bind(self.bind_address)
+            .await
+            .with_context(|| format!("Failed to bind metrics server to {}", self.bind_address))?;
+        info!("📊 Metrics server listening on http://{}{}", self.bind_address, METRICS_PATH);
+
+        loop {
+            tokio::select! {
+                result = listener.accept() => {
+                    match result {
+                        Ok((stream, _)) => {
+                            let server = self.clone();
+                            tokio::spawn(async move {
+                                let service = service_fn(move |request| {
+                                    let server = server.clone();
+                                    async move { Ok::<_, Infallible>(server.handle_request(request).await) }
+                                });
+                                if let Err(e) = http1::Builder::new()
+                                    .serve_connection(TokioIo::new(stream), service)
+                                    .await
+                                {
+                                    debug!("Metrics connection error: {}", e);
+                                }
+                            });
+                        }
+                        Err(e) => warn!("Failed to accept metrics connection: {}", e),
+                    }
+                }
+
+                _ = shutdown.recv() => {
+                    info!("🛑 Shutting down metrics server");
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn handle_request<B>(&self, request: Request<B>) -> Response<Full<Bytes>> {
+        let status = if request.uri().path() != METRICS_PATH {
+            StatusCode::NOT_FOUND
+        } else if request.method() != Method::GET && request.method() != Method::HEAD {
+            StatusCode::METHOD_NOT_ALLOWED
+        } else {
+            let mut response = Response::new(Full::new(Bytes::from(self.render().await)));
+            response
+                .headers_mut()
+                .insert(header::CONTENT_TYPE, HeaderValue::from_static(CONTENT_TYPE));
+            return response;
+        };
+
+        let mut response = Response::new(Full::new(Bytes::new()));
+        *response.status_mut() = status;
+        response
+    }
+
+    /// Current metrics in the text exposition format
+    pub async fn render(&self) -> String {
+        let mut out = Exposition::default();
+
+        out.header("node_info", "gauge", "Node version and network");
+        out.sample(
+            "node_info",
+            &[
+                ("version", env!("CARGO_PKG_VERSION")),
+                ("network", &self.config.network.name),
+            ],
+            1.0,
+        );
+
+        self.render_consensus(&mut out);
+        self.render_services(&mut out);
+        self.render_p2p(&mut out).await;
+        self.render_rpc(&mut out).await;
+        out.0
+    }
+
+    fn render_consensus(&self, out: &mut Exposition) {
+        let status = self.consensus.get_status();
+        let sync = self.consensus.sync_status(self.config.health.sync_tolerance_blocks);
+
+        out.header("consensus_head_height", "gauge", "Height of the latest block per layer");
+        out.sample("consensus_head_height", &[("layer", "commerce")], status.commerce_height as f64);
+        out.sample("consensus_head_height", &[("layer", "security")], status.security_height as f64);
+        out.header("consensus_epoch", "gauge", "Current epoch per layer");
+        out.sample("consensus_epoch", &[("layer", "commerce")], status.commerce_epoch as f64);
+        out.sample("consensus_epoch", &[("layer", "security")], status.security_epoch as f64);
+
+        out.gauge("consensus_finalized_epoch", "Epoch of the latest finalized checkpoint", status.finalized_epoch as f64);
+        out.gauge("consensus_finalized_height", "Security height of the latest finalized checkpoint", status.finalized_height as f64);
+        out.gauge(
+            "consensus_finality_lag_blocks",
+            "Security blocks above the latest finalized checkpoint",
+            status.security_height.saturating_sub(status.finalized_height) as f64,
+        );
+        out.gauge(
+            "consensus_finality_lag_epochs",
+            "Security epochs since the latest finalized checkpoint",
+            status.security_epoch.saturating_sub(status.finalized_epoch) as f64,
+        );
+        out.gauge("consensus_sync_blocks_behind", "Blocks behind the best head announced by peers", sync.blocks_behind as f64);
+
+        let performance = self.consensus.performance_metrics();
+        out.counter("consensus_blocks_proposed_total", "Proposer slots fulfilled", performance.blocks_proposed as f64);
+        out.counter("consensus_slots_missed_total", "Proposer slots missed", performance.blocks_missed as f64);
+        out.gauge(
+            "consensus_consecutive_missed_slots",
+            "Slots missed since the last fulfilled one",
+            self.consensus.consecutive_missed_slots() as f64,
+        );
+
+        let (made, included) = self.consensus.attestation_counts();
+        out.counter("consensus_attestations_made_total", "Attestations published by this validator", made as f64);
+        out.counter("consensus_attestations_included_total", "Own attestations included in blocks", included as f64);
+
+        out.gauge("validator_active", "Whether the validator participates in consensus", flag(status.is_active));
+        out.gauge("validator_validation_paused", "Whether validation is paused by an operator", flag(status.validation_paused));
+        out.gauge("validator_stake", "Validator stake in OGT", status.stake as f64);
+        out.gauge("validator_uptime_percent", "Share of tracked time the validator was up", status.uptime_percentage);
+        out.counter("validator_uptime_seconds_total", "Tracked uptime", performance.total_uptime as f64);
+        out.counter("validator_downtime_seconds_total", "Tracked downtime", performance.total_downtime as f64);
+    }
+
+    /// Infrastructure service counters from `PerformanceMetrics`
+    fn render_services(&self, out: &mut Exposition) {
+        let performance = self.consensus.performance_metrics();
+
+        out.counter("service_oon_jobs_completed_total", "OON computational jobs completed", performance.oon_jobs_completed as f64);
+        out.counter("service_omp_requests_served_total", "OMP storage requests served", performance.omp_requests_served as f64);
+        out.counter("service_orc20_txs_relayed_total", "ORC-20 transactions relayed", performance.orc20_txs_relayed as f64);
+        out.counter(
+            "service_paymaster_txs_sponsored_total",
+            "OEC-4337 paymaster transactions sponsored",
+            performance.paymaster_txs_sponsored as f64,
+        );
+
+        let revenue = &performance.revenue_by_service;
+        out.header("service_revenue_total", "counter", "Revenue earned per infrastructure service in base units");
+        for (service, amount) in [
+            ("oon", revenue.oon_revenue),
+            ("omp", revenue.omp_revenue),
+            ("orc20_relayer", revenue.orc20_relayer_revenue),
+            ("paymaster", revenue.paymaster_revenue),
+        ] {
+            out.sample("service_revenue_total", &[("service", service)], amount as f64);
+        }
+        out.counter(
+            "revenue_generated_total",
+            "Revenue generated for the network in base units",
+            performance.total_revenue_generated as f64,
+        );
+    }
+
+    async fn render_p2p(&self, out: &mut Exposition) {
+        let metrics = self.p2p_network.metrics().await;
+        let gossip = &metrics.gossip;
+
+        out.gauge("p2p_connected_peers", "Connected peers", metrics.connected_peers as f64);
+
+        out.header("p2p_gossip_messages_received_total", "counter", "Gossip messages received per topic");
+        for (topic, count) in &gossip.messages_received {
+            out.sample("p2p_gossip_messages_received_total", &[("topic", topic)], *count as f64);
+        }
+
+        out.header("p2p_gossip_validation_total", "counter", "Validation outcomes of received gossip messages");
+        for (outcome, count) in [("accept", gossip.accepted), ("reject", gossip.rejected), ("ignore", gossip.ignored)] {
+            out.sample("p2p_gossip_validation_total", &[("outcome", outcome)], count as f64);
+        }
+
+        out.header("p2p_bandwidth_bytes_total", "counter", "Bytes transferred through the P2P transport");
+        out.sample("p2p_bandwidth_bytes_total", &[("direction", "inbound")], metrics.bytes_inbound as f64);
+        out.sample("p2p_bandwidth_bytes_total", &[("direction", "outbound")], metrics.bytes_outbound as f64);
+    }
+
+    async fn render_rpc(&self, out: &mut Exposition) {
+        let status = match self.rpc_server.status().await {
+            Ok(status) => status,
+            Err(e) => {
+                debug!("RPC status unavailable for metrics: {}", e);
+                return;
+            }
+        };
+
+        out.counter("rpc_requests_total", "JSON-RPC calls over all transports", status.total_requests as f64);
+        out.counter("rpc_errors_total", "JSON-RPC calls answered with an error", status.total_errors as f64);
+        out.counter("rpc_rate_limited_total", "Requests rejected by the rate limiter", status.rate_limited_requests as f64);
+        out.gauge("rpc_active_connections", "Open HTTP and WebSocket connections", status.active_connections as f64);
+
+        out.header("rpc_method_calls_total", "counter", "Calls per JSON-RPC method");
+        for method in &status.methods {
+            out.sample("rpc_method_calls_total", &[("method", &method.method)], method.calls as f64);
+        }
+        out.header("rpc_method_errors_total", "counter", "Errors per JSON-RPC method");
+        for method in &status.methods {
+            out.sample("rpc_method_errors_total", &[("method", &method.method)], method.errors as f64);
+        }
+        out.header("rpc_method_duration_seconds", "summary", "Time spent handling each JSON-RPC method");
+        for method in &status.methods {
+            let labels = [("method", method.method.as_str())];
+            out.sample("rpc_method_duration_seconds_sum", &labels, method.total_latency_ms / 1000.0);
+            out.sample("rpc_method_duration_seconds_count", &labels, method.calls as f64);
+        }
+        out.header("rpc_method_duration_seconds_max", "gauge", "Slowest call per JSON-RPC method");
+        for method in &status.methods {
+            out.sample("rpc_method_duration_seconds_max", &[("method", &method.method)], method.max_latency_ms / 1000.0);
+        }
+    }
+}
+
+fn flag(value: bool) -> f64 {
+    if value {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+/// Text exposition being written
+#[derive(Default)]
+struct Exposition(String);
+
+impl Exposition {
+    /// Start the metric family `name`
+    fn header(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.0, "# HELP {}{} {}", PREFIX, name, help);
+        let _ = writeln!(self.0, "# TYPE {}{} {}", PREFIX, name, kind);
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: f64) {
+        let _ = write!(self.0, "{}{}", PREFIX, name);
+        if !labels.is_empty() {
+            let labels: Vec<String> = labels
+                .iter()
+                .map(|(key, value)| format!("{}=\"{}\"", key, escape(value)))
+                .collect();
+            let _ = write!(self.0, "{{{}}}", labels.join(","));
+        }
+        let _ = writeln!(self.0, " {}", value);
+    }
+
+    fn counter(&mut self, name: &str, help: &str, value: f64) {
+        self.header(name, "counter", help);
+        self.sample(name, &[], value);
+    }
+
+    fn gauge(&mut self, name: &str, help: &str, value: f64) {
+        self.header(name, "gauge", help);
+        self.sample(name, &[], value);
+    }
+}
+
+/// Escape a label value
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::ChainStore;
+    use crate::store::Store;
+    use crate::events::{EventBus, Layer};
+    use crate::infrastructure::InfrastructureServices;
+    use http_body_util::{BodyExt, Empty};
+
+    async fn test_server() -> MetricsServer {
+        let mut config = ValidatorConfig::new_for_network("devnet").unwrap();
+        config.data_dir = std::env::temp_dir().join(format!("omne-metrics-{}", rand::random::<u32>()));
+        let store = Store::temporary();
+        let chain = Arc::new(ChainStore::open(&store).unwrap());
+        let events = EventBus::new();
+        let consensus = Arc::new(PoVERAValidator::new(&config, chain.clone(), None, events.clone()).await.unwrap());
+        let p2p_network = Arc::new(
+            P2PNetwork::new(&config, consensus.clone(), store.db(), events.clone()).await.unwrap()
+        );
+        let infrastructure = Arc::new(InfrastructureServices::new(&config).await.unwrap());
+        let (shutdown, _) = broadcast::channel(1);
+        let rpc_server = Arc::new(
+            RpcServer::new(&config, consensus.clone(), p2p_network.clone(), infrastructure, chain, events, shutdown)
+                .await
+                .unwrap(),
+        );
+        MetricsServer::new(&config, consensus, p2p_network, rpc_server).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_render() {
+        let server = test_server().await;
+        server.consensus.record_slot_duty(Layer::Commerce, 1, false);
+        server.consensus.record_slot_duty(Layer::Commerce, 2, true);
+        server.consensus.record_attestation();
+
+        let text = server.render().await;
+        for line in text.lines() {
+            let name = line.strip_prefix("# HELP ").or(line.strip_prefix("# TYPE ")).unwrap_or(line);
+            assert!(name.starts_with(PREFIX), "unprefixed line {:?}", line);
+        }
+
+        assert!(text.contains("omne_consensus_head_height{layer=\"security\"} 0\n"));
+        assert!(text.contains("omne_consensus_blocks_proposed_total 1\n"));
+        assert!(text.contains("omne_consensus_slots_missed_total 1\n"));
+        assert!(text.contains("omne_consensus_attestations_made_total 1\n"));
+        assert!(text.contains("omne_p2p_gossip_validation_total{outcome=\"reject\"} 0\n"));
+        assert!(text.contains("omne_service_revenue_total{service=\"paymaster\"} 0\n"));
+        assert!(text.contains("# TYPE omne_rpc_method_duration_seconds summary\n"));
+    }
+
+    #[tokio::test]
+    async fn test_scrape() {
+        let server = test_server().await;
+
+        let request = |method: Method, path: &str| {
+            Request::builder().method(method).uri(path).body(Empty::<Bytes>::new()).unwrap()
+        };
+
+        let response = server.handle_request(request(Method::GET, METRICS_PATH)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], CONTENT_TYPE);
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert!(body.starts_with(b"# HELP omne_node_info"));
+
+        let response = server.handle_request(request(Method::GET, "/")).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let response = server.handle_request(request(Method::POST, METRICS_PATH)).await;
+        assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    #[test]
+    fn test_label_escaping() {
+        let mut out = Exposition::default();
+        out.sample("x", &[("topic", "a\"b\\c\nd")], 2.5);
+        assert_eq!(out.0, "omne_x{topic=\"a\\\"b\\\\c\\nd\"} 2.5\n");
+    }
+}
@@ -1,26 +1,115 @@
 //! P2P networking for Omne validator nodes
 
-use crate::config::ValidatorConfig;
-use crate::consensus::PoVERAValidator;
+mod bootstrap;
+mod gossip;
+mod identity;
+mod peer_limit;
+mod peer_store;
+mod transport;
+
+use bootstrap::{Backoff, SystemResolver};
+use gossip::GossipStats;
+use peer_store::PeerStore;
+pub use gossip::GossipCounters;
+pub use identity::load_or_create as load_or_create_network_key;
+
+use crate::config::{P2PConfig, SentryMode, ValidatorConfig};
+use crate::consensus::{ConsensusMessage, PoVERAValidator};
+use crate::events::{EventBus, NodeEvent};
 
 use anyhow::Result;
 use futures::StreamExt;
 use libp2p::{
-    gossipsub, identify, kad, mdns, noise, ping, yamux, 
-    swarm::{NetworkBehaviour, SwarmEvent},
-    tcp, Multiaddr, PeerId, Swarm, Transport,
+    allow_block_list, autonat, dcutr, gossipsub, identify, kad, mdns, ping, relay,
+    bandwidth::BandwidthSinks,
+    multiaddr::Protocol,
+    swarm::{
+        behaviour::toggle::Toggle,
+        dial_opts::{DialOpts, PeerCondition},
+        ConnectionId, DialError, NetworkBehaviour, SwarmEvent,
+    },
+    Multiaddr, PeerId, Swarm,
 };
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
 use std::time::Duration;
-use tokio::sync::{broadcast, Mutex};
+use tokio::sync::{broadcast, mpsc, oneshot, Mutex, RwLock};
 use tracing::{info, debug, warn};
 
 /// P2P network implementation for Omne validators
 pub struct P2PNetwork {
     config: ValidatorConfig,
     consensus: Arc<PoVERAValidator>,
+    /// Identity the swarm runs under and signs gossip with
+    local_key: libp2p::identity::Keypair,
     swarm: Mutex<Option<Swarm<ValidatorNetworkBehaviour>>>,
+    state: RwLock<NetworkState>,
+    /// Trusted sentries this private validator exclusively peers with
+    sentries: Vec<(PeerId, Multiaddr)>,
+    /// Private validators shielded by this sentry
+    private_peers: HashSet<PeerId>,
+    /// Peer limit shared with the swarm's `peer_limit` behaviour, which a
+    /// config reload may change
+    max_peers: Arc<AtomicUsize>,
+    /// Peers remembered across restarts (not used by private validators)
+    peer_store: Option<PeerStore>,
+    events: EventBus,
+    /// Operator commands for the event loop
+    commands: mpsc::UnboundedSender<P2PCommand>,
+    command_rx: Mutex<Option<mpsc::UnboundedReceiver<P2PCommand>>>,
+    /// Received gossip and validation outcomes
+    gossip: GossipStats,
+    /// Bytes through the transport, set once the swarm is built
+    bandwidth: OnceLock<Arc<BandwidthSinks>>,
+}
+
+/// Operator command executed by the event loop, which owns the swarm
+enum P2PCommand {
+    Dial {
+        address: Multiaddr,
+        reply: oneshot::Sender<Result<()>>,
+    },
+    Disconnect {
+        peer_id: PeerId,
+        reply: oneshot::Sender<Result<bool>>,
+    },
+    Ban {
+        peer_id: PeerId,
+        reply: oneshot::Sender<Result<()>>,
+    },
+}
+
+/// Interval for redialing sentries and other connection upkeep
+const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Peer store capacity as a multiple of `max_peers`
+const PEER_STORE_CAPACITY_FACTOR: usize = 4;
+
+/// Bootstrap retry backoff bounds
+const BOOTSTRAP_INITIAL_BACKOFF: Duration = Duration::from_secs(5);
+const BOOTSTRAP_MAX_BACKOFF: Duration = Duration::from_secs(300);
+
+/// Snapshot of the swarm maintained by the event loop for status queries
+#[derive(Debug, Default)]
+struct NetworkState {
+    local_peer_id: Option<PeerId>,
+    listen_addresses: Vec<Multiaddr>,
+    external_addresses: Vec<Multiaddr>,
+    reachability: Reachability,
+    /// Whether listening through at least one configured relay succeeded;
+    /// until then the relays are tried again whenever AutoNAT reports us private
+    relay_listening: bool,
+    /// Backoff between bootstrap rounds while the node has no peers
+    bootstrap_backoff: Option<Backoff>,
+    /// When the next bootstrap round is due, if one is scheduled
+    next_bootstrap: Option<tokio::time::Instant>,
+    /// Established connections per peer with their remote address
+    connections: HashMap<PeerId, Vec<(ConnectionId, Multiaddr)>>,
+    /// Current bootstrap list, which a config reload may change
+    bootstrap_peers: Vec<String>,
 }
 
 // NOTE: Removed unsafe Send + Sync implementations for security.
@@ -33,9 +122,19 @@ pub struct P2PNetwork {
 pub struct ValidatorNetworkBehaviour {
     pub ping: ping::Behaviour,
     pub identify: identify::Behaviour,  
-    pub kad: kad::Behaviour<kad::store::MemoryStore>,
+    pub kad: Toggle<kad::Behaviour<kad::store::MemoryStore>>,
     pub gossipsub: gossipsub::Behaviour,
-    pub mdns: mdns::tokio::Behaviour,
+    pub mdns: Toggle<mdns::tokio::Behaviour>,
+    /// Restricts a private validator to its sentries
+    pub sentry_allowlist: Toggle<allow_block_list::Behaviour<allow_block_list::AllowedPeers>>,
+    /// Peers banned by the operator
+    pub banned_peers: allow_block_list::Behaviour<allow_block_list::BlockedPeers>,
+    /// Refuses peers beyond `max_peers`, except sentries and shielded validators
+    pub peer_limit: peer_limit::Behaviour,
+    pub autonat: Toggle<autonat::Behaviour>,
+    pub relay_client: relay::client::Behaviour,
+    pub relay_server: Toggle<relay::Behaviour>,
+    pub dcutr: dcutr::Behaviour,
 }
 
 /// Events from the validator network behaviour
@@ -46,6 +145,10 @@ pub enum ValidatorNetworkBehaviourEvent {
     Kad(kad::Event),
     Gossipsub(gossipsub::Event),
     Mdns(mdns::Event),
+    Autonat(autonat::Event),
+    RelayClient(relay::client::Event),
+    RelayServer(relay::Event),
+    Dcutr(dcutr::Event),
 }
 
 impl From<ping::Event> for ValidatorNetworkBehaviourEvent {
@@ -78,49 +181,169 @@ impl From<mdns::Event> for ValidatorNetworkBehaviourEvent {
     }
 }
 
+impl From<void::Void> for ValidatorNetworkBehaviourEvent {
+    fn from(event: void::Void) -> Self {
+        void::unreachable(event)
+    }
+}
+
+impl From<autonat::Event> for ValidatorNetworkBehaviourEvent {
+    fn from(event: autonat::Event) -> Self {
+        ValidatorNetworkBehaviourEvent::Autonat(event)
+    }
+}
+
+impl From<relay::client::Event> for ValidatorNetworkBehaviourEvent {
+    fn from(event: relay::client::Event) -> Self {
+        ValidatorNetworkBehaviourEvent::RelayClient(event)
+    }
+}
+
+impl From<relay::Event> for ValidatorNetworkBehaviourEvent {
+    fn from(event: relay::Event) -> Self {
+        ValidatorNetworkBehaviourEvent::RelayServer(event)
+    }
+}
+
+impl From<dcutr::Event> for ValidatorNetworkBehaviourEvent {
+    fn from(event: dcutr::Event) -> Self {
+        ValidatorNetworkBehaviourEvent::Dcutr(event)
+    }
+}
+
+/// Reachability of this node as determined by AutoNAT
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Reachability {
+    #[default]
+    Unknown,
+    Public,
+    Private,
+}
+
 /// P2P network status
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct P2PStatus {
     pub local_peer_id: String,
     pub connected_peers: usize,
+    /// Peer limit in effect; sentries and shielded validators are not counted against it
+    #[serde(default)]
+    pub max_peers: usize,
     pub listening_addresses: Vec<String>,
+    pub external_addresses: Vec<String>,
+    pub reachability: Reachability,
+    pub sentry_mode: SentryMode,
     pub network_id: u64,
     pub gossipsub_topics: Vec<String>,
 }
 
+/// Counters exported as P2P metrics
+#[derive(Debug, Clone)]
+pub struct P2PMetrics {
+    pub connected_peers: usize,
+    pub gossip: GossipCounters,
+    pub bytes_inbound: u64,
+    pub bytes_outbound: u64,
+}
+
+/// A currently connected peer
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ConnectedPeer {
+    pub peer_id: String,
+    pub addresses: Vec<String>,
+}
+
 impl P2PNetwork {
     /// Create a new P2P network
     pub async fn new(
         config: &ValidatorConfig, 
-        consensus: Arc<PoVERAValidator>
+        consensus: Arc<PoVERAValidator>,
+        db: &sled::Db,
+        events: EventBus,
     ) -> Result<Self> {
         info!("🌐 Initializing P2P network");
         info!("   Network: {} (ID: {})", config.network.name, config.network.id);
         info!("   P2P Port: {}", config.p2p.port);
         info!("   Max Peers: {}", config.p2p.max_peers);
         info!("   Bootstrap Peers: {}", config.p2p.bootstrap_peers.len());
+        info!("   Transports: TCP={} QUIC={}", config.p2p.enable_tcp, config.p2p.enable_quic);
+
+        config.p2p.validate_sentry()?;
+        let (sentries, private_peers) = match config.p2p.sentry.mode {
+            SentryMode::Disabled => (Vec::new(), HashSet::new()),
+            SentryMode::Validator => {
+                info!("   Sentry Mode: private validator behind {} sentries", config.p2p.sentry.sentry_addresses.len());
+                (config.p2p.sentry_peers()?, HashSet::new())
+            }
+            SentryMode::Sentry => {
+                info!("   Sentry Mode: shielding {} private validators", config.p2p.sentry.private_peer_ids.len());
+                (Vec::new(), config.p2p.private_peers()?.into_iter().collect())
+            }
+        };
+
+        let peer_store = if config.p2p.is_private_validator() {
+            None
+        } else {
+            let store = PeerStore::open(db, config.p2p.max_peers * PEER_STORE_CAPACITY_FACTOR)?;
+            info!("   Known Peers: {}", store.load()?.len());
+            Some(store)
+        };
+
+        let local_key = identity::load_or_create(&config.network_key_path())?;
+        let (commands, command_rx) = mpsc::unbounded_channel();
 
         Ok(Self {
             config: config.clone(),
             consensus,
+            local_key,
             swarm: Mutex::new(None),
+            state: RwLock::new(NetworkState {
+                bootstrap_peers: config.p2p.bootstrap_peers.clone(),
+                ..NetworkState::default()
+            }),
+            sentries,
+            private_peers,
+            max_peers: Arc::new(AtomicUsize::new(config.p2p.max_peers)),
+            peer_store,
+            events,
+            commands,
+            command_rx: Mutex::new(Some(command_rx)),
+            gossip: GossipStats::default(),
+            bandwidth: OnceLock::new(),
         })
     }
 
     /// Initialize the libp2p swarm
     async fn init_swarm(&self) -> Result<()> {
-        // Create a random key for this node
-        let local_key = libp2p::identity::Keypair::generate_ed25519();
+        let local_key = &self.local_key;
         let local_peer_id = PeerId::from(local_key.public());
 
         info!("🆔 Local Peer ID: {}", local_peer_id);
 
         // Create transport
-        let transport = tcp::tokio::Transport::default()
-            .upgrade(libp2p::core::upgrade::Version::V1)
-            .authenticate(noise::Config::new(&local_key)?)
-            .multiplex(yamux::Config::default())
-            .boxed();
+        let (relay_transport, relay_client) = relay::client::new(local_peer_id);
+        let (transport, bandwidth) = transport::build_transport(local_key, &self.config.p2p, relay_transport)?;
+        let _ = self.bandwidth.set(bandwidth);
+
+        // A private validator stays off discovery and only talks to its sentries
+        let private_validator = self.config.p2p.is_private_validator();
+        let enable_kad = self.config.p2p.enable_kad && !private_validator;
+        let enable_mdns = self.config.p2p.enable_mdns && !private_validator;
+        let enable_autonat = self.config.p2p.enable_autonat && !private_validator;
+
+        let sentry_allowlist = private_validator.then(|| {
+            let mut allowlist = allow_block_list::Behaviour::<allow_block_list::AllowedPeers>::default();
+            for (peer_id, _) in &self.sentries {
+                allowlist.allow_peer(*peer_id);
+            }
+            allowlist
+        });
+
+        let mut gossipsub = self.create_gossipsub_behaviour()?;
+        for peer_id in self.sentries.iter().map(|(id, _)| id).chain(&self.private_peers) {
+            // Explicit peers always receive our gossip regardless of mesh state
+            gossipsub.add_explicit_peer(peer_id);
+        }
 
         // Create network behaviour
         let behaviour = ValidatorNetworkBehaviour {
@@ -129,15 +352,35 @@ impl P2PNetwork {
                 "/omne/validator/1.0.0".to_string(),
                 local_key.public(),
             )),
-            kad: kad::Behaviour::new(
-                local_peer_id,
-                kad::store::MemoryStore::new(local_peer_id),
+            kad: Toggle::from(enable_kad.then(|| {
+                kad::Behaviour::new(
+                    local_peer_id,
+                    kad::store::MemoryStore::new(local_peer_id),
+                )
+            })),
+            gossipsub,
+            mdns: Toggle::from(if enable_mdns {
+                Some(mdns::tokio::Behaviour::new(
+                    mdns::Config::default(),
+                    local_peer_id,
+                )?)
+            } else {
+                None
+            }),
+            sentry_allowlist: Toggle::from(sentry_allowlist),
+            banned_peers: allow_block_list::Behaviour::default(),
+            peer_limit: peer_limit::Behaviour::new(
+                self.max_peers.clone(),
+                self.sentries.iter().map(|(id, _)| *id).chain(self.private_peers.iter().copied()).collect(),
             ),
-            gossipsub: self.create_gossipsub_behaviour()?,
-            mdns: mdns::tokio::Behaviour::new(
-                mdns::Config::default(),
-                local_peer_id,
-            )?,
+            autonat: Toggle::from(enable_autonat.then(|| {
+                autonat::Behaviour::new(local_peer_id, autonat::Config::default())
+            })),
+            relay_client,
+            relay_server: Toggle::from(self.config.p2p.enable_relay_server.then(|| {
+                relay::Behaviour::new(local_peer_id, relay::Config::default())
+            })),
+            dcutr: dcutr::Behaviour::new(local_peer_id),
         };
 
         // Create swarm
@@ -148,20 +391,38 @@ impl P2PNetwork {
             libp2p::swarm::Config::with_tokio_executor()
         );
 
-        // Listen on configured port
-        let listen_addr: Multiaddr = format!("/ip4/0.0.0.0/tcp/{}", self.config.p2p.port)
-            .parse()?;
-        swarm.listen_on(listen_addr)?;
+        // Listen on every enabled transport
+        for listen_addr in transport::listen_addresses(&self.config.p2p) {
+            swarm.listen_on(listen_addr)?;
+        }
 
-        // Connect to bootstrap peers
-        for peer_addr in &self.config.p2p.bootstrap_peers {
-            if let Ok(addr) = peer_addr.parse::<Multiaddr>() {
-                if let Err(e) = swarm.dial(addr) {
-                    warn!("Failed to dial bootstrap peer {}: {}", peer_addr, e);
+        // Announce operator-configured external addresses
+        for addr in &self.config.p2p.external_addresses {
+            match addr.parse::<Multiaddr>() {
+                Ok(addr) => {
+                    swarm.add_external_address(addr.clone());
+                    self.state.write().await.external_addresses.push(addr);
                 }
+                Err(e) => warn!("Invalid external address {}: {}", addr, e),
             }
         }
 
+        if private_validator {
+            // Sentries are our only peers; bootstrap peers are never dialed
+            self.dial_sentries(&mut swarm).await;
+        } else {
+            // Without AutoNAT we cannot tell whether we are reachable, so reserve
+            // relay slots straight away when relays are configured
+            if !enable_autonat {
+                self.listen_via_relays(&mut swarm).await;
+            }
+
+            // Prefer peers that worked before, then fall back to bootstrap peers
+            self.dial_known_peers(&mut swarm, self.max_peers.load(Ordering::Relaxed));
+            self.dial_bootstrap_peers(&mut swarm).await;
+        }
+
+        self.state.write().await.local_peer_id = Some(local_peer_id);
         *self.swarm.lock().await = Some(swarm);
         Ok(())
     }
@@ -171,6 +432,8 @@ impl P2PNetwork {
         let gossipsub_config = gossipsub::ConfigBuilder::default()
             .heartbeat_interval(Duration::from_millis(1000))
             .validation_mode(gossipsub::ValidationMode::Strict)
+            // Messages are forwarded only once `handle_gossipsub_event` accepts them
+            .validate_messages()
             .message_id_fn(|message| {
                 use sha2::{Digest, Sha256};
                 let mut hasher = Sha256::new();
@@ -182,21 +445,13 @@ impl P2PNetwork {
             .map_err(|e| anyhow::anyhow!("Failed to build gossipsub config: {:?}", e))?;
 
         let mut gossipsub = gossipsub::Behaviour::new(
-            gossipsub::MessageAuthenticity::Signed(libp2p::identity::Keypair::generate_ed25519()),
+            gossipsub::MessageAuthenticity::Signed(self.local_key.clone()),
             gossipsub_config,
         )
         .map_err(|e| anyhow::anyhow!("Failed to create gossipsub behaviour: {:?}", e))?;
 
         // Subscribe to consensus topics
-        let network_id = self.config.network.id;
-        let topics = vec![
-            format!("omne/consensus/commerce/{}", network_id),
-            format!("omne/consensus/security/{}", network_id),
-            format!("omne/transactions/{}", network_id),
-            format!("omne/attestations/{}", network_id),
-        ];
-
-        for topic_name in topics {
+        for topic_name in gossip::topics(self.config.network.id) {
             let topic = gossipsub::IdentTopic::new(&topic_name);
             gossipsub.subscribe(&topic)?;
             info!("📡 Subscribed to topic: {}", topic_name);
@@ -211,24 +466,47 @@ impl P2PNetwork {
 
         // Initialize swarm
         self.init_swarm().await?;
-        
+
+        // The event loop owns the swarm for its whole lifetime; status queries
+        // read the `NetworkState` snapshot instead.
+        let mut swarm_guard = self.swarm.lock().await;
+        let swarm = swarm_guard
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("Swarm not initialized"))?;
+
+        let mut commands = self
+            .command_rx
+            .lock()
+            .await
+            .take()
+            .ok_or_else(|| anyhow::anyhow!("P2P network already started"))?;
+        let mut outbound = self
+            .consensus
+            .take_outbound()
+            .ok_or_else(|| anyhow::anyhow!("Consensus messages already published by another network"))?;
+        let mut maintenance = tokio::time::interval(MAINTENANCE_INTERVAL);
+
         // Main network event loop
         loop {
             tokio::select! {
-                event = async {
-                    let mut swarm_guard = self.swarm.lock().await;
-                    if let Some(swarm) = swarm_guard.as_mut() {
-                        swarm.next().await
-                    } else {
-                        None
-                    }
-                } => {
-                    if let Some(event) = event {
-                        if let Err(e) = Self::handle_swarm_event_static(event, &self.consensus).await {
-                            warn!("Error handling swarm event: {}", e);
-                        }
+                event = swarm.select_next_some() => {
+                    if let Err(e) = self.handle_swarm_event(swarm, event).await {
+                        warn!("Error handling swarm event: {}", e);
                     }
                 }
+
+                Some(command) = commands.recv() => {
+                    self.handle_command(swarm, command);
+                }
+
+                Some(message) = outbound.recv() => {
+                    self.publish(swarm, message);
+                }
+
+                _ = maintenance.tick() => {
+                    self.dial_sentries(swarm).await;
+                    self.retry_bootstrap(swarm).await;
+                }
                 
                 _ = shutdown.recv() => {
                     info!("🛑 Shutting down P2P network");
@@ -240,111 +518,460 @@ impl P2PNetwork {
         Ok(())
     }
 
-    /// Handle libp2p swarm events (static version to avoid borrow issues)
-    async fn handle_swarm_event_static(
-        event: SwarmEvent<ValidatorNetworkBehaviourEvent, impl std::error::Error>, 
-        consensus: &Arc<PoVERAValidator>
+    /// Execute an operator command against the swarm
+    fn handle_command(&self, swarm: &mut Swarm<ValidatorNetworkBehaviour>, command: P2PCommand) {
+        match command {
+            P2PCommand::Dial { address, reply } => {
+                info!("➕ Adding peer {}", address);
+                if let Some(Protocol::P2p(peer_id)) = address.iter().last() {
+                    if let Some(kad) = swarm.behaviour_mut().kad.as_mut() {
+                        kad.add_address(&peer_id, address.clone());
+                    }
+                }
+                let result = swarm
+                    .dial(address)
+                    .map_err(|e| anyhow::anyhow!("Failed to dial peer: {}", e));
+                let _ = reply.send(result);
+            }
+            P2PCommand::Disconnect { peer_id, reply } => {
+                info!("➖ Removing peer {}", peer_id);
+                let was_connected = swarm.disconnect_peer_id(peer_id).is_ok();
+                self.forget_peer(swarm, &peer_id);
+                let _ = reply.send(Ok(was_connected));
+            }
+            P2PCommand::Ban { peer_id, reply } => {
+                warn!("⛔ Banning peer {}", peer_id);
+                // Blocking also closes any open connections to the peer
+                swarm.behaviour_mut().banned_peers.block_peer(peer_id);
+                self.forget_peer(swarm, &peer_id);
+                let _ = reply.send(Ok(()));
+            }
+        }
+    }
+
+    /// Publish a block or attestation of our validator on its topic
+    fn publish(&self, swarm: &mut Swarm<ValidatorNetworkBehaviour>, message: ConsensusMessage) {
+        let topic = gossipsub::IdentTopic::new(gossip::topic_for(self.config.network.id, &message));
+        let result = message
+            .encode()
+            .and_then(|data| swarm.behaviour_mut().gossipsub.publish(topic.clone(), data).map_err(Into::into));
+        if let Err(e) = result {
+            // Without peers on the topic there is nobody to tell; not an error
+            debug!("Failed to publish on {}: {}", topic, e);
+        }
+    }
+
+    /// Drop a peer from discovery and the peer store so it is not redialed
+    fn forget_peer(&self, swarm: &mut Swarm<ValidatorNetworkBehaviour>, peer_id: &PeerId) {
+        if let Some(kad) = swarm.behaviour_mut().kad.as_mut() {
+            kad.remove_peer(peer_id);
+        }
+        self.with_peer_store(peer_id, |store| store.remove(peer_id));
+    }
+
+    /// Send a command to the event loop and wait for its reply
+    async fn send_command<T>(&self, command: impl FnOnce(oneshot::Sender<Result<T>>) -> P2PCommand) -> Result<T> {
+        let (reply, response) = oneshot::channel();
+        self.commands
+            .send(command(reply))
+            .map_err(|_| anyhow::anyhow!("P2P network is not running"))?;
+        response
+            .await
+            .map_err(|_| anyhow::anyhow!("P2P network is not running"))?
+    }
+
+    /// Dial a peer at `address`
+    pub async fn add_peer(&self, address: Multiaddr) -> Result<()> {
+        self.send_command(|reply| P2PCommand::Dial { address, reply }).await
+    }
+
+    /// Disconnect and forget a peer; returns whether it was connected
+    pub async fn remove_peer(&self, peer_id: PeerId) -> Result<bool> {
+        self.send_command(|reply| P2PCommand::Disconnect { peer_id, reply }).await
+    }
+
+    /// Disconnect a peer and refuse future connections from it
+    pub async fn ban_peer(&self, peer_id: PeerId) -> Result<()> {
+        self.send_command(|reply| P2PCommand::Ban { peer_id, reply }).await
+    }
+
+    /// Apply a reloaded bootstrap list and peer limit. A node with no peers
+    /// tries the new bootstrap list straight away; otherwise it is used from
+    /// the next bootstrap round. A lower limit refuses new peers until enough
+    /// existing ones disconnect.
+    pub async fn reconfigure(&self, config: &P2PConfig) {
+        self.max_peers.store(config.max_peers, Ordering::Relaxed);
+        let mut state = self.state.write().await;
+        if state.bootstrap_peers != config.bootstrap_peers {
+            state.bootstrap_peers = config.bootstrap_peers.clone();
+            if state.connections.is_empty() && !self.config.p2p.is_private_validator() {
+                state.next_bootstrap = Some(tokio::time::Instant::now());
+            }
+        }
+    }
+
+    /// Handle libp2p swarm events
+    async fn handle_swarm_event(
+        &self,
+        swarm: &mut Swarm<ValidatorNetworkBehaviour>,
+        event: SwarmEvent<ValidatorNetworkBehaviourEvent, impl std::error::Error>,
     ) -> Result<()> {
         match event {
             SwarmEvent::NewListenAddr { address, .. } => {
                 info!("🎧 Listening on: {}", address);
+                self.state.write().await.listen_addresses.push(address);
+            }
+            SwarmEvent::ExpiredListenAddr { address, .. } => {
+                self.state.write().await.listen_addresses.retain(|a| a != &address);
             }
             SwarmEvent::Behaviour(ValidatorNetworkBehaviourEvent::Ping(event)) => {
                 debug!("🏓 Ping event: {:?}", event);
             }
             SwarmEvent::Behaviour(ValidatorNetworkBehaviourEvent::Identify(event)) => {
                 debug!("🆔 Identify event: {:?}", event);
+                if let identify::Event::Received { peer_id, info } = event {
+                    self.with_peer_store(&peer_id, |store| {
+                        let protocols = info.protocols.iter().map(|p| p.to_string()).collect();
+                        store.record_identify(&peer_id, &info.listen_addrs, protocols)
+                    });
+                    self.prefer_quic(swarm, peer_id, &info.listen_addrs).await;
+                }
             }
             SwarmEvent::Behaviour(ValidatorNetworkBehaviourEvent::Kad(event)) => {
                 debug!("🗺️  Kademlia event: {:?}", event);
             }
             SwarmEvent::Behaviour(ValidatorNetworkBehaviourEvent::Gossipsub(event)) => {
-                Self::handle_gossipsub_event_static(event, consensus).await?;
+                self.handle_gossipsub_event(swarm, event).await?;
             }
             SwarmEvent::Behaviour(ValidatorNetworkBehaviourEvent::Mdns(event)) => {
                 debug!("🔍 mDNS event: {:?}", event);
             }
-            SwarmEvent::ConnectionEstablished { peer_id, .. } => {
-                info!("🤝 Connected to peer: {}", peer_id);
+            SwarmEvent::Behaviour(ValidatorNetworkBehaviourEvent::Autonat(event)) => {
+                debug!("🧭 AutoNAT event: {:?}", event);
+                if let autonat::Event::StatusChanged { new, .. } = event {
+                    self.handle_nat_status(swarm, new).await;
+                }
+            }
+            SwarmEvent::Behaviour(ValidatorNetworkBehaviourEvent::RelayClient(event)) => {
+                match event {
+                    relay::client::Event::ReservationReqAccepted { relay_peer_id, .. } => {
+                        info!("🔁 Relay reservation accepted by {}", relay_peer_id);
+                    }
+                    relay::client::Event::ReservationReqFailed { relay_peer_id, error, .. } => {
+                        warn!("Relay reservation with {} failed: {}", relay_peer_id, error);
+                    }
+                    event => debug!("🔁 Relay client event: {:?}", event),
+                }
+            }
+            SwarmEvent::Behaviour(ValidatorNetworkBehaviourEvent::RelayServer(event)) => {
+                debug!("🔁 Relay server event: {:?}", event);
             }
-            SwarmEvent::ConnectionClosed { peer_id, cause, .. } => {
+            SwarmEvent::Behaviour(ValidatorNetworkBehaviourEvent::Dcutr(event)) => {
+                match event {
+                    dcutr::Event::DirectConnectionUpgradeSucceeded { remote_peer_id } => {
+                        info!("🕳️  Hole punch to {} succeeded", remote_peer_id);
+                    }
+                    event => debug!("🕳️  DCUtR event: {:?}", event),
+                }
+            }
+            SwarmEvent::ConnectionEstablished { peer_id, connection_id, endpoint, .. } => {
+                let address = endpoint.get_remote_address().clone();
+                info!("🤝 Connected to peer: {} via {}", peer_id, address);
+
+                // Only remember addresses we dialed; inbound ones are ephemeral ports
+                if endpoint.is_dialer() {
+                    self.with_peer_store(&peer_id, |store| store.record_connected(&peer_id, &address));
+                }
+
+                // Sentries never advertise the validators they shield
+                if self.private_peers.contains(&peer_id) {
+                    if let Some(kad) = swarm.behaviour_mut().kad.as_mut() {
+                        kad.remove_peer(&peer_id);
+                    }
+                }
+
+                let mut state = self.state.write().await;
+                let connections = state.connections.entry(peer_id).or_default();
+                if connections.is_empty() {
+                    self.events.publish(NodeEvent::PeerConnected { peer_id: peer_id.to_string() });
+                }
+
+                // Once a QUIC connection is up, drop the redundant TCP ones
+                if transport::is_quic(&address) {
+                    for (id, addr) in connections.iter() {
+                        if !transport::is_quic(addr) {
+                            debug!("Closing TCP connection to {} in favour of QUIC", peer_id);
+                            swarm.close_connection(*id);
+                        }
+                    }
+                }
+                connections.push((connection_id, address));
+            }
+            SwarmEvent::OutgoingConnectionError { peer_id: Some(peer_id), error: DialError::Denied { cause, .. }, .. } => {
+                // Refused by us, typically at the peer limit; the peer did nothing wrong
+                debug!("Not connecting to {}: {}", peer_id, cause);
+            }
+            SwarmEvent::OutgoingConnectionError { peer_id: Some(peer_id), error, .. } => {
+                debug!("Failed to connect to {}: {}", peer_id, error);
+                self.with_peer_store(&peer_id, |store| store.record_failure(&peer_id));
+            }
+            SwarmEvent::ConnectionClosed { peer_id, connection_id, cause, .. } => {
                 info!("👋 Disconnected from peer: {} (cause: {:?})", peer_id, cause);
+
+                let mut state = self.state.write().await;
+                if let Some(connections) = state.connections.get_mut(&peer_id) {
+                    connections.retain(|(id, _)| *id != connection_id);
+                    if connections.is_empty() {
+                        state.connections.remove(&peer_id);
+                        self.consensus.forget_peer_head(&peer_id.to_string());
+                        self.events.publish(NodeEvent::PeerDisconnected { peer_id: peer_id.to_string() });
+                    }
+                }
             }
             _ => {}
         }
         Ok(())
     }
 
-    /// Handle libp2p swarm events
-    async fn handle_swarm_event(&self, event: SwarmEvent<ValidatorNetworkBehaviourEvent, impl std::error::Error>) -> Result<()> {
-        match event {
-            SwarmEvent::NewListenAddr { address, .. } => {
-                info!("🎧 Listening on: {}", address);
+    /// Resolve and dial the bootstrap list, scheduling the next retry round
+    async fn dial_bootstrap_peers(&self, swarm: &mut Swarm<ValidatorNetworkBehaviour>) {
+        let bootstrap_peers = self.state.read().await.bootstrap_peers.clone();
+        if bootstrap_peers.is_empty() {
+            return;
+        }
+
+        let addresses = match SystemResolver::new() {
+            Ok(resolver) => bootstrap::expand_bootstrap_peers(&bootstrap_peers, &resolver).await,
+            Err(e) => {
+                warn!("DNS resolver unavailable, /dnsaddr/ bootstrap peers are skipped: {}", e);
+                bootstrap_peers
+                    .iter()
+                    .filter_map(|peer| peer.parse().ok())
+                    .collect()
             }
-            SwarmEvent::Behaviour(ValidatorNetworkBehaviourEvent::Ping(event)) => {
-                debug!("🏓 Ping event: {:?}", event);
+        };
+
+        debug!("🥾 Dialing {} bootstrap addresses", addresses.len());
+        for addr in addresses {
+            if let Err(e) = swarm.dial(addr.clone()) {
+                warn!("Failed to dial bootstrap peer {}: {}", addr, e);
             }
-            SwarmEvent::Behaviour(ValidatorNetworkBehaviourEvent::Identify(event)) => {
-                debug!("🆔 Identify event: {:?}", event);
+        }
+
+        let mut state = self.state.write().await;
+        let backoff = state.bootstrap_backoff.get_or_insert_with(|| {
+            Backoff::new(BOOTSTRAP_INITIAL_BACKOFF, BOOTSTRAP_MAX_BACKOFF)
+        });
+        let delay = backoff.next_delay();
+        state.next_bootstrap = Some(tokio::time::Instant::now() + delay);
+    }
+
+    /// Retry bootstrapping with backoff for as long as the node has no peers
+    async fn retry_bootstrap(&self, swarm: &mut Swarm<ValidatorNetworkBehaviour>) {
+        let max_peers = {
+            let mut state = self.state.write().await;
+            if !state.connections.is_empty() {
+                if let Some(backoff) = state.bootstrap_backoff.as_mut() {
+                    backoff.reset();
+                }
+                state.next_bootstrap = None;
+                return;
             }
-            SwarmEvent::Behaviour(ValidatorNetworkBehaviourEvent::Kad(event)) => {
-                debug!("🗺️  Kademlia event: {:?}", event);
+
+            match state.next_bootstrap {
+                Some(due) if tokio::time::Instant::now() < due => return,
+                // Peers dropped to zero after a successful bootstrap: start over
+                None if state.bootstrap_backoff.is_some() => {}
+                None => return,
+                Some(_) => {
+                    let attempt = state.bootstrap_backoff.as_ref().map(Backoff::attempt).unwrap_or(0);
+                    warn!(
+                        "⚠️  None of the {} bootstrap peers could be reached (attempt {}), retrying",
+                        state.bootstrap_peers.len(),
+                        attempt
+                    );
+                }
             }
-            SwarmEvent::Behaviour(ValidatorNetworkBehaviourEvent::Gossipsub(event)) => {
-                self.handle_gossipsub_event(event).await?;
+            self.max_peers.load(Ordering::Relaxed)
+        };
+
+        self.dial_known_peers(swarm, max_peers);
+        self.dial_bootstrap_peers(swarm).await;
+    }
+
+    /// Dial up to `limit` of the best previously good peers from the peer store
+    fn dial_known_peers(&self, swarm: &mut Swarm<ValidatorNetworkBehaviour>, limit: usize) {
+        let Some(store) = &self.peer_store else {
+            return;
+        };
+
+        let peers = match store.best_peers(limit) {
+            Ok(peers) => peers,
+            Err(e) => {
+                warn!("Failed to load known peers: {}", e);
+                return;
             }
-            SwarmEvent::Behaviour(ValidatorNetworkBehaviourEvent::Mdns(event)) => {
-                debug!("🔍 mDNS event: {:?}", event);
+        };
+
+        info!("📒 Dialing {} known peers", peers.len());
+        for (peer_id, record) in peers {
+            let addresses = record.multiaddrs();
+            if let Some(kad) = swarm.behaviour_mut().kad.as_mut() {
+                for addr in &addresses {
+                    kad.add_address(&peer_id, addr.clone());
+                }
             }
-            SwarmEvent::ConnectionEstablished { peer_id, .. } => {
-                info!("🤝 Connected to peer: {}", peer_id);
+            let opts = DialOpts::peer_id(peer_id).addresses(addresses).build();
+            if let Err(e) = swarm.dial(opts) {
+                debug!("Failed to dial known peer {}: {}", peer_id, e);
             }
-            SwarmEvent::ConnectionClosed { peer_id, cause, .. } => {
-                info!("👋 Disconnected from peer: {} (cause: {:?})", peer_id, cause);
+        }
+    }
+
+    /// Run a peer store update, logging rather than propagating failures
+    fn with_peer_store(&self, peer_id: &PeerId, f: impl FnOnce(&PeerStore) -> Result<()>) {
+        if self.private_peers.contains(peer_id) {
+            return;
+        }
+        if let Some(store) = &self.peer_store {
+            if let Err(e) = f(store) {
+                warn!("Failed to update peer store for {}: {}", peer_id, e);
             }
-            _ => {}
         }
-        Ok(())
     }
 
-    /// Handle gossipsub events (consensus messages) - static version
-    async fn handle_gossipsub_event_static(
-        event: gossipsub::Event, 
-        _consensus: &Arc<PoVERAValidator>
-    ) -> Result<()> {
-        match event {
-            gossipsub::Event::Message { 
-                propagation_source, 
-                message_id, 
-                message 
-            } => {
-                debug!(
-                    "📨 Received message {} from {} on topic {}",
-                    message_id,
-                    propagation_source,
-                    message.topic
-                );
-                
-                // TODO: Route message to consensus validator for processing
-                // This is where we'd handle:
-                // - Commerce block proposals
-                // - Security block proposals  
-                // - Attestations
-                // - Transaction broadcasts
+    /// Redial any trusted sentry we are not connected to (private validator only)
+    async fn dial_sentries(&self, swarm: &mut Swarm<ValidatorNetworkBehaviour>) {
+        let state = self.state.read().await;
+        for (peer_id, addr) in &self.sentries {
+            if state.connections.contains_key(peer_id) {
+                continue;
             }
-            gossipsub::Event::Subscribed { peer_id, topic } => {
-                debug!("📡 Peer {} subscribed to topic {}", peer_id, topic);
+            debug!("🛡️  Dialing sentry {}", peer_id);
+            let opts = DialOpts::peer_id(*peer_id)
+                .addresses(vec![addr.clone()])
+                .condition(PeerCondition::NotDialing)
+                .build();
+            if let Err(e) = swarm.dial(opts) {
+                warn!("Failed to dial sentry {}: {}", addr, e);
             }
-            gossipsub::Event::Unsubscribed { peer_id, topic } => {
-                debug!("📡 Peer {} unsubscribed from topic {}", peer_id, topic);
+        }
+    }
+
+    /// Track AutoNAT status changes and fall back to relays when private
+    async fn handle_nat_status(
+        &self,
+        swarm: &mut Swarm<ValidatorNetworkBehaviour>,
+        status: autonat::NatStatus,
+    ) {
+        let reachability = match status {
+            autonat::NatStatus::Public(address) => {
+                info!("🧭 Node is publicly reachable at {}", address);
+                let mut state = self.state.write().await;
+                if !state.external_addresses.contains(&address) {
+                    state.external_addresses.push(address);
+                }
+                Reachability::Public
             }
-            _ => {}
+            autonat::NatStatus::Private => {
+                warn!("🧭 Node is behind NAT, inbound connections require a relay");
+                Reachability::Private
+            }
+            autonat::NatStatus::Unknown => Reachability::Unknown,
+        };
+        self.state.write().await.reachability = reachability;
+
+        if reachability == Reachability::Private {
+            self.listen_via_relays(swarm).await;
+        }
+    }
+
+    /// Reserve a slot on every configured relay so peers can reach us
+    async fn listen_via_relays(&self, swarm: &mut Swarm<ValidatorNetworkBehaviour>) {
+        let mut state = self.state.write().await;
+        if state.relay_listening || self.config.p2p.relay_addresses.is_empty() {
+            return;
+        }
+
+        for addr in &self.config.p2p.relay_addresses {
+            let relay_addr = match addr.parse::<Multiaddr>() {
+                Ok(addr) => addr,
+                Err(e) => {
+                    warn!("Invalid relay address {}: {}", addr, e);
+                    continue;
+                }
+            };
+            if !relay_addr.iter().any(|p| matches!(p, Protocol::P2p(_))) {
+                warn!("Relay address {} must end with /p2p/<peer id>", addr);
+                continue;
+            }
+
+            match swarm.listen_on(relay_addr.clone().with(Protocol::P2pCircuit)) {
+                Ok(_) => {
+                    info!("🔁 Listening via relay {}", relay_addr);
+                    state.relay_listening = true;
+                }
+                Err(e) => warn!("Failed to listen via relay {}: {}", addr, e),
+            }
+        }
+    }
+
+    /// Dial a peer's advertised QUIC addresses if we only reach it over TCP
+    async fn prefer_quic(
+        &self,
+        swarm: &mut Swarm<ValidatorNetworkBehaviour>,
+        peer_id: PeerId,
+        listen_addrs: &[Multiaddr],
+    ) {
+        if !self.config.p2p.enable_quic {
+            return;
+        }
+
+        let quic_addrs: Vec<Multiaddr> = listen_addrs
+            .iter()
+            .filter(|addr| transport::is_quic(addr))
+            .cloned()
+            .collect();
+        if quic_addrs.is_empty() {
+            return;
+        }
+
+        if !self.private_peers.contains(&peer_id) {
+            if let Some(kad) = swarm.behaviour_mut().kad.as_mut() {
+                for addr in &quic_addrs {
+                    kad.add_address(&peer_id, addr.clone());
+                }
+            }
+        }
+
+        let has_quic = self
+            .state
+            .read()
+            .await
+            .connections
+            .get(&peer_id)
+            .map(|conns| conns.iter().any(|(_, addr)| transport::is_quic(addr)))
+            .unwrap_or(false);
+        if has_quic {
+            return;
+        }
+
+        debug!("⚡ Peer {} advertises QUIC, upgrading connection", peer_id);
+        let opts = DialOpts::peer_id(peer_id)
+            .addresses(quic_addrs)
+            .condition(PeerCondition::Always)
+            .build();
+        if let Err(e) = swarm.dial(opts) {
+            debug!("Failed to dial QUIC address of {}: {}", peer_id, e);
         }
-        Ok(())
     }
 
     /// Handle gossipsub events (consensus messages)
-    async fn handle_gossipsub_event(&self, event: gossipsub::Event) -> Result<()> {
+    async fn handle_gossipsub_event(
+        &self,
+        swarm: &mut Swarm<ValidatorNetworkBehaviour>,
+        event: gossipsub::Event,
+    ) -> Result<()> {
         match event {
             gossipsub::Event::Message { 
                 propagation_source, 
@@ -357,13 +984,28 @@ impl P2PNetwork {
                     propagation_source,
                     message.topic
                 );
-                
-                // TODO: Route message to consensus validator for processing
-                // This is where we'd handle:
-                // - Commerce block proposals
-                // - Security block proposals  
-                // - Attestations
-                // - Transaction broadcasts
+
+                let topics = gossip::topics(self.config.network.id);
+                let mut outcome = gossip::validate(&topics, &message.topic, &message.data);
+                // Blocks and attestations are forwarded only once consensus accepts them
+                if matches!(outcome, gossipsub::MessageAcceptance::Accept) && gossip::is_consensus_topic(&message.topic) {
+                    // Signed messages name their author; count heads per author, not per forwarder
+                    let author = message.source.unwrap_or(propagation_source);
+                    if let Err(e) = self.consensus.handle_gossip(&author.to_string(), &message.data) {
+                        debug!("Rejected message {} from {}: {:#}", message_id, propagation_source, e);
+                        outcome = gossipsub::MessageAcceptance::Reject;
+                    }
+                }
+                self.gossip.record(&message.topic, &outcome);
+                if let Err(e) = swarm.behaviour_mut().gossipsub.report_message_validation_result(
+                    &message_id,
+                    &propagation_source,
+                    outcome,
+                ) {
+                    debug!("Failed to report validation of {}: {:?}", message_id, e);
+                }
+
+                // TODO: Hand transactions to a mempool once the node keeps one
             }
             gossipsub::Event::Subscribed { peer_id, topic } => {
                 debug!("📡 Peer {} subscribed to topic {}", peer_id, topic);
@@ -378,11 +1020,19 @@ impl P2PNetwork {
 
     /// Get P2P network status
     pub async fn status(&self) -> Result<P2PStatus> {
-        // TODO: Implement actual status collection from swarm
+        let state = self.state.read().await;
+
         Ok(P2PStatus {
-            local_peer_id: "placeholder_peer_id".to_string(),
-            connected_peers: 0,
-            listening_addresses: vec!["placeholder_address".to_string()],
+            local_peer_id: state
+                .local_peer_id
+                .map(|id| id.to_string())
+                .unwrap_or_default(),
+            connected_peers: state.connections.len(),
+            max_peers: self.max_peers.load(Ordering::Relaxed),
+            listening_addresses: state.listen_addresses.iter().map(|a| a.to_string()).collect(),
+            external_addresses: state.external_addresses.iter().map(|a| a.to_string()).collect(),
+            reachability: state.reachability,
+            sentry_mode: self.config.p2p.sentry.mode,
             network_id: self.config.network.id,
             gossipsub_topics: vec![
                 format!("omne/consensus/commerce/{}", self.config.network.id),
@@ -390,4 +1040,91 @@ impl P2PNetwork {
             ],
         })
     }
+
+    /// Peer, gossip and bandwidth counters for the metrics endpoint
+    pub async fn metrics(&self) -> P2PMetrics {
+        let bandwidth = self.bandwidth.get();
+        P2PMetrics {
+            connected_peers: self.state.read().await.connections.len(),
+            gossip: self.gossip.snapshot(),
+            bytes_inbound: bandwidth.map_or(0, |sinks| sinks.total_inbound()),
+            bytes_outbound: bandwidth.map_or(0, |sinks| sinks.total_outbound()),
+        }
+    }
+
+    /// Currently connected peers and the addresses of their connections
+    pub async fn connected_peers(&self) -> Vec<ConnectedPeer> {
+        let state = self.state.read().await;
+
+        state
+            .connections
+            .iter()
+            .map(|(peer_id, connections)| ConnectedPeer {
+                peer_id: peer_id.to_string(),
+                addresses: connections.iter().map(|(_, addr)| addr.to_string()).collect(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::ChainStore;
+    use crate::store::Store;
+    use std::path::Path;
+
+    /// A TCP-only node with its data under `dir`, on a free local port
+    async fn test_network(dir: &Path) -> (Arc<P2PNetwork>, Multiaddr) {
+        let mut config = ValidatorConfig::new_for_network("devnet").unwrap();
+        config.data_dir = dir.to_path_buf();
+        config.p2p.port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        config.p2p.enable_quic = false;
+        config.p2p.enable_mdns = false;
+        config.p2p.enable_autonat = false;
+        config.p2p.bootstrap_peers.clear();
+
+        let store = Store::temporary();
+        let chain = Arc::new(ChainStore::open(&store).unwrap());
+        let events = EventBus::new();
+        let consensus = Arc::new(PoVERAValidator::new(&config, chain, None, events.clone()).await.unwrap());
+        let network = P2PNetwork::new(&config, consensus, store.db(), events).await.unwrap();
+        let address = format!("/ip4/127.0.0.1/tcp/{}", config.p2p.port).parse().unwrap();
+        (Arc::new(network), address)
+    }
+
+    #[tokio::test]
+    async fn test_redials_known_peer_after_restart() {
+        let dir = std::env::temp_dir().join(format!("omne-p2p-{}", rand::random::<u32>()));
+        let (shutdown, _) = broadcast::channel(1);
+
+        // The peer ID survives a restart of the remote node
+        let peer_id = test_network(&dir.join("remote")).await.0.local_key.public().to_peer_id();
+        let (remote, remote_address) = test_network(&dir.join("remote")).await;
+        assert_eq!(remote.local_key.public().to_peer_id(), peer_id);
+        tokio::spawn({
+            let remote = remote.clone();
+            let shutdown = shutdown.subscribe();
+            async move { remote.start(shutdown).await }
+        });
+
+        // So a node that stored it redials it without any bootstrap peers
+        let (local, _) = test_network(&dir.join("local")).await;
+        local.peer_store.as_ref().unwrap().record_connected(&peer_id, &remote_address).unwrap();
+        tokio::spawn({
+            let local = local.clone();
+            let shutdown = shutdown.subscribe();
+            async move { local.start(shutdown).await }
+        });
+
+        let connected = tokio::time::timeout(Duration::from_secs(10), async {
+            while !local.state.read().await.connections.contains_key(&peer_id) {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await;
+        let _ = shutdown.send(());
+        assert!(connected.is_ok(), "known peer was not redialed");
+        let _ = std::fs::remove_dir_all(dir);
+    }
 }
@@ -0,0 +1,283 @@
+//! Bootstrap peer resolution and retry scheduling
+//!
+//! `/dns4/` and `/dns6/` bootstrap addresses are resolved by the DNS transport
+//! at dial time. `/dnsaddr/` entries are expanded up front from their
+//! `_dnsaddr.<domain>` TXT records so the node dials concrete addresses and
+//! can log what a bootstrap list actually resolved to.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use libp2p::{multiaddr::Protocol, Multiaddr, PeerId};
+use std::collections::HashSet;
+use std::time::Duration;
+use tracing::{debug, warn};
+use trust_dns_resolver::TokioAsyncResolver;
+
+/// Prefix of the TXT record name holding `dnsaddr` entries
+const DNSADDR_PREFIX: &str = "_dnsaddr.";
+
+/// Nested `/dnsaddr/` lookups followed before giving up
+const MAX_DNSADDR_DEPTH: usize = 4;
+
+/// Upper bound on addresses produced by a single bootstrap list
+const MAX_EXPANDED_ADDRESSES: usize = 64;
+
+/// Source of DNS TXT records
+#[async_trait]
+pub trait TxtResolver: Send + Sync {
+    /// Return the TXT record strings for `name`
+    async fn txt_lookup(&self, name: &str) -> Result<Vec<String>>;
+}
+
+/// TXT resolver backed by the operating system DNS configuration
+pub struct SystemResolver {
+    resolver: TokioAsyncResolver,
+}
+
+impl SystemResolver {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            resolver: TokioAsyncResolver::tokio_from_system_conf()?,
+        })
+    }
+}
+
+#[async_trait]
+impl TxtResolver for SystemResolver {
+    async fn txt_lookup(&self, name: &str) -> Result<Vec<String>> {
+        let lookup = self.resolver.txt_lookup(name).await?;
+        Ok(lookup
+            .iter()
+            .map(|txt| {
+                txt.txt_data()
+                    .iter()
+                    .map(|data| String::from_utf8_lossy(data))
+                    .collect::<String>()
+            })
+            .collect())
+    }
+}
+
+/// Expand the configured bootstrap list into dialable addresses
+///
+/// Entries that fail to parse or resolve are skipped with a warning.
+pub async fn expand_bootstrap_peers(
+    bootstrap_peers: &[String],
+    resolver: &dyn TxtResolver,
+) -> Vec<Multiaddr> {
+    let mut expanded = Vec::new();
+
+    for peer in bootstrap_peers {
+        let addr = match peer.parse::<Multiaddr>() {
+            Ok(addr) => addr,
+            Err(e) => {
+                warn!("Invalid bootstrap peer address {}: {}", peer, e);
+                continue;
+            }
+        };
+
+        let before = expanded.len();
+        expand_address(addr, resolver, MAX_DNSADDR_DEPTH, &mut expanded).await;
+        if expanded.len() == before {
+            warn!("Bootstrap peer {} did not resolve to any address", peer);
+        }
+    }
+
+    // The same address may be listed directly and behind a `/dnsaddr/`
+    let mut seen = HashSet::new();
+    expanded.retain(|addr| seen.insert(addr.clone()));
+    expanded
+}
+
+/// Recursively expand `/dnsaddr/` components of `addr` into `out`
+async fn expand_address(
+    addr: Multiaddr,
+    resolver: &dyn TxtResolver,
+    depth: usize,
+    out: &mut Vec<Multiaddr>,
+) {
+    let domain = match addr.iter().next() {
+        Some(Protocol::Dnsaddr(domain)) => domain.to_string(),
+        _ => {
+            if out.len() < MAX_EXPANDED_ADDRESSES {
+                out.push(addr);
+            }
+            return;
+        }
+    };
+
+    if depth == 0 {
+        warn!("Giving up on {}: too many nested /dnsaddr/ lookups", addr);
+        return;
+    }
+
+    let records = match resolver.txt_lookup(&format!("{}{}", DNSADDR_PREFIX, domain)).await {
+        Ok(records) => records,
+        Err(e) => {
+            warn!("Failed to resolve {}: {}", addr, e);
+            return;
+        }
+    };
+
+    // `/dnsaddr/<domain>/p2p/<id>` only keeps records for that peer
+    let suffix = peer_id_of(&addr);
+
+    for record in records {
+        let Some(entry) = record.strip_prefix("dnsaddr=") else {
+            continue;
+        };
+        let resolved = match entry.parse::<Multiaddr>() {
+            Ok(resolved) => resolved,
+            Err(e) => {
+                debug!("Ignoring malformed dnsaddr record {:?}: {}", record, e);
+                continue;
+            }
+        };
+        if suffix.is_some() && peer_id_of(&resolved) != suffix {
+            continue;
+        }
+
+        Box::pin(expand_address(resolved, resolver, depth - 1, out)).await;
+    }
+}
+
+/// Trailing `/p2p/<id>` of an address, if any
+fn peer_id_of(addr: &Multiaddr) -> Option<PeerId> {
+    match addr.iter().last() {
+        Some(Protocol::P2p(peer_id)) => Some(peer_id),
+        _ => None,
+    }
+}
+
+/// Exponential backoff between bootstrap attempts
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    attempt: u32,
+    initial: Duration,
+    max: Duration,
+}
+
+impl Backoff {
+    pub fn new(initial: Duration, max: Duration) -> Self {
+        Self { attempt: 0, initial, max }
+    }
+
+    /// Number of delays handed out since the last reset
+    pub fn attempt(&self) -> u32 {
+        self.attempt
+    }
+
+    /// Delay before the next attempt, doubling each time up to `max`
+    pub fn next_delay(&mut self) -> Duration {
+        let delay = self
+            .initial
+            .saturating_mul(2u32.saturating_pow(self.attempt))
+            .min(self.max);
+        self.attempt = self.attempt.saturating_add(1);
+        delay
+    }
+
+    /// Start over after a successful attempt
+    pub fn reset(&mut self) {
+        self.attempt = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// Resolver stand-in answering from a fixed table
+    struct StaticResolver(HashMap<String, Vec<String>>);
+
+    #[async_trait]
+    impl TxtResolver for StaticResolver {
+        async fn txt_lookup(&self, name: &str) -> Result<Vec<String>> {
+            self.0
+                .get(name)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("NXDOMAIN: {}", name))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_expand_dnsaddr() {
+        let peer_a = PeerId::random();
+        let peer_b = PeerId::random();
+        let resolver = StaticResolver(HashMap::from([
+            (
+                "_dnsaddr.boot.omne.network".to_string(),
+                vec![
+                    format!("dnsaddr=/ip4/10.0.0.1/tcp/30303/p2p/{}", peer_a),
+                    "dnsaddr=/dnsaddr/eu.boot.omne.network".to_string(),
+                    "unrelated=record".to_string(),
+                ],
+            ),
+            (
+                "_dnsaddr.eu.boot.omne.network".to_string(),
+                vec![format!("dnsaddr=/ip4/10.0.0.2/udp/30303/quic-v1/p2p/{}", peer_b)],
+            ),
+        ]));
+
+        let expanded = expand_bootstrap_peers(
+            &[
+                "/dnsaddr/boot.omne.network".to_string(),
+                "/dns4/boot1.omne.network/tcp/30303".to_string(),
+                "/dnsaddr/missing.omne.network".to_string(),
+                "not a multiaddr".to_string(),
+            ],
+            &resolver,
+        )
+        .await;
+
+        let expanded: Vec<String> = expanded.iter().map(|a| a.to_string()).collect();
+        assert_eq!(
+            expanded,
+            vec![
+                format!("/ip4/10.0.0.1/tcp/30303/p2p/{}", peer_a),
+                format!("/ip4/10.0.0.2/udp/30303/quic-v1/p2p/{}", peer_b),
+                "/dns4/boot1.omne.network/tcp/30303".to_string(),
+            ]
+        );
+
+        // A trailing peer ID filters the records
+        let filtered = expand_bootstrap_peers(
+            &[format!("/dnsaddr/eu.boot.omne.network/p2p/{}", peer_a)],
+            &resolver,
+        )
+        .await;
+        assert!(filtered.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_expand_removes_duplicates() {
+        let peer = PeerId::random();
+        let direct = format!("/ip4/10.0.0.1/tcp/30303/p2p/{}", peer);
+        let resolver = StaticResolver(HashMap::from([(
+            "_dnsaddr.boot.omne.network".to_string(),
+            vec![format!("dnsaddr={}", direct), "dnsaddr=/ip4/10.0.0.2/tcp/30303".to_string()],
+        )]));
+
+        let expanded = expand_bootstrap_peers(
+            &[direct.clone(), "/dnsaddr/boot.omne.network".to_string(), direct.clone()],
+            &resolver,
+        )
+        .await;
+        let expanded: Vec<String> = expanded.iter().map(|a| a.to_string()).collect();
+        assert_eq!(expanded, vec![direct, "/ip4/10.0.0.2/tcp/30303".to_string()]);
+    }
+
+    #[test]
+    fn test_backoff() {
+        let mut backoff = Backoff::new(Duration::from_secs(5), Duration::from_secs(30));
+        assert_eq!(backoff.next_delay(), Duration::from_secs(5));
+        assert_eq!(backoff.next_delay(), Duration::from_secs(10));
+        assert_eq!(backoff.next_delay(), Duration::from_secs(20));
+        assert_eq!(backoff.next_delay(), Duration::from_secs(30));
+        assert_eq!(backoff.attempt(), 4);
+
+        backoff.reset();
+        assert_eq!(backoff.next_delay(), Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,133 @@
+//! Gossip topics, message validation and accounting
+//!
+//! Gossipsub holds every received message until we report a validation
+//! result, so nothing is forwarded to the mesh before it has been checked;
+//! blocks and attestations are also checked by consensus.
+//! Received messages are counted per topic along with the validation
+//! outcomes; both feed the P2P metrics.
+
+use crate::consensus::ConsensusMessage;
+use crate::events::Layer;
+
+use libp2p::gossipsub::{MessageAcceptance, TopicHash};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// Topics a node of network `network_id` subscribes to
+pub fn topics(network_id: u64) -> Vec<String> {
+    vec![
+        format!("omne/consensus/commerce/{}", network_id),
+        format!("omne/consensus/security/{}", network_id),
+        format!("omne/transactions/{}", network_id),
+        format!("omne/attestations/{}", network_id),
+    ]
+}
+
+/// Topic `message` is published on
+pub fn topic_for(network_id: u64, message: &ConsensusMessage) -> String {
+    match message {
+        ConsensusMessage::Block { block, .. } => match block.header.layer {
+            Layer::Commerce => format!("omne/consensus/commerce/{}", network_id),
+            Layer::Security => format!("omne/consensus/security/{}", network_id),
+        },
+        ConsensusMessage::Attestation(_) => format!("omne/attestations/{}", network_id),
+    }
+}
+
+/// Whether `topic` carries blocks or attestations for consensus
+pub fn is_consensus_topic(topic: &TopicHash) -> bool {
+    let topic = topic.as_str();
+    topic.starts_with("omne/consensus/") || topic.starts_with("omne/attestations/")
+}
+
+/// Decide whether a message received on `topic` is delivered and forwarded
+pub fn validate(topics: &[String], topic: &TopicHash, data: &[u8]) -> MessageAcceptance {
+    if !topics.iter().any(|name| name == topic.as_str()) {
+        MessageAcceptance::Ignore
+    } else if data.is_empty() {
+        MessageAcceptance::Reject
+    } else {
+        MessageAcceptance::Accept
+    }
+}
+
+/// Snapshot of the gossip counters
+#[derive(Debug, Clone, Default)]
+pub struct GossipCounters {
+    /// Messages received per subscribed topic, sorted by topic
+    pub messages_received: Vec<(String, u64)>,
+    pub accepted: u64,
+    pub rejected: u64,
+    pub ignored: u64,
+}
+
+#[derive(Default)]
+pub struct GossipStats {
+    /// Keyed by subscribed topics only, so the map stays bounded
+    received: Mutex<HashMap<String, u64>>,
+    accepted: AtomicU64,
+    rejected: AtomicU64,
+    ignored: AtomicU64,
+}
+
+impl GossipStats {
+    /// Account for a message on `topic` and its validation outcome
+    pub fn record(&self, topic: &TopicHash, outcome: &MessageAcceptance) {
+        let counter = match outcome {
+            MessageAcceptance::Accept => &self.accepted,
+            MessageAcceptance::Reject => &self.rejected,
+            MessageAcceptance::Ignore => {
+                // Not one of our topics; do not let it add a label
+                self.ignored.fetch_add(1, Ordering::Relaxed);
+                return;
+            }
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        *self
+            .received
+            .lock()
+            .unwrap()
+            .entry(topic.as_str().to_string())
+            .or_default() += 1;
+    }
+
+    pub fn snapshot(&self) -> GossipCounters {
+        let mut messages_received: Vec<(String, u64)> = self
+            .received
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(topic, count)| (topic.clone(), *count))
+            .collect();
+        messages_received.sort();
+
+        GossipCounters {
+            messages_received,
+            accepted: self.accepted.load(Ordering::Relaxed),
+            rejected: self.rejected.load(Ordering::Relaxed),
+            ignored: self.ignored.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validation_outcomes() {
+        let topics = topics(7);
+        let stats = GossipStats::default();
+        let commerce = TopicHash::from_raw("omne/consensus/commerce/7");
+        let foreign = TopicHash::from_raw("omne/consensus/commerce/8");
+
+        for (topic, data) in [(&commerce, &b"block"[..]), (&commerce, b""), (&foreign, b"block")] {
+            stats.record(topic, &validate(&topics, topic, data));
+        }
+
+        let counters = stats.snapshot();
+        assert_eq!((counters.accepted, counters.rejected, counters.ignored), (1, 1, 1));
+        assert_eq!(counters.messages_received, [(commerce.into_string(), 2)]);
+    }
+}
@@ -0,0 +1,75 @@
+//! Network identity key
+//!
+//! The Ed25519 key behind the node's peer ID is kept in libp2p's protobuf
+//! encoding at `keys/network.key`, or `validator.network_key_path` when set,
+//! so the peer ID survives restarts and peers that stored our address can
+//! dial us again.
+
+use anyhow::{Context, Result};
+use libp2p::identity::Keypair;
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+use tracing::{info, warn};
+
+/// Permissions of a newly created key file
+const KEY_FILE_MODE: u32 = 0o600;
+
+/// What `init --generate-keys` wrote before network keys were real
+const PLACEHOLDER_KEY: &[u8] = b"placeholder_network_key";
+
+/// Load the network key at `path`, creating one if it is missing or still
+/// the placeholder
+pub fn load_or_create(path: &Path) -> Result<Keypair> {
+    match std::fs::read(path) {
+        Ok(bytes) if bytes == PLACEHOLDER_KEY => {
+            warn!("🔑 Replacing placeholder network key {}", path.display());
+            std::fs::remove_file(path)?;
+        }
+        Ok(bytes) => {
+            return Keypair::from_protobuf_encoding(&bytes)
+                .with_context(|| format!("Network key {} is not a libp2p key", path.display()));
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e).with_context(|| format!("Failed to read network key {}", path.display())),
+    }
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let keypair = Keypair::generate_ed25519();
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(KEY_FILE_MODE)
+        .open(path)
+        .with_context(|| format!("Failed to create network key {}", path.display()))?;
+    file.write_all(&keypair.to_protobuf_encoding()?)?;
+
+    info!("🔑 Generated network key at {}", path.display());
+    Ok(keypair)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_is_created_once() {
+        let dir = std::env::temp_dir().join(format!("omne-identity-{}", rand::random::<u32>()));
+        let path = dir.join("keys").join("network.key");
+
+        let created = load_or_create(&path).unwrap();
+        let loaded = load_or_create(&path).unwrap();
+        assert_eq!(created.public().to_peer_id(), loaded.public().to_peer_id());
+
+        std::fs::write(&path, PLACEHOLDER_KEY).unwrap();
+        let replaced = load_or_create(&path).unwrap();
+        assert_ne!(replaced.public().to_peer_id(), created.public().to_peer_id());
+        assert_eq!(load_or_create(&path).unwrap().public().to_peer_id(), replaced.public().to_peer_id());
+
+        std::fs::write(&path, "not a key").unwrap();
+        assert!(load_or_create(&path).is_err());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
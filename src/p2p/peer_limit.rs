@@ -0,0 +1,144 @@
+//! Peer limit enforced before connections reach the other behaviours
+//!
+//! `libp2p::connection_limits` counts connections and fixes its limits at
+//! construction. This behaviour counts peers instead, lets sentries and the
+//! validators they shield through, and reads `max_peers` from a handle the
+//! node updates on reload.
+
+use libp2p::{
+    core::Endpoint,
+    swarm::{
+        dummy, ConnectionDenied, ConnectionId, FromSwarm, NetworkBehaviour, PollParameters, THandler,
+        THandlerInEvent, THandlerOutEvent, ToSwarm,
+    },
+    Multiaddr, PeerId,
+};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use void::Void;
+
+/// Denies connections to new peers once `max_peers` peers are connected
+pub struct Behaviour {
+    max_peers: Arc<AtomicUsize>,
+    /// Peers that are never refused nor counted
+    exempt: HashSet<PeerId>,
+    /// Open connections per counted peer
+    connected: HashMap<PeerId, usize>,
+}
+
+impl Behaviour {
+    pub fn new(max_peers: Arc<AtomicUsize>, exempt: HashSet<PeerId>) -> Self {
+        Self { max_peers, exempt, connected: HashMap::new() }
+    }
+
+    fn check(&self, peer: &PeerId) -> Result<(), ConnectionDenied> {
+        let max_peers = self.max_peers.load(Ordering::Relaxed);
+        if self.exempt.contains(peer) || self.connected.contains_key(peer) || self.connected.len() < max_peers {
+            return Ok(());
+        }
+        Err(ConnectionDenied::new(format!("{} peers already connected", max_peers)))
+    }
+}
+
+impl NetworkBehaviour for Behaviour {
+    type ConnectionHandler = dummy::ConnectionHandler;
+    type ToSwarm = Void;
+
+    fn handle_established_inbound_connection(
+        &mut self,
+        _: ConnectionId,
+        peer: PeerId,
+        _: &Multiaddr,
+        _: &Multiaddr,
+    ) -> Result<THandler<Self>, ConnectionDenied> {
+        self.check(&peer)?;
+        Ok(dummy::ConnectionHandler)
+    }
+
+    fn handle_established_outbound_connection(
+        &mut self,
+        _: ConnectionId,
+        peer: PeerId,
+        _: &Multiaddr,
+        _: Endpoint,
+    ) -> Result<THandler<Self>, ConnectionDenied> {
+        self.check(&peer)?;
+        Ok(dummy::ConnectionHandler)
+    }
+
+    fn on_swarm_event(&mut self, event: FromSwarm<Self::ConnectionHandler>) {
+        // Counted only once every behaviour accepted the connection
+        match event {
+            FromSwarm::ConnectionEstablished(established) if !self.exempt.contains(&established.peer_id) => {
+                *self.connected.entry(established.peer_id).or_default() += 1;
+            }
+            FromSwarm::ConnectionClosed(closed) => {
+                if let Some(connections) = self.connected.get_mut(&closed.peer_id) {
+                    *connections -= 1;
+                    if *connections == 0 {
+                        self.connected.remove(&closed.peer_id);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn on_connection_handler_event(&mut self, _: PeerId, _: ConnectionId, event: THandlerOutEvent<Self>) {
+        void::unreachable(event)
+    }
+
+    fn poll(
+        &mut self,
+        _: &mut Context<'_>,
+        _: &mut impl PollParameters,
+    ) -> Poll<ToSwarm<Self::ToSwarm, THandlerInEvent<Self>>> {
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libp2p::core::ConnectedPoint;
+    use libp2p::swarm::behaviour::ConnectionEstablished;
+
+    #[test]
+    fn test_refuses_new_peers_over_the_limit() {
+        let max_peers = Arc::new(AtomicUsize::new(1));
+        let sentry = PeerId::random();
+        let mut limit = Behaviour::new(max_peers.clone(), HashSet::from([sentry]));
+        let address: Multiaddr = "/ip4/203.0.113.1/tcp/30303".parse().unwrap();
+        let endpoint = ConnectedPoint::Dialer { address: address.clone(), role_override: Endpoint::Dialer };
+        let connect = |limit: &mut Behaviour, peer: PeerId| {
+            let result = limit.handle_established_outbound_connection(
+                ConnectionId::new_unchecked(0),
+                peer,
+                &address,
+                Endpoint::Dialer,
+            );
+            if result.is_ok() {
+                limit.on_swarm_event(FromSwarm::ConnectionEstablished(ConnectionEstablished {
+                    peer_id: peer,
+                    connection_id: ConnectionId::new_unchecked(0),
+                    endpoint: &endpoint,
+                    failed_addresses: &[],
+                    other_established: 0,
+                }));
+            }
+            result.is_ok()
+        };
+
+        let first = PeerId::random();
+        assert!(connect(&mut limit, first));
+        assert!(connect(&mut limit, first), "further connections of a connected peer are let through");
+        assert!(!connect(&mut limit, PeerId::random()));
+        assert!(connect(&mut limit, sentry), "sentries are exempt");
+
+        // A reload raising the limit takes effect on the next connection
+        max_peers.store(2, Ordering::Relaxed);
+        assert!(connect(&mut limit, PeerId::random()));
+    }
+}
@@ -0,0 +1,273 @@
+//! Persistent peer store
+//!
+//! Known peers (addresses, last seen time, score and supported protocols)
+//! are kept in the `peers` sled tree so a restarted node can re-dial peers
+//! that worked before instead of relying only on the static bootstrap list.
+
+use anyhow::{Context, Result};
+use libp2p::{multiaddr::Protocol, Multiaddr, PeerId};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Name of the sled tree holding peer records
+const PEERS_TREE: &str = "peers";
+
+/// Records not seen for this long are evicted on load
+const PEER_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Score bounds; peers at or below `MIN_SCORE` are evicted
+const MAX_SCORE: i32 = 100;
+const MIN_SCORE: i32 = -20;
+
+/// Score adjustments
+const CONNECTED_REWARD: i32 = 1;
+const DIAL_FAILURE_PENALTY: i32 = 5;
+
+/// Maximum addresses remembered per peer
+const MAX_ADDRESSES: usize = 8;
+
+/// A peer remembered across restarts
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PeerRecord {
+    /// Known addresses, most recently used first
+    pub addresses: Vec<String>,
+    /// Unix timestamp of the last successful connection
+    pub last_seen: u64,
+    /// Reputation score, higher is better
+    pub score: i32,
+    /// Protocols reported by identify
+    pub protocols: Vec<String>,
+}
+
+impl PeerRecord {
+    /// Parsed addresses, skipping any that no longer parse
+    pub fn multiaddrs(&self) -> Vec<Multiaddr> {
+        self.addresses.iter().filter_map(|a| a.parse().ok()).collect()
+    }
+
+    fn add_address(&mut self, address: &Multiaddr) {
+        let address = address.to_string();
+        self.addresses.retain(|a| a != &address);
+        self.addresses.insert(0, address);
+        self.addresses.truncate(MAX_ADDRESSES);
+    }
+}
+
+/// Sled-backed store of known peers
+pub struct PeerStore {
+    tree: sled::Tree,
+    capacity: usize,
+}
+
+impl PeerStore {
+    /// Open the peer store, keeping at most `capacity` records
+    pub fn open(db: &sled::Db, capacity: usize) -> Result<Self> {
+        let tree = db.open_tree(PEERS_TREE).context("Failed to open peer store")?;
+        Ok(Self { tree, capacity })
+    }
+
+    /// Load all peers after applying the eviction policy, best peers first
+    pub fn load(&self) -> Result<Vec<(PeerId, PeerRecord)>> {
+        let now = unix_now();
+        let mut peers = Vec::new();
+
+        for entry in self.tree.iter() {
+            let (key, value) = entry?;
+            let record = PeerId::from_bytes(&key)
+                .ok()
+                .zip(bincode::deserialize::<PeerRecord>(&value).ok());
+
+            match record {
+                Some((peer_id, record))
+                    if record.score > MIN_SCORE
+                        && now.saturating_sub(record.last_seen) <= PEER_TTL.as_secs() =>
+                {
+                    peers.push((peer_id, record));
+                }
+                _ => {
+                    self.tree.remove(key)?;
+                }
+            }
+        }
+
+        sort_best_first(&mut peers);
+        for (peer_id, _) in peers.drain(self.capacity.min(peers.len())..) {
+            self.tree.remove(peer_id.to_bytes())?;
+        }
+
+        Ok(peers)
+    }
+
+    /// Best previously good peers to fill connection slots with
+    pub fn best_peers(&self, limit: usize) -> Result<Vec<(PeerId, PeerRecord)>> {
+        let mut peers = self.load()?;
+        peers.retain(|(_, record)| record.score > 0 && !record.addresses.is_empty());
+        peers.truncate(limit);
+        Ok(peers)
+    }
+
+    /// Look up a single peer
+    pub fn get(&self, peer_id: &PeerId) -> Result<Option<PeerRecord>> {
+        Ok(self
+            .tree
+            .get(peer_id.to_bytes())?
+            .and_then(|value| bincode::deserialize(&value).ok()))
+    }
+
+    /// Record a successful connection to `peer_id` via `address`
+    pub fn record_connected(&self, peer_id: &PeerId, address: &Multiaddr) -> Result<()> {
+        self.update(peer_id, |record| {
+            record.add_address(address);
+            record.last_seen = unix_now();
+            record.score = (record.score + CONNECTED_REWARD).min(MAX_SCORE);
+        })
+    }
+
+    /// Record the listen addresses and protocols a peer reported via identify.
+    /// Only globally reachable addresses are kept: a peer's loopback or LAN
+    /// addresses are its own and would make us dial ourselves or our network.
+    pub fn record_identify(
+        &self,
+        peer_id: &PeerId,
+        listen_addrs: &[Multiaddr],
+        protocols: Vec<String>,
+    ) -> Result<()> {
+        self.update(peer_id, |record| {
+            for address in listen_addrs.iter().rev().filter(|address| is_global(address)) {
+                record.add_address(address);
+            }
+            record.protocols = protocols;
+        })
+    }
+
+    /// Penalize a peer after a failed dial
+    pub fn record_failure(&self, peer_id: &PeerId) -> Result<()> {
+        if self.tree.contains_key(peer_id.to_bytes())? {
+            self.update(peer_id, |record| {
+                record.score = (record.score - DIAL_FAILURE_PENALTY).max(MIN_SCORE);
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Remove a peer entirely
+    pub fn remove(&self, peer_id: &PeerId) -> Result<()> {
+        self.tree.remove(peer_id.to_bytes())?;
+        Ok(())
+    }
+
+    fn update(&self, peer_id: &PeerId, f: impl FnOnce(&mut PeerRecord)) -> Result<()> {
+        let mut record = self.get(peer_id)?.unwrap_or_default();
+        f(&mut record);
+        self.tree
+            .insert(peer_id.to_bytes(), bincode::serialize(&record)?)?;
+        Ok(())
+    }
+}
+
+/// Whether `address` is reachable from other networks; DNS names are taken
+/// to be
+fn is_global(address: &Multiaddr) -> bool {
+    match address.iter().next() {
+        Some(Protocol::Ip4(ip)) => {
+            !(ip.is_loopback() || ip.is_private() || ip.is_link_local() || ip.is_unspecified())
+        }
+        Some(Protocol::Ip6(ip)) => {
+            !(ip.is_loopback() || ip.is_unique_local() || ip.is_unicast_link_local() || ip.is_unspecified())
+        }
+        Some(Protocol::Dns(_) | Protocol::Dns4(_) | Protocol::Dns6(_) | Protocol::Dnsaddr(_)) => true,
+        _ => false,
+    }
+}
+
+fn sort_best_first(peers: &mut [(PeerId, PeerRecord)]) {
+    peers.sort_by(|(_, a), (_, b)| {
+        b.score
+            .cmp(&a.score)
+            .then(b.last_seen.cmp(&a.last_seen))
+    });
+}
+
+fn unix_now() -> u64 {
+    chrono::Utc::now().timestamp().max(0) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_store(capacity: usize) -> PeerStore {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        PeerStore::open(&db, capacity).unwrap()
+    }
+
+    #[test]
+    fn test_record_and_reload() {
+        let store = temp_store(10);
+        let peer = PeerId::random();
+        let addr: Multiaddr = "/ip4/203.0.113.1/tcp/30303".parse().unwrap();
+
+        store.record_connected(&peer, &addr).unwrap();
+        let listen_addrs: Vec<Multiaddr> = [
+            "/ip4/127.0.0.1/tcp/30303",
+            "/ip4/192.168.1.5/tcp/30303",
+            "/ip4/169.254.0.7/tcp/30303",
+            "/ip6/fe80::1/tcp/30303",
+            "/ip4/203.0.113.1/tcp/30303",
+        ]
+        .iter()
+        .map(|a| a.parse().unwrap())
+        .collect();
+        store
+            .record_identify(&peer, &listen_addrs, vec!["/omne/validator/1.0.0".to_string()])
+            .unwrap();
+
+        let peers = store.load().unwrap();
+        assert_eq!(peers.len(), 1);
+        assert_eq!(peers[0].0, peer);
+        assert_eq!(peers[0].1.multiaddrs(), vec![addr]);
+        assert_eq!(peers[0].1.score, CONNECTED_REWARD);
+        assert_eq!(peers[0].1.protocols, vec!["/omne/validator/1.0.0".to_string()]);
+    }
+
+    #[test]
+    fn test_eviction() {
+        let store = temp_store(2);
+        let addr: Multiaddr = "/ip4/10.0.0.1/tcp/30303".parse().unwrap();
+
+        let good = PeerId::random();
+        store.record_connected(&good, &addr).unwrap();
+        store.record_connected(&good, &addr).unwrap();
+
+        let ok = PeerId::random();
+        store.record_connected(&ok, &addr).unwrap();
+
+        let bad = PeerId::random();
+        store.record_connected(&bad, &addr).unwrap();
+        for _ in 0..5 {
+            store.record_failure(&bad).unwrap();
+        }
+
+        let stale = PeerId::random();
+        store
+            .update(&stale, |record| {
+                record.add_address(&addr);
+                record.score = 50;
+                record.last_seen = unix_now() - PEER_TTL.as_secs() - 1;
+            })
+            .unwrap();
+
+        let peers: Vec<PeerId> = store.load().unwrap().into_iter().map(|(id, _)| id).collect();
+        assert_eq!(peers, vec![good, ok]);
+        assert!(store.get(&bad).unwrap().is_none());
+        assert!(store.get(&stale).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_failure_ignores_unknown_peers() {
+        let store = temp_store(10);
+        let peer = PeerId::random();
+        store.record_failure(&peer).unwrap();
+        assert!(store.get(&peer).unwrap().is_none());
+    }
+}
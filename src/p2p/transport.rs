@@ -0,0 +1,140 @@
+//! Transport construction for the validator swarm
+//!
+//! Combines TCP (noise + yamux), QUIC and the circuit relay v2 client into a
+//! single boxed transport. QUIC is tried first when dialing, so peers
+//! advertising a `/quic-v1` address are reached over QUIC and everything
+//! else falls back to TCP. `/p2p-circuit` addresses go through the relay.
+//! A DNS layer resolves `/dns4/`, `/dns6/` and `/dnsaddr/` addresses, and
+//! all traffic is counted for the bandwidth metrics.
+
+use crate::config::P2PConfig;
+
+use anyhow::{Context, Result};
+use libp2p::{
+    bandwidth::BandwidthSinks,
+    core::{
+        muxing::StreamMuxerBox,
+        transport::{Boxed, OrTransport},
+        upgrade,
+    },
+    dns,
+    identity::Keypair,
+    multiaddr::Protocol,
+    noise, quic, relay, tcp, yamux, Multiaddr, PeerId, Transport, TransportExt,
+};
+use std::sync::Arc;
+
+/// Boxed transport yielding authenticated, multiplexed connections
+pub type NodeTransport = Boxed<(PeerId, StreamMuxerBox)>;
+
+/// Build the node transport according to the enabled transports in `config`,
+/// along with the sinks counting bytes sent and received through it
+pub fn build_transport(
+    local_key: &Keypair,
+    config: &P2PConfig,
+    relay_transport: relay::client::Transport,
+) -> Result<(NodeTransport, Arc<BandwidthSinks>)> {
+    let tcp_transport = || -> Result<NodeTransport> {
+        Ok(tcp::tokio::Transport::new(tcp::Config::default().nodelay(true))
+            .upgrade(upgrade::Version::V1)
+            .authenticate(noise::Config::new(local_key)?)
+            .multiplex(yamux::Config::default())
+            .timeout(config.connection_timeout)
+            .map(|(peer_id, muxer), _| (peer_id, StreamMuxerBox::new(muxer)))
+            .boxed())
+    };
+
+    let quic_transport = || {
+        quic::tokio::Transport::new(quic::Config::new(local_key))
+            .map(|(peer_id, connection), _| (peer_id, StreamMuxerBox::new(connection)))
+            .boxed()
+    };
+
+    let transport = match (config.enable_tcp, config.enable_quic) {
+        (true, true) => OrTransport::new(quic_transport(), tcp_transport()?)
+            .map(|either, _| either.into_inner())
+            .boxed(),
+        (true, false) => tcp_transport()?,
+        (false, true) => quic_transport(),
+        (false, false) => {
+            return Err(anyhow::anyhow!(
+                "At least one of p2p.enable_tcp or p2p.enable_quic must be set"
+            ))
+        }
+    };
+
+    let relay_transport = relay_transport
+        .upgrade(upgrade::Version::V1)
+        .authenticate(noise::Config::new(local_key)?)
+        .multiplex(yamux::Config::default())
+        .map(|(peer_id, muxer), _| (peer_id, StreamMuxerBox::new(muxer)));
+
+    let transport = dns::tokio::Transport::system(transport)
+        .context("Failed to read system DNS configuration")?;
+
+    Ok(OrTransport::new(relay_transport, transport)
+        .map(|either, _| either.into_inner())
+        .boxed()
+        .with_bandwidth_logging())
+}
+
+/// Listen addresses for every enabled transport
+pub fn listen_addresses(config: &P2PConfig) -> Vec<Multiaddr> {
+    let mut addresses = Vec::new();
+
+    if config.enable_tcp {
+        addresses.push(
+            Multiaddr::empty()
+                .with(Protocol::Ip4([0, 0, 0, 0].into()))
+                .with(Protocol::Tcp(config.port)),
+        );
+    }
+
+    if config.enable_quic {
+        addresses.push(
+            Multiaddr::empty()
+                .with(Protocol::Ip4([0, 0, 0, 0].into()))
+                .with(Protocol::Udp(config.quic_port.unwrap_or(config.port)))
+                .with(Protocol::QuicV1),
+        );
+    }
+
+    addresses
+}
+
+/// Whether the address is a QUIC address
+pub fn is_quic(address: &Multiaddr) -> bool {
+    address.iter().any(|protocol| matches!(protocol, Protocol::QuicV1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ValidatorConfig;
+
+    #[test]
+    fn test_listen_addresses() {
+        let mut config = ValidatorConfig::new_for_network("devnet").unwrap().p2p;
+        config.quic_port = Some(30304);
+
+        let addresses = listen_addresses(&config);
+        assert_eq!(addresses.len(), 2);
+        assert_eq!(addresses[0].to_string(), "/ip4/0.0.0.0/tcp/30303");
+        assert_eq!(addresses[1].to_string(), "/ip4/0.0.0.0/udp/30304/quic-v1");
+        assert!(is_quic(&addresses[1]));
+
+        config.enable_quic = false;
+        assert_eq!(listen_addresses(&config).len(), 1);
+    }
+
+    #[test]
+    fn test_build_transport_requires_one_transport() {
+        let mut config = ValidatorConfig::new_for_network("devnet").unwrap().p2p;
+        config.enable_tcp = false;
+        config.enable_quic = false;
+
+        let local_key = Keypair::generate_ed25519();
+        let (relay_transport, _) = relay::client::new(local_key.public().to_peer_id());
+        assert!(build_transport(&local_key, &config, relay_transport).is_err());
+    }
+}
@@ -0,0 +1,196 @@
+//! History pruning
+//!
+//! In pruned mode, commerce blocks and their attestations are deleted once
+//! their epoch falls more than `retention_epochs` commerce epochs behind the
+//! finalized commerce block: the last one timestamped no later than the
+//! block of the latest finalized security checkpoint. Each pass deletes at
+//! most `prune_batch_size` blocks per write on the blocking pool and yields
+//! between writes, so a large backlog is worked off gradually without
+//! holding up slot processing. Archive mode keeps everything.
+
+use crate::chain::ChainStore;
+use crate::config::{PruningMode, StorageConfig};
+use crate::events::Layer;
+
+use anyhow::Result;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tracing::{debug, info, warn};
+
+/// Storage usage and retention, as reported by `storage_status`
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StorageStatus {
+    pub pruning: PruningMode,
+    pub retention_epochs: u64,
+    /// Bytes the node database occupies on disk
+    pub size_on_disk: u64,
+    /// Lowest commerce height still stored
+    pub oldest_commerce_height: Option<u64>,
+    /// Commerce blocks from commerce epochs before this one are pruned
+    pub prune_before_epoch: Option<u64>,
+}
+
+/// Background task deleting history behind finality
+pub struct Pruner {
+    config: StorageConfig,
+    chain: Arc<ChainStore>,
+}
+
+impl Pruner {
+    pub fn new(config: &StorageConfig, chain: Arc<ChainStore>) -> Self {
+        Self {
+            config: config.clone(),
+            chain,
+        }
+    }
+
+    /// Prune every `prune_interval_secs` until shutdown
+    pub async fn start(self: Arc<Self>, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+        if self.config.pruning == PruningMode::Archive {
+            info!("🗄️  Archive mode: keeping full history");
+            return Ok(());
+        }
+        info!(
+            "✂️  Pruning commerce history older than {} epochs behind finality",
+            self.config.retention_epochs
+        );
+
+        let mut ticker = tokio::time::interval(Duration::from_secs(self.config.prune_interval_secs.max(1)));
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    if let Err(e) = self.prune().await {
+                        warn!("⚠️  Pruning failed: {:#}", e);
+                    }
+                }
+                _ = shutdown.recv() => {
+                    info!("🛑 Stopping pruner");
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Delete everything currently outside the retention window, one batch
+    /// at a time; returns the number of blocks deleted
+    pub async fn prune(&self) -> Result<usize> {
+        let Some(before_epoch) = prune_before_epoch(&self.config, &self.chain)? else {
+            return Ok(0);
+        };
+
+        let batch_size = self.config.prune_batch_size.max(1);
+        let mut total = 0;
+        loop {
+            let chain = self.chain.clone();
+            let pruned =
+                tokio::task::spawn_blocking(move || chain.prune_commerce(before_epoch, batch_size)).await??;
+            total += pruned;
+            if pruned < batch_size {
+                break;
+            }
+            tokio::task::yield_now().await;
+        }
+
+        if total > 0 {
+            debug!("Pruned {} commerce blocks before epoch {}", total, before_epoch);
+        }
+        Ok(total)
+    }
+}
+
+/// First commerce epoch kept under `config`, or `None` if nothing is to be
+/// pruned
+fn prune_before_epoch(config: &StorageConfig, chain: &ChainStore) -> Result<Option<u64>> {
+    if config.pruning == PruningMode::Archive {
+        return Ok(None);
+    }
+    Ok(chain
+        .finalized_commerce_block()?
+        .and_then(|block| block.header.epoch.checked_sub(config.retention_epochs))
+        .filter(|&epoch| epoch > 0))
+}
+
+/// Current storage usage and retention of `chain`
+pub fn storage_status(config: &StorageConfig, chain: &ChainStore) -> Result<StorageStatus> {
+    Ok(StorageStatus {
+        pruning: config.pruning,
+        retention_epochs: config.retention_epochs,
+        size_on_disk: chain.size_on_disk()?,
+        oldest_commerce_height: chain.oldest_height(Layer::Commerce)?,
+        prune_before_epoch: prune_before_epoch(config, chain)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::tests::{block, hash, pubkey};
+    use crate::chain::{Attestation, Checkpoint};
+    use crate::store::Store;
+
+    #[tokio::test]
+    async fn test_prune_behind_finality() {
+        let chain = Arc::new(ChainStore::open(&Store::temporary()).unwrap());
+        // Test blocks are in epoch height / 32: heights 0..=31 are epoch 0, and so on
+        for height in 0..128u64 {
+            chain
+                .import_block(
+                    &block(Layer::Commerce, height, height as u8),
+                    &[Attestation {
+                        block_hash: hash(height as u8),
+                        validator: pubkey(1),
+                        epoch: height / 32,
+                        signature: "0x00".to_string(),
+                    }],
+                )
+                .unwrap();
+        }
+        // Security epoch 3 is finalized at the time of commerce height 40, in commerce epoch 1
+        let mut security = block(Layer::Security, 1, 200);
+        security.header.epoch = 3;
+        security.header.timestamp = block(Layer::Commerce, 40, 0).header.timestamp;
+        chain.insert_block(&security).unwrap();
+        chain
+            .set_finalized_checkpoint(&Checkpoint { epoch: 3, height: 1, block_hash: hash(200) })
+            .unwrap();
+
+        let mut config = StorageConfig {
+            retention_epochs: 1,
+            prune_batch_size: 10,
+            ..StorageConfig::default()
+        };
+        let archive = Pruner::new(&config, chain.clone());
+        assert_eq!(archive.prune().await.unwrap(), 0);
+
+        config.pruning = PruningMode::Pruned;
+        let pruner = Pruner::new(&config, chain.clone());
+        assert_eq!(chain.finalized_commerce_block().unwrap().unwrap().header.height, 40);
+        assert_eq!(pruner.prune().await.unwrap(), 0, "the security epoch does not count against commerce");
+
+        // Finality reaches commerce height 127, in commerce epoch 3
+        let mut security = block(Layer::Security, 2, 201);
+        security.header.epoch = 4;
+        security.header.timestamp = block(Layer::Commerce, 127, 0).header.timestamp;
+        chain.insert_block(&security).unwrap();
+        chain
+            .set_finalized_checkpoint(&Checkpoint { epoch: 4, height: 2, block_hash: hash(201) })
+            .unwrap();
+        assert_eq!(pruner.prune().await.unwrap(), 64, "epochs 0 and 1 are outside the window");
+        assert_eq!(pruner.prune().await.unwrap(), 0);
+
+        assert!(chain.block_by_height(Layer::Commerce, 63).unwrap().is_none());
+        assert!(chain.attestations_for_block(&hash(63)).unwrap().is_empty());
+        assert_eq!(chain.block_by_height(Layer::Commerce, 64).unwrap().unwrap().header.epoch, 2);
+        assert_eq!(chain.attestations_for_block(&hash(64)).unwrap().len(), 1);
+        assert!(chain.block_by_hash(&hash(200)).unwrap().is_some(), "security blocks are kept");
+        assert!(chain.verify().unwrap().is_empty());
+
+        let status = storage_status(&config, &chain).unwrap();
+        assert_eq!(status.oldest_commerce_height, Some(64));
+        assert_eq!(status.prune_before_epoch, Some(2));
+    }
+}
@@ -0,0 +1,296 @@
+//! Runtime configuration reload
+//!
+//! On SIGHUP or `admin_reloadConfig` the node loads its configuration again
+//! from the same layers it started with and applies the fields that can
+//! change while running. A reload that touches anything else, or finds the
+//! key files changed, is rejected as a whole: nothing is applied and the
+//! fields that need a restart are named.
+
+use crate::config::{ConfigSource, ValidatorConfig};
+use crate::infrastructure::InfrastructureServices;
+use crate::logging;
+use crate::p2p::P2PNetwork;
+use crate::rpc::RpcServer;
+
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::{broadcast, Mutex};
+use toml::Value;
+use tracing::{error, info, warn};
+
+/// Fields applied without a restart
+const RELOADABLE: &[&str] = &[
+    "logging.level",
+    "p2p.bootstrap_peers",
+    "p2p.max_peers",
+    "rpc.rate_limit_per_second",
+    "rpc.rate_limit_burst",
+    "oon.max_concurrent_jobs",
+    "oon.resource_allocation",
+    "omp.max_storage_gb",
+    "omp.preferred_tiers",
+    "orc20_relayer.max_concurrent_tx",
+    "orc20_relayer.gas_price_multiplier",
+    "paymaster.daily_sponsorship_budget",
+    "paymaster.max_gas_per_tx",
+    "paymaster.max_tx_per_user_per_hour",
+];
+
+/// Reloads the configuration into the running components
+pub struct ConfigReloader {
+    source: ConfigSource,
+    /// Configuration currently in effect
+    current: Mutex<ValidatorConfig>,
+    /// SHA-256 of each file under `keys/` at startup
+    key_digests: BTreeMap<String, Vec<u8>>,
+    p2p_network: Arc<P2PNetwork>,
+    rpc_server: Arc<RpcServer>,
+    infrastructure: Arc<InfrastructureServices>,
+}
+
+impl ConfigReloader {
+    pub fn new(
+        source: ConfigSource,
+        config: &ValidatorConfig,
+        p2p_network: Arc<P2PNetwork>,
+        rpc_server: Arc<RpcServer>,
+        infrastructure: Arc<InfrastructureServices>,
+    ) -> Result<Self> {
+        Ok(Self {
+            source,
+            current: Mutex::new(config.clone()),
+            key_digests: key_digests(&config.data_dir.join("keys"))?,
+            p2p_network,
+            rpc_server,
+            infrastructure,
+        })
+    }
+
+    /// Reload on every SIGHUP until shutdown
+    pub async fn start(&self, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{signal, SignalKind};
+
+            let mut hangup = signal(SignalKind::hangup()).context("Failed to listen for SIGHUP")?;
+            loop {
+                tokio::select! {
+                    _ = hangup.recv() => {
+                        info!("🔁 Received SIGHUP, reloading configuration");
+                        if let Err(e) = self.reload().await {
+                            error!("Configuration reload failed: {:#}", e);
+                        }
+                    }
+                    _ = shutdown.recv() => break,
+                }
+            }
+        }
+
+        #[cfg(not(unix))]
+        let _ = shutdown.recv().await;
+
+        Ok(())
+    }
+
+    /// Load the configuration again and apply it; returns the changed fields
+    pub async fn reload(&self) -> Result<Vec<String>> {
+        let mut current = self.current.lock().await;
+        let new = self.source.load()?.config;
+        new.validate()?;
+
+        let changed = changed_fields(&current, &new)?;
+        let mut restart: Vec<String> =
+            changed.iter().filter(|field| !RELOADABLE.contains(&field.as_str())).cloned().collect();
+        let digests = key_digests(&new.data_dir.join("keys"))?;
+        let changed_keys: BTreeSet<&String> = self
+            .key_digests
+            .keys()
+            .chain(digests.keys())
+            .filter(|name| self.key_digests.get(*name) != digests.get(*name))
+            .collect();
+        restart.extend(changed_keys.into_iter().map(|name| format!("keys/{}", name)));
+        if !restart.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Configuration not reloaded: changing {} requires a restart; restart the node or revert the change",
+                restart.join(", ")
+            ));
+        }
+
+        if changed.is_empty() {
+            info!("🔁 Configuration reloaded, nothing changed");
+            return Ok(changed);
+        }
+
+        if changed.iter().any(|field| field == "logging.level") {
+            logging::set_filter(new.logging.level.as_deref().unwrap_or_else(|| logging::default_filter()))?;
+        }
+        self.p2p_network.reconfigure(&new.p2p).await;
+        self.rpc_server.reconfigure(&new.rpc);
+        self.infrastructure.reconfigure(&new).await;
+
+        warn!("🔁 Configuration reloaded: {}", changed.join(", "));
+        *current = new;
+        Ok(changed)
+    }
+}
+
+/// Dotted paths of the leaves that differ between `old` and `new`
+fn changed_fields(old: &ValidatorConfig, new: &ValidatorConfig) -> Result<Vec<String>> {
+    let (mut old_leaves, mut new_leaves) = (BTreeMap::new(), BTreeMap::new());
+    flatten(&Value::try_from(old)?, "", &mut old_leaves);
+    flatten(&Value::try_from(new)?, "", &mut new_leaves);
+
+    let mut changed: Vec<String> = old_leaves
+        .keys()
+        .chain(new_leaves.keys())
+        .filter(|path| old_leaves.get(*path) != new_leaves.get(*path))
+        .cloned()
+        .collect();
+    changed.sort();
+    changed.dedup();
+    Ok(changed)
+}
+
+fn flatten(value: &Value, prefix: &str, leaves: &mut BTreeMap<String, Value>) {
+    match value.as_table() {
+        Some(table) => {
+            for (key, child) in table {
+                let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                flatten(child, &path, leaves);
+            }
+        }
+        None => {
+            leaves.insert(prefix.to_string(), value.clone());
+        }
+    }
+}
+
+fn key_digests(keys_dir: &Path) -> Result<BTreeMap<String, Vec<u8>>> {
+    let mut digests = BTreeMap::new();
+    if !keys_dir.exists() {
+        return Ok(digests);
+    }
+    for entry in std::fs::read_dir(keys_dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() {
+            let digest = Sha256::digest(std::fs::read(entry.path())?);
+            digests.insert(entry.file_name().to_string_lossy().into_owned(), digest.to_vec());
+        }
+    }
+    Ok(digests)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::ChainStore;
+    use crate::consensus::PoVERAValidator;
+    use crate::events::EventBus;
+    use crate::store::Store;
+    use std::path::PathBuf;
+
+    /// A reloader over the components of a node started from `dir/config.toml`,
+    /// with OMP and the paymaster enabled
+    async fn test_reloader(
+        dir: &PathBuf,
+    ) -> (ConfigReloader, Arc<P2PNetwork>, Arc<InfrastructureServices>, ValidatorConfig) {
+        let mut config = ValidatorConfig::new_for_network("devnet").unwrap();
+        config.data_dir = dir.clone();
+        config.init_directories(dir).unwrap();
+        config.enable_service("omp").unwrap();
+        config.enable_service("paymaster").unwrap();
+        let path = dir.join("config.toml");
+        config.save_to_file(&path).unwrap();
+
+        let store = Store::temporary();
+        let chain = Arc::new(ChainStore::open(&store).unwrap());
+        let events = EventBus::new();
+        let consensus = Arc::new(PoVERAValidator::new(&config, chain.clone(), None, events.clone()).await.unwrap());
+        let p2p_network =
+            Arc::new(P2PNetwork::new(&config, consensus.clone(), store.db(), events.clone()).await.unwrap());
+        let infrastructure = Arc::new(InfrastructureServices::new(&config).await.unwrap());
+        let (shutdown, _) = broadcast::channel(1);
+        let rpc_server = Arc::new(
+            RpcServer::new(&config, consensus, p2p_network.clone(), infrastructure.clone(), chain, events, shutdown)
+                .await
+                .unwrap(),
+        );
+
+        let source = ConfigSource { path, chain_spec: None, overrides: vec![] };
+        let reloader =
+            ConfigReloader::new(source, &config, p2p_network.clone(), rpc_server, infrastructure.clone()).unwrap();
+        (reloader, p2p_network, infrastructure, config)
+    }
+
+    #[tokio::test]
+    async fn test_reload_applies_runtime_fields() {
+        let dir = std::env::temp_dir().join(format!("omne-reload-{}", rand::random::<u32>()));
+        let (reloader, p2p_network, infrastructure, mut config) = test_reloader(&dir).await;
+        assert!(reloader.reload().await.unwrap().is_empty());
+
+        config.p2p.max_peers = 7;
+        config.rpc.rate_limit_burst += 1;
+        config.omp.max_storage_gb = 250;
+        config.paymaster.max_gas_per_tx = 90_000;
+        config.save_to_file(&dir.join("config.toml")).unwrap();
+        let changed = reloader.reload().await.unwrap();
+        assert_eq!(
+            changed,
+            ["omp.max_storage_gb", "p2p.max_peers", "paymaster.max_gas_per_tx", "rpc.rate_limit_burst"]
+        );
+        assert_eq!(p2p_network.status().await.unwrap().max_peers, 7);
+        assert_eq!(infrastructure.limits().await, (Some(250), Some(90_000)));
+
+        // Applied changes become the baseline for the next reload
+        assert!(reloader.reload().await.unwrap().is_empty());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn test_reload_rejects_restart_fields() {
+        let dir = std::env::temp_dir().join(format!("omne-reload-{}", rand::random::<u32>()));
+        let (reloader, p2p_network, infrastructure, mut config) = test_reloader(&dir).await;
+        let max_peers = config.p2p.max_peers;
+        let limits = infrastructure.limits().await;
+
+        config.p2p.max_peers = max_peers + 1;
+        config.omp.max_storage_gb += 1;
+        config.p2p.port += 1;
+        config.oon.enable_oon = true;
+        config.save_to_file(&dir.join("config.toml")).unwrap();
+        let err = reloader.reload().await.unwrap_err().to_string();
+        assert!(err.contains("oon.enable_oon, p2p.port requires a restart"), "{}", err);
+        assert_eq!(p2p_network.status().await.unwrap().max_peers, max_peers, "nothing is applied");
+        assert_eq!(infrastructure.limits().await, limits);
+
+        // So is a changed key file
+        config.p2p.port -= 1;
+        config.oon.enable_oon = false;
+        config.save_to_file(&dir.join("config.toml")).unwrap();
+        std::fs::write(dir.join("keys").join("oon.key"), "replaced").unwrap();
+        let err = reloader.reload().await.unwrap_err().to_string();
+        assert!(err.contains("keys/oon.key"), "{}", err);
+        assert_eq!(p2p_network.status().await.unwrap().max_peers, max_peers);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_changed_fields() {
+        let old = ValidatorConfig::new_for_network("devnet").unwrap();
+        let mut new = old.clone();
+        assert!(changed_fields(&old, &new).unwrap().is_empty());
+
+        new.rpc.rate_limit_burst += 1;
+        new.p2p.bootstrap_peers.push("/ip4/10.0.0.1/tcp/30303".to_string());
+        new.logging.level = Some("debug".to_string());
+        new.network.id = 99;
+        let changed = changed_fields(&old, &new).unwrap();
+        assert_eq!(changed, ["logging.level", "network.id", "p2p.bootstrap_peers", "rpc.rate_limit_burst"]);
+
+        let restart: Vec<&String> = changed.iter().filter(|f| !RELOADABLE.contains(&f.as_str())).collect();
+        assert_eq!(restart, ["network.id"]);
+    }
+}
@@ -1,29 +1,130 @@
 //! JSON-RPC server for validator queries and control
 
-use crate::config::ValidatorConfig;
+mod admin;
+pub mod auth;
+mod blocks;
+pub mod client;
+mod discover;
+mod health;
+mod http;
+pub mod ipc;
+mod methods;
+mod rate_limit;
+mod stats;
+mod ws;
+
+use crate::chain::ChainStore;
+use crate::config::{RpcConfig, ValidatorConfig};
 use crate::consensus::PoVERAValidator;
+use crate::events::EventBus;
+use crate::infrastructure::InfrastructureServices;
 use crate::p2p::P2PNetwork;
+use crate::reload::ConfigReloader;
+use auth::Authenticator;
+use methods::MethodTable;
+use rate_limit::RateLimiter;
+use stats::RpcStats;
+pub use stats::MethodStats;
+pub use health::HealthResult;
+pub use methods::ValidatorStatusResult;
+use ws::WsSession;
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::collections::HashMap;
 use std::net::SocketAddr;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock, Weak};
 use tokio::net::TcpListener;
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, Semaphore};
 use tracing::{info, debug, warn};
 
+/// JSON-RPC 2.0 error codes
+pub const PARSE_ERROR: i32 = -32700;
+pub const INVALID_REQUEST: i32 = -32600;
+pub const METHOD_NOT_FOUND: i32 = -32601;
+pub const INVALID_PARAMS: i32 = -32602;
+pub const INTERNAL_ERROR: i32 = -32603;
+
+/// Server-defined error: requested block or checkpoint is unknown
+pub const NOT_FOUND: i32 = -32001;
+/// Server-defined error: the node does not have this kind of data yet
+pub const NOT_AVAILABLE: i32 = -32002;
+/// Server-defined error: admin method called without credentials
+pub const FORBIDDEN: i32 = -32003;
+/// Server-defined error: caller exceeded its request rate
+pub const RATE_LIMITED: i32 = -32005;
+
 /// JSON-RPC server for validator API
 pub struct RpcServer {
     config: ValidatorConfig,
     consensus: Arc<PoVERAValidator>,
     p2p_network: Arc<P2PNetwork>,
+    infrastructure: Arc<InfrastructureServices>,
+    /// Blocks, attestations and validators served by query methods
+    chain: Arc<ChainStore>,
+    /// Registered method handlers
+    methods: MethodTable,
+    /// Verifies admin credentials on HTTP and WebSocket
+    auth: Authenticator,
+    /// Node-wide shutdown trigger used by `admin_shutdown`
+    shutdown: broadcast::Sender<()>,
     bind_address: SocketAddr,
+    /// Permits for concurrent connections, sized by `max_connections`
+    connection_permits: Arc<Semaphore>,
+    /// Source of subscription notifications
+    events: EventBus,
+    /// Per-IP request budget for HTTP and WebSocket
+    rate_limiter: RateLimiter,
+    /// Request, error and latency counters
+    stats: RpcStats,
+    /// Serves `admin_reloadConfig`, set once the node has built it
+    reloader: OnceLock<Weak<ConfigReloader>>,
+}
+
+/// A parsed JSON-RPC payload
+enum Payload {
+    Single(Value),
+    Batch(Vec<Value>),
+}
+
+impl Payload {
+    /// Number of calls, each charged against the rate limit
+    fn calls(&self) -> usize {
+        match self {
+            Payload::Single(_) => 1,
+            Payload::Batch(batch) => batch.len(),
+        }
+    }
 }
 
-/// RPC method handler
-type RpcHandler = fn(&RpcServer, &[Value]) -> Result<Value>;
+/// Transport a payload arrived on
+#[derive(Clone, Copy)]
+enum Transport<'a> {
+    /// HTTP request; `admin` is set when it carried valid credentials
+    Http { admin: bool },
+    /// WebSocket connection with its subscription state
+    WebSocket(&'a WsSession),
+    /// Local Unix socket, trusted with admin methods
+    Ipc,
+}
+
+impl<'a> Transport<'a> {
+    fn session(&self) -> Option<&'a WsSession> {
+        match self {
+            Transport::WebSocket(session) => Some(session),
+            _ => None,
+        }
+    }
+
+    /// Whether `admin_*` methods may be called over this transport
+    fn allows_admin(&self, config: &RpcConfig) -> bool {
+        match self {
+            Transport::Ipc => true,
+            Transport::Http { admin } => config.enable_admin_http && *admin,
+            Transport::WebSocket(session) => config.enable_admin_http && session.is_admin(),
+        }
+    }
+}
 
 /// RPC status information  
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,6 +133,10 @@ pub struct RpcStatus {
     pub bind_address: String,
     pub active_connections: usize,
     pub total_requests: u64,
+    pub total_errors: u64,
+    /// HTTP requests and WebSocket messages rejected by the rate limiter
+    pub rate_limited_requests: u64,
+    pub methods: Vec<MethodStats>,
 }
 
 /// JSON-RPC request structure
@@ -39,12 +144,12 @@ pub struct RpcStatus {
 struct JsonRpcRequest {
     jsonrpc: String,
     method: String,
-    params: Option<Vec<Value>>,
+    params: Option<Value>,
     id: Option<Value>,
 }
 
 /// JSON-RPC response structure
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct JsonRpcResponse {
     jsonrpc: String,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -55,7 +160,7 @@ struct JsonRpcResponse {
 }
 
 /// JSON-RPC error structure
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct JsonRpcError {
     code: i32,
     message: String,
@@ -63,12 +168,52 @@ struct JsonRpcError {
     data: Option<Value>,
 }
 
+impl JsonRpcError {
+    fn new(code: i32, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+}
+
+impl From<anyhow::Error> for JsonRpcError {
+    fn from(e: anyhow::Error) -> Self {
+        Self::new(INTERNAL_ERROR, format!("Internal error: {}", e))
+    }
+}
+
+impl JsonRpcResponse {
+    fn result(id: Option<Value>, result: Value) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            result: Some(result),
+            error: None,
+            id,
+        }
+    }
+
+    fn error(id: Option<Value>, code: i32, message: impl Into<String>) -> Self {
+        Self {
+            jsonrpc: "2.0".to_string(),
+            result: None,
+            error: Some(JsonRpcError::new(code, message)),
+            id,
+        }
+    }
+}
+
 impl RpcServer {
     /// Create a new RPC server
     pub async fn new(
         config: &ValidatorConfig,
         consensus: Arc<PoVERAValidator>,
         p2p_network: Arc<P2PNetwork>,
+        infrastructure: Arc<InfrastructureServices>,
+        chain: Arc<ChainStore>,
+        events: EventBus,
+        shutdown: broadcast::Sender<()>,
     ) -> Result<Self> {
         let bind_address: SocketAddr = format!("{}:{}", config.rpc.bind_address, config.rpc.port)
             .parse()?;
@@ -77,21 +222,67 @@ impl RpcServer {
         info!("   Bind Address: {}", bind_address);
         info!("   HTTP Enabled: {}", config.rpc.enable_http);
         info!("   WebSocket Enabled: {}", config.rpc.enable_ws);
+        info!("   IPC Enabled: {}", config.rpc.enable_ipc);
 
         Ok(Self {
             config: config.clone(),
             consensus,
             p2p_network,
+            infrastructure,
+            chain,
+            methods: MethodTable::new(),
+            auth: Self::authenticator(config)?,
+            shutdown,
             bind_address,
+            connection_permits: Arc::new(Semaphore::new(config.rpc.max_connections)),
+            events,
+            rate_limiter: RateLimiter::new(&config.rpc),
+            stats: RpcStats::default(),
+            reloader: OnceLock::new(),
         })
     }
 
+    /// Serve `admin_reloadConfig` with `reloader`
+    pub fn set_reloader(&self, reloader: &Arc<ConfigReloader>) {
+        let _ = self.reloader.set(Arc::downgrade(reloader));
+    }
+
+    /// Apply reloaded rate limits
+    pub fn reconfigure(&self, config: &RpcConfig) {
+        self.rate_limiter.reconfigure(config);
+    }
+
+    /// Load admin credentials, creating the JWT secret if it is missing
+    fn authenticator(config: &ValidatorConfig) -> Result<Authenticator> {
+        if !config.rpc.enable_admin_http {
+            return Ok(Authenticator::new(None, None));
+        }
+
+        let secret_path = config.rpc.jwt_secret_path(&config.data_dir);
+        auth::ensure_secret(&secret_path)?;
+        info!("   Admin JWT Secret: {}", secret_path.display());
+        Ok(Authenticator::new(
+            Some(auth::load_secret(&secret_path)?),
+            config.rpc.admin_token.clone(),
+        ))
+    }
+
     /// Start the RPC server
-    pub async fn start(&self, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+    pub async fn start(self: Arc<Self>, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
         info!("🚀 Starting RPC server on {}", self.bind_address);
 
-        if !self.config.rpc.enable_http {
-            info!("📵 HTTP RPC disabled, waiting for shutdown signal");
+        if self.config.rpc.enable_ipc {
+            let server = self.clone();
+            let shutdown = shutdown.resubscribe();
+            tokio::spawn(async move {
+                if let Err(e) = ipc::serve(server, shutdown).await {
+                    warn!("IPC RPC endpoint failed: {}", e);
+                }
+            });
+        }
+
+        if !self.config.rpc.enable_http && !self.config.rpc.enable_ws {
+            info!("📵 HTTP and WebSocket RPC disabled, waiting for shutdown signal");
             let _ = shutdown.recv().await;
             return Ok(());
         }
@@ -105,8 +296,15 @@ impl RpcServer {
                     match result {
                         Ok((stream, addr)) => {
                             debug!("🔌 New RPC connection from {}", addr);
-                            // TODO: Handle HTTP/WebSocket upgrade and process requests
-                            let _ = stream; // Placeholder
+                            match self.connection_permits.clone().try_acquire_owned() {
+                                Ok(permit) => {
+                                    tokio::spawn(http::serve_connection(self.clone(), stream, addr.ip(), permit));
+                                }
+                                Err(_) => {
+                                    warn!("RPC connection limit reached, rejecting {}", addr);
+                                    tokio::spawn(http::reject_connection(stream));
+                                }
+                            }
                         }
                         Err(e) => {
                             warn!("Failed to accept RPC connection: {}", e);
@@ -124,189 +322,265 @@ impl RpcServer {
         Ok(())
     }
 
-    /// Process a JSON-RPC request
-    async fn process_request(&self, request: JsonRpcRequest) -> JsonRpcResponse {
-        let id = request.id.clone();
-
-        // Validate JSON-RPC version
-        if request.jsonrpc != "2.0" {
-            return JsonRpcResponse {
-                jsonrpc: "2.0".to_string(),
-                result: None,
-                error: Some(JsonRpcError {
-                    code: -32600,
-                    message: "Invalid Request".to_string(),
-                    data: None,
-                }),
-                id,
-            };
+    /// Handle a raw JSON-RPC payload containing a single request or a batch
+    ///
+    /// Returns `None` when there is nothing to send back, i.e. the payload
+    /// only contained notifications. WebSocket connections additionally
+    /// support `subscribe`/`unsubscribe`.
+    async fn handle_payload(self: &Arc<Self>, payload: &[u8], transport: Transport<'_>) -> Option<Value> {
+        match self.parse_payload(payload) {
+            Ok(payload) => self.dispatch(payload, transport).await,
+            Err(reply) => Some(reply),
         }
+    }
 
-        // Get method handler
-        let methods = self.get_rpc_methods();
-        let params = request.params.unwrap_or_default();
+    /// Parse a raw payload, or return the error response to send instead
+    fn parse_payload(&self, payload: &[u8]) -> Result<Payload, Value> {
+        let value: Value = serde_json::from_slice(payload)
+            .map_err(|e| json!(JsonRpcResponse::error(None, PARSE_ERROR, format!("Parse error: {}", e))))?;
 
-        let result = match methods.get(&request.method) {
-            Some(handler) => match handler(self, &params) {
-                Ok(result) => Some(result),
-                Err(e) => {
-                    return JsonRpcResponse {
-                        jsonrpc: "2.0".to_string(),
-                        result: None,
-                        error: Some(JsonRpcError {
-                            code: -32603,
-                            message: format!("Internal error: {}", e),
-                            data: None,
-                        }),
-                        id,
-                    };
+        match value {
+            Value::Array(batch) if batch.is_empty() => {
+                Err(json!(JsonRpcResponse::error(None, INVALID_REQUEST, "Invalid Request: empty batch")))
+            }
+            Value::Array(batch) if batch.len() > self.config.rpc.max_batch_size => Err(json!(JsonRpcResponse::error(
+                None,
+                INVALID_REQUEST,
+                format!(
+                    "Invalid Request: batch of {} calls exceeds the limit of {}",
+                    batch.len(),
+                    self.config.rpc.max_batch_size
+                ),
+            ))),
+            Value::Array(batch) => Ok(Payload::Batch(batch)),
+            call => Ok(Payload::Single(call)),
+        }
+    }
+
+    /// Handle the calls of a parsed payload
+    async fn dispatch(self: &Arc<Self>, payload: Payload, transport: Transport<'_>) -> Option<Value> {
+        match payload {
+            Payload::Batch(batch) => {
+                let responses: Vec<JsonRpcResponse> =
+                    futures::future::join_all(batch.into_iter().map(|call| self.handle_call(call, transport)))
+                        .await
+                        .into_iter()
+                        .flatten()
+                        .collect();
+
+                if responses.is_empty() {
+                    None
+                } else {
+                    Some(json!(responses))
                 }
-            },
-            None => {
-                return JsonRpcResponse {
-                    jsonrpc: "2.0".to_string(),
-                    result: None,
-                    error: Some(JsonRpcError {
-                        code: -32601,
-                        message: "Method not found".to_string(),
-                        data: None,
-                    }),
-                    id,
-                };
             }
+            Payload::Single(call) => self.handle_call(call, transport).await.map(|response| json!(response)),
+        }
+    }
+
+    /// Handle one call of a payload; notifications (no `id`) yield no
+    /// response, but an invalid request is always answered, with a null
+    /// `id` when it has none
+    async fn handle_call(self: &Arc<Self>, call: Value, transport: Transport<'_>) -> Option<JsonRpcResponse> {
+        let has_id = call.as_object().is_none_or(|obj| obj.contains_key("id"));
+        let id = call.get("id").cloned();
+
+        let started = std::time::Instant::now();
+        let (method, response, is_notification) = match parse_request(call) {
+            Ok(request) => (
+                self.methods.name(&request.method),
+                self.process_request(request, transport).await,
+                !has_id,
+            ),
+            Err(message) => (None, JsonRpcResponse::error(id, INVALID_REQUEST, message), false),
         };
+        self.stats.record(method, started.elapsed(), response.error.is_some());
 
-        JsonRpcResponse {
-            jsonrpc: "2.0".to_string(),
-            result,
-            error: None,
-            id,
+        if is_notification {
+            None
+        } else {
+            Some(response)
         }
     }
 
-    /// Get available RPC methods
-    fn get_rpc_methods(&self) -> HashMap<String, RpcHandler> {
-        let mut methods: HashMap<String, RpcHandler> = HashMap::new();
-
-        // Validator status methods
-        methods.insert("validator_status".to_string(), Self::rpc_validator_status);
-        methods.insert("consensus_status".to_string(), Self::rpc_consensus_status);
-        methods.insert("p2p_status".to_string(), Self::rpc_p2p_status);
-        
-        // Network methods
-        methods.insert("network_info".to_string(), Self::rpc_network_info);
-        methods.insert("peer_list".to_string(), Self::rpc_peer_list);
-        
-        // Block and transaction methods
-        methods.insert("latest_block".to_string(), Self::rpc_latest_block);
-        methods.insert("block_by_height".to_string(), Self::rpc_block_by_height);
-        
-        // Utility methods
-        methods.insert("health".to_string(), Self::rpc_health);
-        methods.insert("version".to_string(), Self::rpc_version);
-
-        methods
-    }
+    /// Process a JSON-RPC request checked by `parse_request`
+    async fn process_request(self: &Arc<Self>, request: JsonRpcRequest, transport: Transport<'_>) -> JsonRpcResponse {
+        let id = request.id.clone();
+        let params = request.params.unwrap_or_default();
+
+        // Subscriptions are scoped to the WebSocket connection
+        let positional = params.as_array().map(Vec::as_slice).unwrap_or_default();
+        if let Some(outcome) = ws::handle_subscription_call(transport.session(), &request.method, positional) {
+            return match outcome {
+                Ok(result) => JsonRpcResponse::result(id, result),
+                Err((code, message)) => JsonRpcResponse::error(id, code, message),
+            };
+        }
 
-    /// RPC method: validator_status
-    fn rpc_validator_status(&self, _params: &[Value]) -> Result<Value> {
-        // TODO: Get actual validator status
-        Ok(json!({
-            "is_validator": self.config.validator.is_validator,
-            "stake": self.config.validator.validator_stake,
-            "is_active": true,
-            "uptime": "99.9%"
-        }))
+        if is_admin_method(&request.method) && !transport.allows_admin(&self.config.rpc) {
+            return JsonRpcResponse::error(id, FORBIDDEN, "Admin methods require authentication");
+        }
+
+        let Some(call) = self.methods.call(self.clone(), &request.method, params) else {
+            return JsonRpcResponse::error(id, METHOD_NOT_FOUND, "Method not found");
+        };
+
+        match call.await {
+            Ok(result) => JsonRpcResponse::result(id, result),
+            Err(error) => JsonRpcResponse {
+                jsonrpc: "2.0".to_string(),
+                result: None,
+                error: Some(error),
+                id,
+            },
+        }
     }
 
-    /// RPC method: consensus_status
-    fn rpc_consensus_status(&self, _params: &[Value]) -> Result<Value> {
-        // TODO: Get actual consensus status from consensus module
-        Ok(json!({
-            "commerce_epoch": 0,
-            "security_epoch": 0,
-            "commerce_height": 0,
-            "security_height": 0
-        }))
+    /// Get RPC server status
+    pub async fn status(&self) -> Result<RpcStatus> {
+        Ok(RpcStatus {
+            is_running: true,
+            bind_address: self.bind_address.to_string(),
+            active_connections: self.config.rpc.max_connections
+                - self.connection_permits.available_permits(),
+            total_requests: self.stats.total_requests(),
+            total_errors: self.stats.total_errors(),
+            rate_limited_requests: self.stats.rate_limited(),
+            methods: self.stats.methods(),
+        })
     }
+}
 
-    /// RPC method: p2p_status
-    fn rpc_p2p_status(&self, _params: &[Value]) -> Result<Value> {
-        // TODO: Get actual P2P status
-        Ok(json!({
-            "connected_peers": 0,
-            "network_id": self.config.network.id,
-            "local_peer_id": "placeholder"
-        }))
+/// OpenRPC document describing every method the node serves
+pub fn openrpc_document() -> Value {
+    discover::document(&MethodTable::new())
+}
+
+/// Check that `call` is a well-formed request, defaulting absent params to
+/// an empty array; an error is the `INVALID_REQUEST` message
+fn parse_request(call: Value) -> Result<JsonRpcRequest, String> {
+    let mut request =
+        serde_json::from_value::<JsonRpcRequest>(call).map_err(|e| format!("Invalid Request: {}", e))?;
+    if request.jsonrpc != "2.0" {
+        return Err("Invalid Request".to_string());
+    }
+    match request.params {
+        None => request.params = Some(Value::Array(Vec::new())),
+        Some(Value::Array(_) | Value::Object(_)) => {}
+        Some(_) => return Err("Invalid Request: params must be an array or object".to_string()),
     }
+    Ok(request)
+}
+
+/// Methods in the `admin_` namespace change node state
+fn is_admin_method(method: &str) -> bool {
+    method.starts_with("admin_")
+}
 
-    /// RPC method: network_info
-    fn rpc_network_info(&self, _params: &[Value]) -> Result<Value> {
-        Ok(json!({
-            "network_name": self.config.network.name,
-            "network_id": self.config.network.id,
-            "genesis_hash": self.config.network.genesis_hash,
-            "commerce_block_time": self.config.network.chain_spec.commerce_block_time,
-            "security_block_time": self.config.network.chain_spec.security_block_time
-        }))
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::Store;
+
+    pub(super) async fn test_server() -> RpcServer {
+        let mut config = ValidatorConfig::new_for_network("devnet").unwrap();
+        config.data_dir = std::env::temp_dir().join(format!("omne-rpc-{}", rand::random::<u32>()));
+        let store = Store::temporary();
+        let chain = Arc::new(ChainStore::open(&store).unwrap());
+        let events = EventBus::new();
+        let consensus = Arc::new(PoVERAValidator::new(&config, chain.clone(), None, events.clone()).await.unwrap());
+        let p2p_network = Arc::new(
+            P2PNetwork::new(&config, consensus.clone(), store.db(), events.clone()).await.unwrap()
+        );
+        let infrastructure = Arc::new(InfrastructureServices::new(&config).await.unwrap());
+        let (shutdown, _) = broadcast::channel(1);
+        RpcServer::new(&config, consensus, p2p_network, infrastructure, chain, events, shutdown)
+            .await
+            .unwrap()
     }
 
-    /// RPC method: peer_list
-    fn rpc_peer_list(&self, _params: &[Value]) -> Result<Value> {
-        Ok(json!({
-            "peers": []
-        }))
+    async fn call(server: &Arc<RpcServer>, payload: &str) -> Option<Value> {
+        server.handle_payload(payload.as_bytes(), Transport::Http { admin: false }).await
     }
 
-    /// RPC method: latest_block
-    fn rpc_latest_block(&self, _params: &[Value]) -> Result<Value> {
-        Ok(json!({
-            "height": 0,
-            "hash": "bh_000000000000000000000000000000000000000000000000000000000000000000",
-            "timestamp": chrono::Utc::now().timestamp()
-        }))
+    #[tokio::test]
+    async fn test_single_request() {
+        let server = Arc::new(test_server().await);
+        let reply = call(&server, r#"{"jsonrpc":"2.0","method":"version","id":1}"#).await.unwrap();
+        assert_eq!(reply["id"], 1);
+        assert_eq!(reply["result"]["name"], env!("CARGO_PKG_NAME"));
     }
 
-    /// RPC method: block_by_height
-    fn rpc_block_by_height(&self, params: &[Value]) -> Result<Value> {
-        if params.is_empty() {
-            return Err(anyhow::anyhow!("Missing height parameter"));
-        }
+    #[tokio::test]
+    async fn test_malformed_payloads() {
+        let server = Arc::new(test_server().await);
 
-        let _height = params[0].as_u64()
-            .ok_or_else(|| anyhow::anyhow!("Invalid height parameter"))?;
+        let reply = call(&server, "{not json").await.unwrap();
+        assert_eq!(reply["error"]["code"], PARSE_ERROR);
+        assert_eq!(reply["id"], Value::Null);
 
-        Ok(json!({
-            "height": _height,
-            "hash": "bh_000000000000000000000000000000000000000000000000000000000000000000",
-            "timestamp": chrono::Utc::now().timestamp()
-        }))
-    }
+        let reply = call(&server, "[]").await.unwrap();
+        assert_eq!(reply["error"]["code"], INVALID_REQUEST);
+
+        let calls = vec![r#"{"jsonrpc":"2.0","method":"version","id":1}"#; server.config.rpc.max_batch_size + 1];
+        let reply = call(&server, &format!("[{}]", calls.join(","))).await.unwrap();
+        assert_eq!(reply["error"]["code"], INVALID_REQUEST);
+        assert!(reply["error"]["message"].as_str().unwrap().contains("exceeds the limit"));
+
+        let reply = call(&server, r#"{"jsonrpc":"2.0","id":7}"#).await.unwrap();
+        assert_eq!(reply["error"]["code"], INVALID_REQUEST);
+        assert_eq!(reply["id"], 7);
 
-    /// RPC method: health
-    fn rpc_health(&self, _params: &[Value]) -> Result<Value> {
-        Ok(json!({
-            "status": "healthy",
-            "timestamp": chrono::Utc::now().to_rfc3339()
-        }))
+        let reply = call(&server, r#"{"jsonrpc":"1.0","method":"version","id":1}"#).await.unwrap();
+        assert_eq!(reply["error"]["code"], INVALID_REQUEST);
+
+        let reply = call(&server, r#"{"jsonrpc":"2.0","method":"nope","id":1}"#).await.unwrap();
+        assert_eq!(reply["error"]["code"], METHOD_NOT_FOUND);
     }
 
-    /// RPC method: version
-    fn rpc_version(&self, _params: &[Value]) -> Result<Value> {
-        Ok(json!({
-            "version": env!("CARGO_PKG_VERSION"),
-            "name": env!("CARGO_PKG_NAME"),
-            "git_commit": "unknown" // TODO: Include git commit hash
-        }))
+    #[tokio::test]
+    async fn test_batch_and_notifications() {
+        let server = Arc::new(test_server().await);
+
+        assert!(call(&server, r#"{"jsonrpc":"2.0","method":"version"}"#).await.is_none());
+        assert!(call(&server, r#"[{"jsonrpc":"2.0","method":"health"}]"#).await.is_none());
+
+        let reply = call(
+            &server,
+            r#"[
+                {"jsonrpc":"2.0","method":"version","id":1},
+                {"jsonrpc":"2.0","method":"health"},
+                1,
+                {"jsonrpc":"2.0","method":"network_info","id":"b"}
+            ]"#,
+        )
+        .await
+        .unwrap();
+
+        let replies = reply.as_array().unwrap();
+        assert_eq!(replies.len(), 3);
+        assert_eq!(replies[0]["id"], 1);
+        assert_eq!(replies[1]["error"]["code"], INVALID_REQUEST);
+        assert_eq!(replies[2]["id"], "b");
+        assert_eq!(replies[2]["result"]["network_name"], "devnet");
     }
 
-    /// Get RPC server status
-    pub async fn status(&self) -> Result<RpcStatus> {
-        Ok(RpcStatus {
-            is_running: true,
-            bind_address: self.bind_address.to_string(),
-            active_connections: 0, // TODO: Track actual connections
-            total_requests: 0,     // TODO: Track actual requests
-        })
+    #[tokio::test]
+    async fn test_invalid_request_without_id_is_answered() {
+        let server = Arc::new(test_server().await);
+
+        for payload in [
+            r#"{"jsonrpc":"2.0"}"#,
+            r#"{"jsonrpc":"1.0","method":"version"}"#,
+            r#"{"jsonrpc":"2.0","method":"version","params":1}"#,
+        ] {
+            let reply = call(&server, payload).await.expect(payload);
+            assert_eq!(reply["error"]["code"], INVALID_REQUEST, "{}", payload);
+            assert_eq!(reply.get("id"), Some(&Value::Null), "{}", payload);
+        }
+
+        let reply = call(&server, r#"[{"jsonrpc":"2.0","method":"health"},{"method":"version"}]"#).await.unwrap();
+        assert_eq!(reply.as_array().unwrap().len(), 1);
+        assert_eq!(reply[0]["error"]["code"], INVALID_REQUEST);
     }
 }
@@ -0,0 +1,162 @@
+//! `admin_*` RPC namespace for operating the node remotely
+//!
+//! These methods change node state, so the dispatcher only lets them through
+//! over IPC or for HTTP/WebSocket callers authenticated by `auth`.
+
+use super::methods::{MethodTable, NoParams, RpcResult};
+use super::{JsonRpcError, RpcServer, INVALID_PARAMS};
+use crate::logging;
+
+use libp2p::{Multiaddr, PeerId};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::sync::Arc;
+use tracing::warn;
+
+/// Params of `admin_addPeer`
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AddPeerParams {
+    /// Multiaddr to dial, ideally ending in `/p2p/<peer id>`
+    pub address: String,
+}
+
+/// Params of `admin_removePeer` and `admin_banPeer`
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct PeerIdParams {
+    pub peer_id: String,
+}
+
+/// Params of `admin_setLogLevel`
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SetLogLevelParams {
+    /// `tracing` filter directives, e.g. `"debug,libp2p=info"`
+    pub filter: String,
+}
+
+/// Register the admin namespace
+pub(super) fn register(table: &mut MethodTable) {
+    table.register("admin_addPeer", RpcServer::rpc_admin_add_peer);
+    table.register("admin_removePeer", RpcServer::rpc_admin_remove_peer);
+    table.register("admin_banPeer", RpcServer::rpc_admin_ban_peer);
+    table.register("admin_setLogLevel", RpcServer::rpc_admin_set_log_level);
+    table.register("admin_reloadConfig", RpcServer::rpc_admin_reload_config);
+    table.register("admin_pauseValidation", RpcServer::rpc_admin_pause_validation);
+    table.register("admin_resumeValidation", RpcServer::rpc_admin_resume_validation);
+    table.register("admin_shutdown", RpcServer::rpc_admin_shutdown);
+}
+
+fn parse_peer_id(peer_id: &str) -> RpcResult<PeerId> {
+    peer_id
+        .parse()
+        .map_err(|e| JsonRpcError::new(INVALID_PARAMS, format!("Invalid peer ID: {}", e)))
+}
+
+impl RpcServer {
+    /// RPC method: admin_addPeer
+    async fn rpc_admin_add_peer(self: Arc<Self>, params: AddPeerParams) -> RpcResult<bool> {
+        let address: Multiaddr = params
+            .address
+            .parse()
+            .map_err(|e| JsonRpcError::new(INVALID_PARAMS, format!("Invalid address: {}", e)))?;
+        self.p2p_network.add_peer(address).await?;
+        Ok(true)
+    }
+
+    /// RPC method: admin_removePeer; returns whether the peer was connected
+    async fn rpc_admin_remove_peer(self: Arc<Self>, params: PeerIdParams) -> RpcResult<bool> {
+        let peer_id = parse_peer_id(&params.peer_id)?;
+        Ok(self.p2p_network.remove_peer(peer_id).await?)
+    }
+
+    /// RPC method: admin_banPeer
+    async fn rpc_admin_ban_peer(self: Arc<Self>, params: PeerIdParams) -> RpcResult<bool> {
+        let peer_id = parse_peer_id(&params.peer_id)?;
+        self.p2p_network.ban_peer(peer_id).await?;
+        Ok(true)
+    }
+
+    /// RPC method: admin_setLogLevel
+    async fn rpc_admin_set_log_level(self: Arc<Self>, params: SetLogLevelParams) -> RpcResult<bool> {
+        logging::set_filter(&params.filter)
+            .map_err(|e| JsonRpcError::new(INVALID_PARAMS, e.to_string()))?;
+        warn!("📝 Log filter changed to {:?} via admin RPC", params.filter);
+        Ok(true)
+    }
+
+    /// RPC method: admin_reloadConfig; reloads config.toml like SIGHUP and
+    /// returns the fields that changed
+    async fn rpc_admin_reload_config(self: Arc<Self>, _params: NoParams) -> RpcResult<Vec<String>> {
+        let reloader = self
+            .reloader
+            .get()
+            .and_then(|reloader| reloader.upgrade())
+            .ok_or_else(|| anyhow::anyhow!("Configuration reload is not available"))?;
+        // Keep the cause, e.g. the TOML parse error, in the reply
+        Ok(reloader.reload().await.map_err(|e| anyhow::anyhow!("{:#}", e))?)
+    }
+
+    /// RPC method: admin_pauseValidation; returns whether validation was running
+    async fn rpc_admin_pause_validation(self: Arc<Self>, _params: NoParams) -> RpcResult<bool> {
+        Ok(self.consensus.pause_validation())
+    }
+
+    /// RPC method: admin_resumeValidation; returns whether validation was paused
+    async fn rpc_admin_resume_validation(self: Arc<Self>, _params: NoParams) -> RpcResult<bool> {
+        Ok(self.consensus.resume_validation())
+    }
+
+    /// RPC method: admin_shutdown
+    async fn rpc_admin_shutdown(self: Arc<Self>, _params: NoParams) -> RpcResult<bool> {
+        warn!("🛑 Shutdown requested via admin RPC");
+        self.shutdown
+            .send(())
+            .map_err(|_| anyhow::anyhow!("Node is already shutting down"))?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rpc::tests::test_server;
+    use crate::rpc::{Transport, FORBIDDEN, INVALID_PARAMS};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_admin_namespace() {
+        let server = Arc::new(test_server().await);
+        let pause = br#"{"jsonrpc":"2.0","method":"admin_pauseValidation","id":1}"#;
+
+        // Unauthenticated network callers are read-only
+        let reply = server.handle_payload(pause, Transport::Http { admin: false }).await.unwrap();
+        assert_eq!(reply["error"]["code"], FORBIDDEN);
+        assert!(!server.consensus.is_validation_paused());
+
+        let reply = server.handle_payload(pause, Transport::Http { admin: true }).await.unwrap();
+        assert_eq!(reply["result"], true);
+        assert!(server.consensus.is_validation_paused());
+
+        let reply = server
+            .handle_payload(br#"{"jsonrpc":"2.0","method":"admin_resumeValidation","id":2}"#, Transport::Ipc)
+            .await
+            .unwrap();
+        assert_eq!(reply["result"], true);
+        assert!(!server.consensus.is_validation_paused());
+
+        let reply = server
+            .handle_payload(
+                br#"{"jsonrpc":"2.0","method":"admin_banPeer","params":["not-a-peer"],"id":3}"#,
+                Transport::Ipc,
+            )
+            .await
+            .unwrap();
+        assert_eq!(reply["error"]["code"], INVALID_PARAMS);
+
+        let mut shutdown = server.shutdown.subscribe();
+        let reply = server
+            .handle_payload(br#"{"jsonrpc":"2.0","method":"admin_shutdown","id":4}"#, Transport::Ipc)
+            .await
+            .unwrap();
+        assert_eq!(reply["result"], true);
+        assert!(shutdown.try_recv().is_ok());
+    }
+}
@@ -0,0 +1,247 @@
+//! Authentication for the admin RPC namespace
+//!
+//! Callers on HTTP and WebSocket unlock `admin_*` methods by sending
+//! `Authorization: Bearer <token>`, where the token is either an HS256 JWT
+//! signed with the node's secret (as with the Ethereum engine API, the `iat`
+//! claim must be within a minute of the node's clock) or the static
+//! `rpc.admin_token`. Requests without the header are served read-only;
+//! requests with an invalid token are rejected outright.
+
+use anyhow::{Context, Result};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+use tracing::info;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Length of a generated secret in bytes
+const SECRET_LENGTH: usize = 32;
+
+/// Accepted distance between a token's `iat` and the local clock
+const MAX_CLOCK_DRIFT_SECS: i64 = 60;
+
+/// Permissions of the secret file
+const SECRET_FILE_MODE: u32 = 0o600;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct JwtHeader {
+    alg: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    typ: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct JwtClaims {
+    iat: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    exp: Option<i64>,
+}
+
+/// Create a random hex-encoded secret at `path` unless one already exists
+pub fn ensure_secret(path: &Path) -> Result<()> {
+    if path.exists() {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let secret: [u8; SECRET_LENGTH] = rand::random();
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(SECRET_FILE_MODE)
+        .open(path)
+        .with_context(|| format!("Failed to create JWT secret {}", path.display()))?;
+    file.write_all(hex::encode(secret).as_bytes())?;
+
+    info!("🔐 Generated admin RPC JWT secret at {}", path.display());
+    Ok(())
+}
+
+/// Read a hex-encoded secret, with or without a `0x` prefix
+pub fn load_secret(path: &Path) -> Result<Vec<u8>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read JWT secret {}", path.display()))?;
+    let contents = contents.trim();
+    let secret = hex::decode(contents.strip_prefix("0x").unwrap_or(contents))
+        .with_context(|| format!("JWT secret {} is not valid hex", path.display()))?;
+    if secret.len() < SECRET_LENGTH {
+        return Err(anyhow::anyhow!(
+            "JWT secret {} must be at least {} bytes",
+            path.display(),
+            SECRET_LENGTH
+        ));
+    }
+    Ok(secret)
+}
+
+/// Issue a JWT for `secret` valid around the current time, as an engine
+/// API client would
+#[cfg(test)]
+pub fn issue_token(secret: &[u8]) -> String {
+    let header = JwtHeader {
+        alg: "HS256".to_string(),
+        typ: Some("JWT".to_string()),
+    };
+    let claims = JwtClaims {
+        iat: chrono::Utc::now().timestamp(),
+        exp: None,
+    };
+
+    let signing_input = format!(
+        "{}.{}",
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(&header).unwrap_or_default()),
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(&claims).unwrap_or_default()),
+    );
+    let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(signing_input.as_bytes());
+    format!("{}.{}", signing_input, URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes()))
+}
+
+/// Outcome of checking a request's credentials
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    /// No credentials: read-only methods
+    ReadOnly,
+    /// Valid credentials: admin methods allowed
+    Admin,
+    /// Credentials present but invalid
+    Denied,
+}
+
+/// Verifies admin credentials
+pub struct Authenticator {
+    secret: Option<Vec<u8>>,
+    admin_token: Option<String>,
+}
+
+impl Authenticator {
+    pub fn new(secret: Option<Vec<u8>>, admin_token: Option<String>) -> Self {
+        Self { secret, admin_token }
+    }
+
+    /// Classify a request by its `Authorization` header value
+    pub fn check(&self, authorization: Option<&str>) -> Access {
+        let Some(authorization) = authorization else {
+            return Access::ReadOnly;
+        };
+        let Some(token) = authorization
+            .strip_prefix("Bearer ")
+            .or_else(|| authorization.strip_prefix("bearer "))
+        else {
+            return Access::Denied;
+        };
+        let token = token.trim();
+
+        let static_match = self
+            .admin_token
+            .as_deref()
+            .is_some_and(|expected| constant_time_eq(expected.as_bytes(), token.as_bytes()));
+        let jwt_match = self
+            .secret
+            .as_deref()
+            .is_some_and(|secret| verify_jwt(secret, token, chrono::Utc::now().timestamp()));
+
+        if static_match || jwt_match {
+            Access::Admin
+        } else {
+            Access::Denied
+        }
+    }
+}
+
+/// Check an HS256 JWT's signature and freshness
+fn verify_jwt(secret: &[u8], token: &str, now: i64) -> bool {
+    let Some((signing_input, signature)) = token.rsplit_once('.') else {
+        return false;
+    };
+    let Some((header, claims)) = signing_input.split_once('.') else {
+        return false;
+    };
+
+    let decode = |part: &str| URL_SAFE_NO_PAD.decode(part).ok();
+    let header = decode(header).and_then(|h| serde_json::from_slice::<JwtHeader>(&h).ok());
+    if header.is_none_or(|header| header.alg != "HS256") {
+        return false;
+    }
+
+    let (Some(signature), Ok(mut mac)) = (decode(signature), HmacSha256::new_from_slice(secret)) else {
+        return false;
+    };
+    mac.update(signing_input.as_bytes());
+    if mac.verify_slice(&signature).is_err() {
+        return false;
+    }
+
+    match decode(claims).and_then(|c| serde_json::from_slice::<JwtClaims>(&c).ok()) {
+        Some(claims) => {
+            (now - claims.iat).abs() <= MAX_CLOCK_DRIFT_SECS && claims.exp.is_none_or(|exp| now < exp)
+        }
+        None => false,
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jwt_verification() {
+        let secret = [7u8; SECRET_LENGTH];
+        let token = issue_token(&secret);
+        let now = chrono::Utc::now().timestamp();
+
+        assert!(verify_jwt(&secret, &token, now));
+        assert!(!verify_jwt(&[8u8; SECRET_LENGTH], &token, now));
+        assert!(!verify_jwt(&secret, &token, now + MAX_CLOCK_DRIFT_SECS + 5));
+
+        // Unsigned tokens are never accepted
+        let (signing_input, _) = token.rsplit_once('.').unwrap();
+        let (_, claims) = signing_input.split_once('.').unwrap();
+        let none_header = URL_SAFE_NO_PAD.encode(br#"{"alg":"none"}"#);
+        assert!(!verify_jwt(&secret, &format!("{}.{}.", none_header, claims), now));
+    }
+
+    #[test]
+    fn test_access_levels() {
+        let secret = vec![1u8; SECRET_LENGTH];
+        let auth = Authenticator::new(Some(secret.clone()), Some("letmein".to_string()));
+
+        assert_eq!(auth.check(None), Access::ReadOnly);
+        assert_eq!(auth.check(Some("Bearer letmein")), Access::Admin);
+        assert_eq!(auth.check(Some(&format!("Bearer {}", issue_token(&secret)))), Access::Admin);
+        assert_eq!(auth.check(Some("Bearer wrong")), Access::Denied);
+        assert_eq!(auth.check(Some("Basic letmein")), Access::Denied);
+
+        let no_credentials = Authenticator::new(None, None);
+        assert_eq!(no_credentials.check(Some("Bearer letmein")), Access::Denied);
+    }
+
+    #[test]
+    fn test_secret_file() {
+        let path = std::env::temp_dir().join(format!("omne-jwt-{}.hex", rand::random::<u32>()));
+        ensure_secret(&path).unwrap();
+        let secret = load_secret(&path).unwrap();
+        assert_eq!(secret.len(), SECRET_LENGTH);
+
+        // An existing secret is kept
+        ensure_secret(&path).unwrap();
+        assert_eq!(load_secret(&path).unwrap(), secret);
+
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, SECRET_FILE_MODE);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
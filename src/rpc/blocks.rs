@@ -0,0 +1,245 @@
+//! Block, attestation and validator queries served from the chain store
+//!
+//! Unknown blocks and checkpoints are reported with `NOT_FOUND`; malformed
+//! hashes and keys with `INVALID_PARAMS`. Proposer schedules and the
+//! validator registry are not synced from the security layer yet, so a
+//! missing one is reported with `NOT_AVAILABLE` rather than as unknown.
+
+use super::methods::{MethodTable, NoParams, RpcResult};
+use super::{JsonRpcError, RpcServer, INVALID_PARAMS, NOT_AVAILABLE, NOT_FOUND};
+use crate::chain::{
+    parse_hex, Attestation, Block, BlockHeader, Checkpoint, ProposerSchedule, ValidatorRecord,
+    HASH_LEN, PUBKEY_LEN,
+};
+use crate::events::Layer;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::sync::Arc;
+
+/// Params of `latest_block`
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct LayerParams {
+    #[serde(default)]
+    pub layer: Layer,
+}
+
+/// Params of `block_by_height`
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct BlockHeightParams {
+    pub height: u64,
+    #[serde(default)]
+    pub layer: Layer,
+}
+
+/// Params of `block_by_hash` and `attestations_for_block`
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct BlockHashParams {
+    pub hash: String,
+    /// When given, the block must be on this layer
+    #[serde(default)]
+    pub layer: Option<Layer>,
+}
+
+/// Block reference: height on a layer, or hash
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum BlockId {
+    Height(u64),
+    Hash(String),
+}
+
+/// Params of `block_header`
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct BlockIdParams {
+    pub block: BlockId,
+    /// Layer of the block, also checked when it is given by hash
+    #[serde(default)]
+    pub layer: Layer,
+}
+
+/// Params of `proposer_schedule`
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct EpochParams {
+    pub epoch: u64,
+}
+
+/// Params of `validator_by_pubkey`
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct PubkeyParams {
+    pub pubkey: String,
+}
+
+/// Register the block query methods
+pub(super) fn register(table: &mut MethodTable) {
+    table.register("latest_block", RpcServer::rpc_latest_block).errors(&[NOT_FOUND]);
+    table.register("block_by_height", RpcServer::rpc_block_by_height).errors(&[NOT_FOUND]);
+    table.register("block_by_hash", RpcServer::rpc_block_by_hash).errors(&[NOT_FOUND]);
+    table.register("block_header", RpcServer::rpc_block_header).errors(&[NOT_FOUND]);
+    table.register("attestations_for_block", RpcServer::rpc_attestations_for_block).errors(&[NOT_FOUND]);
+    table.register("finalized_checkpoint", RpcServer::rpc_finalized_checkpoint).errors(&[NOT_FOUND]);
+    table.register("proposer_schedule", RpcServer::rpc_proposer_schedule).errors(&[NOT_AVAILABLE]);
+    table.register("validator_by_pubkey", RpcServer::rpc_validator_by_pubkey).errors(&[NOT_AVAILABLE]);
+}
+
+fn not_found<T>(value: Option<T>, what: impl FnOnce() -> String) -> RpcResult<T> {
+    value.ok_or_else(|| JsonRpcError::new(NOT_FOUND, format!("{} not found", what())))
+}
+
+fn not_available<T>(value: Option<T>, what: &str) -> RpcResult<T> {
+    value.ok_or_else(|| {
+        JsonRpcError::new(NOT_AVAILABLE, format!("{} are not synced from the security layer yet", what))
+    })
+}
+
+fn check_hex(value: &str, len: usize, what: &str) -> RpcResult<()> {
+    parse_hex(value, len)
+        .map(|_| ())
+        .map_err(|e| JsonRpcError::new(INVALID_PARAMS, format!("Invalid {}: {}", what, e)))
+}
+
+fn layer_name(layer: Layer) -> &'static str {
+    match layer {
+        Layer::Commerce => "Commerce",
+        Layer::Security => "Security",
+    }
+}
+
+impl RpcServer {
+    /// RPC method: latest_block
+    async fn rpc_latest_block(self: Arc<Self>, params: LayerParams) -> RpcResult<Block> {
+        let block = self.chain.latest_block(params.layer)?;
+        not_found(block, || format!("{} head block", layer_name(params.layer)))
+    }
+
+    /// RPC method: block_by_height
+    async fn rpc_block_by_height(self: Arc<Self>, params: BlockHeightParams) -> RpcResult<Block> {
+        let block = self.chain.block_by_height(params.layer, params.height)?;
+        not_found(block, || format!("{} block {}", layer_name(params.layer), params.height))
+    }
+
+    /// RPC method: block_by_hash
+    async fn rpc_block_by_hash(self: Arc<Self>, params: BlockHashParams) -> RpcResult<Block> {
+        check_hex(&params.hash, HASH_LEN, "block hash")?;
+        let block = self
+            .chain
+            .block_by_hash(&params.hash)?
+            .filter(|block| params.layer.is_none_or(|layer| block.header.layer == layer));
+        not_found(block, || format!("Block {}", params.hash))
+    }
+
+    /// RPC method: block_header
+    async fn rpc_block_header(self: Arc<Self>, params: BlockIdParams) -> RpcResult<BlockHeader> {
+        let block = match params.block {
+            BlockId::Height(height) => {
+                self.rpc_block_by_height(BlockHeightParams { height, layer: params.layer }).await?
+            }
+            BlockId::Hash(hash) => {
+                self.rpc_block_by_hash(BlockHashParams { hash, layer: Some(params.layer) }).await?
+            }
+        };
+        Ok(block.header)
+    }
+
+    /// RPC method: attestations_for_block
+    async fn rpc_attestations_for_block(self: Arc<Self>, params: BlockHashParams) -> RpcResult<Vec<Attestation>> {
+        let block = self.clone().rpc_block_by_hash(params).await?;
+        Ok(self.chain.attestations_for_block(&block.header.hash)?)
+    }
+
+    /// RPC method: finalized_checkpoint
+    async fn rpc_finalized_checkpoint(self: Arc<Self>, _params: NoParams) -> RpcResult<Checkpoint> {
+        not_found(self.chain.finalized_checkpoint()?, || "Finalized checkpoint".to_string())
+    }
+
+    /// RPC method: proposer_schedule
+    async fn rpc_proposer_schedule(self: Arc<Self>, params: EpochParams) -> RpcResult<ProposerSchedule> {
+        not_available(self.chain.proposer_schedule(params.epoch)?, "Proposer schedules")
+    }
+
+    /// RPC method: validator_by_pubkey
+    async fn rpc_validator_by_pubkey(self: Arc<Self>, params: PubkeyParams) -> RpcResult<ValidatorRecord> {
+        check_hex(&params.pubkey, PUBKEY_LEN, "public key")?;
+        not_available(self.chain.validator_by_pubkey(&params.pubkey)?, "Validator registry entries")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chain::tests::{block, hash, pubkey};
+    use crate::chain::{Attestation, Checkpoint, ProposerDuty, ProposerSchedule, ValidatorRecord};
+    use crate::events::Layer;
+    use crate::rpc::tests::test_server;
+    use crate::rpc::{Transport, INVALID_PARAMS, NOT_AVAILABLE, NOT_FOUND};
+    use serde_json::{json, Value};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_block_queries() {
+        let server = Arc::new(test_server().await);
+        let call = |method: &str, params: Value| {
+            let server = server.clone();
+            let payload = json!({ "jsonrpc": "2.0", "method": method, "params": params, "id": 1 });
+            async move {
+                server
+                    .handle_payload(payload.to_string().as_bytes(), Transport::Http { admin: false })
+                    .await
+                    .unwrap()
+            }
+        };
+
+        // Nothing is fabricated for an empty store
+        for (method, params) in [
+            ("latest_block", json!([])),
+            ("block_by_height", json!([1])),
+            ("block_by_hash", json!([hash(1)])),
+            ("finalized_checkpoint", json!([])),
+        ] {
+            assert_eq!(call(method, params).await["error"]["code"], NOT_FOUND, "{}", method);
+        }
+        for (method, params) in [("proposer_schedule", json!([0])), ("validator_by_pubkey", json!([pubkey(1)]))] {
+            assert_eq!(call(method, params).await["error"]["code"], NOT_AVAILABLE, "{}", method);
+        }
+
+        server.chain.insert_block(&block(Layer::Commerce, 7, 1)).unwrap();
+        server.chain.insert_block(&block(Layer::Security, 7, 2)).unwrap();
+        server
+            .chain
+            .insert_attestation(&Attestation {
+                block_hash: hash(2),
+                validator: pubkey(1),
+                epoch: 0,
+                signature: "0x00".to_string(),
+            })
+            .unwrap();
+        server
+            .chain
+            .set_finalized_checkpoint(&Checkpoint { epoch: 0, height: 7, block_hash: hash(2) })
+            .unwrap();
+
+        assert_eq!(call("latest_block", json!(["security"])).await["result"]["header"]["hash"], hash(2));
+        assert_eq!(call("block_by_height", json!({ "height": 7 })).await["result"]["header"]["hash"], hash(1));
+        assert_eq!(call("block_by_hash", json!([hash(2)])).await["result"]["header"]["layer"], "security");
+        assert_eq!(call("block_by_hash", json!([hash(2), "commerce"])).await["error"]["code"], NOT_FOUND);
+        assert_eq!(call("block_header", json!([7, "security"])).await["result"]["hash"], hash(2));
+        assert_eq!(call("block_header", json!([hash(1)])).await["result"]["height"], 7);
+        assert_eq!(call("block_header", json!([hash(2)])).await["error"]["code"], NOT_FOUND);
+        assert_eq!(call("block_header", json!([hash(2), "security"])).await["result"]["height"], 7);
+        assert_eq!(call("attestations_for_block", json!([hash(2)])).await["result"][0]["validator"], pubkey(1));
+        assert_eq!(call("attestations_for_block", json!([hash(1)])).await["result"], json!([]));
+        assert_eq!(call("finalized_checkpoint", json!([])).await["result"]["height"], 7);
+
+        // Schedules and registry entries are served once something stores them
+        let duty = ProposerDuty { slot: 7, layer: Layer::Commerce, proposer: pubkey(1) };
+        server.chain.insert_proposer_schedule(&ProposerSchedule { epoch: 0, duties: vec![duty] }).unwrap();
+        server
+            .chain
+            .insert_validator(&ValidatorRecord { pubkey: pubkey(1), stake: 100, is_active: true, activation_epoch: 0 })
+            .unwrap();
+        assert_eq!(call("proposer_schedule", json!([0])).await["result"]["duties"][0]["proposer"], pubkey(1));
+        assert_eq!(call("validator_by_pubkey", json!([pubkey(1)])).await["result"]["stake"], 100);
+
+        assert_eq!(call("block_by_hash", json!(["0xabc"])).await["error"]["code"], INVALID_PARAMS);
+        assert_eq!(call("validator_by_pubkey", json!([hash(1)])).await["error"]["code"], INVALID_PARAMS);
+    }
+}
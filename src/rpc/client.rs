@@ -0,0 +1,185 @@
+//! JSON-RPC client for talking to a running node
+//!
+//! Used by CLI subcommands. Connects either to the HTTP endpoint or to the
+//! IPC socket; each call opens a fresh connection, which is plenty for
+//! command-line use.
+
+use anyhow::{Context, Result};
+use http_body_util::{BodyExt, Full};
+use hyper::body::Bytes;
+use hyper::header::{self, HeaderValue};
+use hyper::{Request, Uri};
+use hyper_util::rt::TokioIo;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpStream, UnixStream};
+
+/// Upper bound on a single call including connecting
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Where the node is reached
+#[derive(Debug, Clone)]
+pub enum Endpoint {
+    Http(Uri),
+    Ipc(PathBuf),
+}
+
+impl std::fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Endpoint::Http(uri) => write!(f, "{}", uri),
+            Endpoint::Ipc(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// JSON-RPC client for a single node
+pub struct RpcClient {
+    endpoint: Endpoint,
+    next_id: AtomicU64,
+}
+
+impl RpcClient {
+    /// Client for an `http://host:port` endpoint
+    pub fn http(url: &str) -> Result<Self> {
+        let uri: Uri = url.parse().with_context(|| format!("Invalid RPC endpoint {}", url))?;
+        if uri.scheme_str() != Some("http") || uri.host().is_none() {
+            return Err(anyhow::anyhow!("RPC endpoint must be an http:// URL, got {}", url));
+        }
+        Ok(Self::new(Endpoint::Http(uri)))
+    }
+
+    /// Client for the IPC socket at `path`
+    pub fn ipc(path: impl Into<PathBuf>) -> Self {
+        Self::new(Endpoint::Ipc(path.into()))
+    }
+
+    fn new(endpoint: Endpoint) -> Self {
+        Self {
+            endpoint,
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    pub fn endpoint(&self) -> &Endpoint {
+        &self.endpoint
+    }
+
+    /// Call `method` and deserialize its result
+    pub async fn call<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let request = json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params,
+            "id": id,
+        });
+
+        let reply = tokio::time::timeout(REQUEST_TIMEOUT, self.send(request.to_string().into_bytes()))
+            .await
+            .map_err(|_| anyhow::anyhow!("Timed out waiting for {}", self.endpoint))??;
+        let mut reply: Value = serde_json::from_slice(&reply)
+            .with_context(|| format!("Invalid JSON-RPC response from {}", self.endpoint))?;
+
+        if let Some(error) = reply.get("error") {
+            return Err(anyhow::anyhow!(
+                "{} failed: {} ({})",
+                method,
+                error["message"].as_str().unwrap_or("unknown error"),
+                error["code"]
+            ));
+        }
+        serde_json::from_value(reply["result"].take())
+            .with_context(|| format!("Unexpected result for {}", method))
+    }
+
+    async fn send(&self, payload: Vec<u8>) -> Result<Vec<u8>> {
+        match &self.endpoint {
+            Endpoint::Http(uri) => send_http(uri, payload).await,
+            Endpoint::Ipc(path) => send_ipc(path, payload).await,
+        }
+    }
+}
+
+async fn send_http(uri: &Uri, payload: Vec<u8>) -> Result<Vec<u8>> {
+    let host = uri.host().unwrap_or_default();
+    let port = uri.port_u16().unwrap_or(80);
+    let stream = TcpStream::connect((host, port))
+        .await
+        .with_context(|| format!("Failed to connect to {}", uri))?;
+
+    let (mut sender, connection) = hyper::client::conn::http1::handshake(TokioIo::new(stream)).await?;
+    tokio::spawn(connection);
+
+    let authority = uri.authority().map(|a| a.as_str()).unwrap_or(host);
+    let path = uri.path_and_query().map(|p| p.as_str()).unwrap_or("/");
+    let request = Request::post(path)
+        .header(header::HOST, HeaderValue::from_str(authority)?)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Full::new(Bytes::from(payload)))?;
+
+    let response = sender.send_request(request).await?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(anyhow::anyhow!("{} responded with HTTP {}", uri, status));
+    }
+    Ok(response.into_body().collect().await?.to_bytes().to_vec())
+}
+
+async fn send_ipc(path: &Path, mut payload: Vec<u8>) -> Result<Vec<u8>> {
+    let stream = UnixStream::connect(path)
+        .await
+        .with_context(|| format!("Failed to connect to IPC socket {}", path.display()))?;
+    let (reader, mut writer) = stream.into_split();
+
+    payload.push(b'\n');
+    writer.write_all(&payload).await?;
+
+    let mut line = Vec::new();
+    BufReader::new(reader).read_until(b'\n', &mut line).await?;
+    Ok(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::tests::test_server;
+    use crate::status;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_status_over_ipc() {
+        let server = Arc::new(test_server().await);
+        let path = server.config.rpc.ipc_socket_path(&server.config.data_dir);
+        let (shutdown_tx, shutdown_rx) = tokio::sync::broadcast::channel(1);
+        let handle = tokio::spawn(crate::rpc::ipc::serve(server.clone(), shutdown_rx));
+        while !path.exists() {
+            tokio::task::yield_now().await;
+        }
+
+        let client = RpcClient::ipc(&path);
+        let report = status::fetch(&client).await.unwrap();
+        assert!(report.healthy);
+        assert_eq!(report.validator.stake, server.config.validator.validator_stake);
+
+        let table = status::render(&report);
+        for heading in ["Consensus", "Finality", "P2P", "Stake", "Infrastructure"] {
+            assert!(table.contains(&format!("\n{}\n", heading)), "missing {}", heading);
+        }
+
+        let error = client.call::<Value>("nope", json!([])).await.unwrap_err();
+        assert!(error.to_string().contains("Method not found"));
+
+        shutdown_tx.send(()).unwrap();
+        handle.await.unwrap().unwrap();
+        let _ = std::fs::remove_dir_all(&server.config.data_dir);
+
+        // Nothing is listening any more
+        assert!(status::fetch(&client).await.is_err());
+        assert!(RpcClient::http("ftp://localhost").is_err());
+    }
+}
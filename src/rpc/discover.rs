@@ -0,0 +1,175 @@
+//! OpenRPC schema discovery
+//!
+//! Builds an OpenRPC document from the method table: every method with its
+//! params (in positional order), result schema and server-defined errors,
+//! plus the shared type definitions. Served by `rpc_discover` and printed
+//! by `omne-nexus rpc-schema` so clients can be generated from it.
+
+use super::methods::{MethodTable, NoParams, RpcResult};
+use super::{
+    is_admin_method, RpcServer, FORBIDDEN, INTERNAL_ERROR, INVALID_PARAMS, INVALID_REQUEST,
+    METHOD_NOT_FOUND, NOT_AVAILABLE, NOT_FOUND, PARSE_ERROR, RATE_LIMITED,
+};
+
+use schemars::gen::SchemaSettings;
+use schemars::schema::Schema;
+use serde_json::{json, Map, Value};
+use std::sync::Arc;
+
+/// OpenRPC specification version the document follows
+const OPENRPC_VERSION: &str = "1.2.6";
+
+/// Every error code the server returns, with its name and meaning
+const ERRORS: &[(&str, i32, &str)] = &[
+    ("ParseError", PARSE_ERROR, "Invalid JSON was received"),
+    ("InvalidRequest", INVALID_REQUEST, "The payload is not a valid request object"),
+    ("MethodNotFound", METHOD_NOT_FOUND, "The method does not exist"),
+    ("InvalidParams", INVALID_PARAMS, "Invalid method parameters"),
+    ("InternalError", INTERNAL_ERROR, "Internal error"),
+    ("NotFound", NOT_FOUND, "The requested block or checkpoint is unknown"),
+    ("NotAvailable", NOT_AVAILABLE, "Proposer schedules and the validator registry are not synced yet"),
+    ("Forbidden", FORBIDDEN, "Admin methods require authentication"),
+    ("RateLimited", RATE_LIMITED, "Request rate limit exceeded"),
+];
+
+pub(super) fn register(table: &mut MethodTable) {
+    table.register("rpc_discover", RpcServer::rpc_discover);
+}
+
+/// OpenRPC document describing every method in `table`
+pub(super) fn document(table: &MethodTable) -> Value {
+    let mut generator = SchemaSettings::draft07()
+        .with(|settings| {
+            settings.definitions_path = "#/components/schemas/".to_string();
+            settings.meta_schema = None;
+        })
+        .into_generator();
+
+    let methods: Vec<Value> = table
+        .schemas()
+        .into_iter()
+        .map(|(name, schema)| {
+            let params = params((schema.params)(&mut generator));
+            let result = (schema.result)(&mut generator);
+
+            let mut codes = schema.errors.clone();
+            if is_admin_method(name) {
+                codes.push(FORBIDDEN);
+            }
+            let errors: Vec<Value> = codes.into_iter().map(error).collect();
+
+            json!({
+                "name": name,
+                "paramStructure": "either",
+                "params": params,
+                "result": { "name": "result", "schema": result },
+                "errors": errors,
+            })
+        })
+        .collect();
+
+    let errors: Map<String, Value> = ERRORS
+        .iter()
+        .map(|(name, code, _)| (name.to_string(), error(*code)))
+        .collect();
+
+    json!({
+        "openrpc": OPENRPC_VERSION,
+        "info": {
+            "title": "Omne Nexus JSON-RPC API",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "methods": methods,
+        "components": {
+            "schemas": generator.definitions(),
+            "errors": errors,
+        },
+    })
+}
+
+/// Content descriptors for the properties of a params struct, in order
+fn params(schema: Schema) -> Vec<Value> {
+    let Schema::Object(schema) = schema else {
+        return Vec::new();
+    };
+    let Some(object) = schema.object else {
+        return Vec::new();
+    };
+
+    object
+        .properties
+        .into_iter()
+        .map(|(name, schema)| {
+            let required = object.required.contains(&name);
+            json!({ "name": name, "required": required, "schema": schema })
+        })
+        .collect()
+}
+
+fn error(code: i32) -> Value {
+    let message = ERRORS
+        .iter()
+        .find(|(_, c, _)| *c == code)
+        .map_or("Server error", |(_, _, message)| *message);
+    json!({ "code": code, "message": message })
+}
+
+impl RpcServer {
+    /// RPC method: rpc_discover
+    async fn rpc_discover(self: Arc<Self>, _params: NoParams) -> RpcResult<Value> {
+        Ok(document(&self.methods))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::tests::test_server;
+    use crate::rpc::Transport;
+
+    /// Every `$ref` in `value` must point into `components.schemas`
+    fn check_refs(value: &Value, schemas: &Map<String, Value>) {
+        match value {
+            Value::Object(map) => {
+                if let Some(Value::String(target)) = map.get("$ref") {
+                    let name = target.strip_prefix("#/components/schemas/").unwrap();
+                    assert!(schemas.contains_key(name), "dangling $ref {}", target);
+                }
+                map.values().for_each(|v| check_refs(v, schemas));
+            }
+            Value::Array(items) => items.iter().for_each(|v| check_refs(v, schemas)),
+            _ => {}
+        }
+    }
+
+    #[tokio::test]
+    async fn test_openrpc_document() {
+        let server = Arc::new(test_server().await);
+        let reply = server
+            .handle_payload(br#"{"jsonrpc":"2.0","method":"rpc_discover","id":1}"#, Transport::Http { admin: false })
+            .await
+            .unwrap();
+        let document = &reply["result"];
+        assert_eq!(document["openrpc"], OPENRPC_VERSION);
+        assert_eq!(document, &super::super::openrpc_document());
+
+        let methods = document["methods"].as_array().unwrap();
+        let method = |name: &str| methods.iter().find(|m| m["name"] == name).unwrap().clone();
+
+        // Params keep their positional order and optionality
+        let block = method("block_by_height");
+        assert_eq!(block["params"][0]["name"], "height");
+        assert_eq!(block["params"][0]["required"], true);
+        assert_eq!(block["params"][1]["name"], "layer");
+        assert_eq!(block["params"][1]["required"], false);
+        assert_eq!(block["errors"][0]["code"], NOT_FOUND);
+
+        assert_eq!(method("version")["params"], json!([]));
+        assert_eq!(method("admin_shutdown")["errors"][0]["code"], FORBIDDEN);
+        assert_eq!(method("subscribe")["params"][0]["name"], "topic");
+
+        let schemas = document["components"]["schemas"].as_object().unwrap();
+        assert!(schemas.contains_key("ConsensusStatus"));
+        check_refs(document, schemas);
+    }
+}
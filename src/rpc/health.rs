@@ -0,0 +1,178 @@
+//! Liveness, readiness and sync checks
+//!
+//! Served as plain `GET /health/live`, `/health/ready` and `/health/sync` on
+//! the RPC port for orchestrators, answering 503 when a probe fails, and as
+//! the `health` JSON-RPC method. Probes skip the host, origin and rate-limit
+//! checks since they come from infrastructure rather than browsers.
+
+use super::http::{empty_response, json_response};
+use super::methods::{MethodTable, NoParams, RpcResult};
+use super::RpcServer;
+use crate::consensus::SyncStatus;
+
+use http_body_util::Full;
+use hyper::body::Bytes;
+use hyper::{Method, Response, StatusCode};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Path prefix of the probe endpoints
+pub(super) const HEALTH_PATH_PREFIX: &str = "/health/";
+
+/// Outcome of one readiness check
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HealthCheck {
+    pub name: String,
+    pub ok: bool,
+    pub detail: String,
+}
+
+/// Result of `health` and body of `/health/ready`
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct HealthResult {
+    /// `healthy` when every check passes, `unhealthy` otherwise
+    pub status: String,
+    pub ready: bool,
+    pub timestamp: String,
+    pub checks: Vec<HealthCheck>,
+    pub sync: SyncStatus,
+}
+
+impl HealthResult {
+    /// Checks that did not pass
+    pub fn failing(&self) -> impl Iterator<Item = &HealthCheck> {
+        self.checks.iter().filter(|check| !check.ok)
+    }
+}
+
+pub(super) fn register(table: &mut MethodTable) {
+    table.register("health", RpcServer::rpc_health);
+}
+
+fn check(name: &str, ok: bool, detail: String) -> HealthCheck {
+    HealthCheck {
+        name: name.to_string(),
+        ok,
+        detail,
+    }
+}
+
+impl RpcServer {
+    /// Run the readiness checks
+    async fn health(&self) -> HealthResult {
+        let thresholds = &self.config.health;
+        let sync = self.consensus.sync_status(thresholds.sync_tolerance_blocks);
+        let mut checks = vec![check(
+            "sync",
+            !sync.is_syncing,
+            format!(
+                "at height {}, {} blocks behind best known head {}",
+                sync.current_height, sync.blocks_behind, sync.highest_height
+            ),
+        )];
+
+        let peers = self.p2p_network.connected_peers().await.len();
+        checks.push(check(
+            "peers",
+            peers >= thresholds.min_peers,
+            format!("{} connected, {} required", peers, thresholds.min_peers),
+        ));
+
+        if self.config.validator.is_validator {
+            let missed = self.consensus.consecutive_missed_slots();
+            checks.push(check(
+                "missed_slots",
+                missed < thresholds.max_consecutive_missed_slots,
+                format!(
+                    "{} consecutive slots missed, limit {}",
+                    missed, thresholds.max_consecutive_missed_slots
+                ),
+            ));
+        }
+
+        let ready = checks.iter().all(|check| check.ok);
+        HealthResult {
+            status: if ready { "healthy" } else { "unhealthy" }.to_string(),
+            ready,
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            checks,
+            sync,
+        }
+    }
+
+    /// RPC method: health
+    async fn rpc_health(self: Arc<Self>, _params: NoParams) -> RpcResult<HealthResult> {
+        Ok(self.health().await)
+    }
+}
+
+/// Answer a probe under `/health/`
+pub(super) async fn handle_probe(server: &RpcServer, method: &Method, path: &str) -> Response<Full<Bytes>> {
+    if method != Method::GET && method != Method::HEAD {
+        return empty_response(StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    let (ok, body) = match path.strip_prefix(HEALTH_PATH_PREFIX) {
+        Some("live") => (true, serde_json::json!({ "status": "live" })),
+        Some("ready") => {
+            let health = server.health().await;
+            (health.ready, serde_json::to_value(&health).unwrap_or_default())
+        }
+        Some("sync") => {
+            let sync = server.consensus.sync_status(server.config.health.sync_tolerance_blocks);
+            (!sync.is_syncing, serde_json::to_value(&sync).unwrap_or_default())
+        }
+        _ => return empty_response(StatusCode::NOT_FOUND),
+    };
+
+    let status = if ok { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    json_response(status, serde_json::to_vec(&body).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::Layer;
+    use crate::rpc::tests::test_server;
+    use http_body_util::BodyExt;
+
+    async fn probe(server: &RpcServer, path: &str) -> (StatusCode, serde_json::Value) {
+        let response = handle_probe(server, &Method::GET, path).await;
+        let status = response.status();
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_probes() {
+        let mut server = test_server().await;
+        server.config.validator.is_validator = true;
+
+        assert_eq!(probe(&server, "/health/live").await.0, StatusCode::OK);
+        let (status, body) = probe(&server, "/health/ready").await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["status"], "healthy");
+
+        // Missing slots and falling behind make the node unready
+        for slot in 0..server.config.health.max_consecutive_missed_slots {
+            server.consensus.record_slot_duty(Layer::Commerce, slot, true);
+        }
+        server.consensus.observe_peer_head("peer", 100);
+
+        let (status, body) = probe(&server, "/health/ready").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        let health: HealthResult = serde_json::from_value(body).unwrap();
+        let failing: Vec<_> = health.failing().map(|check| check.name.as_str()).collect();
+        assert_eq!(failing, ["sync", "missed_slots"]);
+
+        let (status, body) = probe(&server, "/health/sync").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["blocks_behind"], 100);
+
+        // The JSON-RPC method reports the same checks
+        let health = Arc::new(server).rpc_health(NoParams {}).await.unwrap();
+        assert_eq!(health.status, "unhealthy");
+        assert_eq!(health.failing().count(), 2);
+    }
+}
@@ -0,0 +1,416 @@
+//! HTTP/1.1 transport for the JSON-RPC server
+//!
+//! JSON-RPC payloads are accepted as `POST /` bodies, and `GET /` requests
+//! carrying a WebSocket handshake are upgraded. Each connection holds a
+//! permit from the server's connection semaphore for its whole lifetime,
+//! including after a WebSocket upgrade.
+//!
+//! Before dispatching, requests must name an allowed `Host`, come from an
+//! allowed `Origin` if they carry one (answering CORS preflights), and fit
+//! the caller's per-IP rate limit. Health probes under `/health/` are
+//! answered first.
+
+use super::auth::Access;
+use super::health::{self, HEALTH_PATH_PREFIX};
+use super::{ws, RpcServer, Transport};
+
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::{Body, Bytes};
+use hyper::header::{self, HeaderValue};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use std::convert::Infallible;
+use std::net::IpAddr;
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tokio::sync::OwnedSemaphorePermit;
+use tracing::debug;
+
+/// Serve HTTP requests on an accepted connection
+pub(super) async fn serve_connection(
+    server: Arc<RpcServer>,
+    stream: TcpStream,
+    remote: IpAddr,
+    permit: OwnedSemaphorePermit,
+) {
+    let permit = Arc::new(permit);
+    let service = service_fn(move |request| {
+        let server = server.clone();
+        let permit = permit.clone();
+        async move { Ok::<_, Infallible>(handle_request(&server, request, remote, permit).await) }
+    });
+
+    if let Err(e) = http1::Builder::new()
+        .serve_connection(TokioIo::new(stream), service)
+        .with_upgrades()
+        .await
+    {
+        debug!("RPC connection error: {}", e);
+    }
+}
+
+/// Turn away a connection that exceeds `max_connections`
+pub(super) async fn reject_connection(mut stream: TcpStream) {
+    let _ = stream
+        .write_all(
+            b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        )
+        .await;
+    let _ = stream.shutdown().await;
+}
+
+/// Route a single HTTP request
+pub(super) async fn handle_request<B>(
+    server: &Arc<RpcServer>,
+    request: Request<B>,
+    remote: IpAddr,
+    permit: Arc<OwnedSemaphorePermit>,
+) -> Response<Full<Bytes>>
+where
+    B: Body,
+    B::Error: std::error::Error + Send + Sync + 'static,
+{
+    let config = &server.config.rpc;
+    let path = request.uri().path();
+    if path.starts_with(HEALTH_PATH_PREFIX) {
+        return health::handle_probe(server, request.method(), path).await;
+    }
+    if path != "/" {
+        return empty_response(StatusCode::NOT_FOUND);
+    }
+
+    // Without a Host header the request cannot be checked, so only `"*"` lets it through
+    let host = request.headers().get(header::HOST).and_then(|value| value.to_str().ok());
+    if !host.map_or(config.allowed_hosts.iter().any(|entry| entry == "*"), |host| {
+        host_allowed(&config.allowed_hosts, host)
+    }) {
+        debug!("Rejecting RPC request for host {:?}", host);
+        return empty_response(StatusCode::FORBIDDEN);
+    }
+
+    let origin = request.headers().get(header::ORIGIN).cloned();
+    if let Some(origin) = &origin {
+        if !origin_allowed(&config.cors_allowed_origins, origin) {
+            debug!("Rejecting RPC request from origin {:?}", origin);
+            return empty_response(StatusCode::FORBIDDEN);
+        }
+    }
+
+    let mut response = if request.method() == Method::OPTIONS && origin.is_some() {
+        preflight_response()
+    } else if let Err(retry_after) = server.rate_limiter.check(remote) {
+        server.stats.record_rate_limited();
+        rate_limited_response(retry_after)
+    } else {
+        route(server, request, remote, permit).await
+    };
+
+    if let Some(origin) = origin {
+        let headers = response.headers_mut();
+        headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+        headers.insert(header::VARY, HeaderValue::from_static("origin"));
+    }
+    response
+}
+
+/// Dispatch an accepted request to WebSocket or JSON-RPC handling
+async fn route<B>(
+    server: &Arc<RpcServer>,
+    request: Request<B>,
+    remote: IpAddr,
+    permit: Arc<OwnedSemaphorePermit>,
+) -> Response<Full<Bytes>>
+where
+    B: Body,
+    B::Error: std::error::Error + Send + Sync + 'static,
+{
+    let authorization = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .map(|value| value.to_str().unwrap_or_default());
+    let admin = match server.auth.check(authorization) {
+        Access::ReadOnly => false,
+        Access::Admin => true,
+        Access::Denied => return empty_response(StatusCode::UNAUTHORIZED),
+    };
+
+    if server.config.rpc.enable_ws && ws::is_upgrade_request(&request) {
+        return ws::upgrade(server.clone(), request, remote, permit, admin);
+    }
+
+    if request.method() != Method::POST || !server.config.rpc.enable_http {
+        let mut response = empty_response(StatusCode::METHOD_NOT_ALLOWED);
+        if server.config.rpc.enable_http {
+            response
+                .headers_mut()
+                .insert(header::ALLOW, HeaderValue::from_static("POST"));
+        }
+        return response;
+    }
+
+    let limit = server.config.rpc.max_request_body_size;
+    let declared_length = request
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<usize>().ok());
+    if declared_length.is_some_and(|length| length > limit) {
+        return empty_response(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    let body = match Limited::new(request.into_body(), limit).collect().await {
+        Ok(collected) => collected.to_bytes(),
+        Err(e) if e.is::<http_body_util::LengthLimitError>() => {
+            return empty_response(StatusCode::PAYLOAD_TOO_LARGE);
+        }
+        Err(e) => {
+            debug!("Failed to read RPC request body: {}", e);
+            return empty_response(StatusCode::BAD_REQUEST);
+        }
+    };
+
+    let payload = match server.parse_payload(&body) {
+        Ok(payload) => payload,
+        Err(reply) => return json_response(StatusCode::OK, serde_json::to_vec(&reply).unwrap_or_default()),
+    };
+    // The request took a token on arrival; every further call in a batch takes one more
+    if let Err(retry_after) = server.rate_limiter.take(remote, payload.calls() - 1) {
+        server.stats.record_rate_limited();
+        return rate_limited_response(retry_after);
+    }
+
+    match server.dispatch(payload, Transport::Http { admin }).await {
+        Some(reply) => json_response(StatusCode::OK, serde_json::to_vec(&reply).unwrap_or_default()),
+        None => empty_response(StatusCode::NO_CONTENT),
+    }
+}
+
+/// Whether `host` (a `Host` header, possibly with a port) is allowed
+fn host_allowed(allowed: &[String], host: &str) -> bool {
+    let name = match host.strip_prefix('[') {
+        Some(rest) => rest.split(']').next().unwrap_or_default(),
+        None => host.rsplit_once(':').map_or(host, |(name, _)| name),
+    };
+    allowed
+        .iter()
+        .any(|entry| entry == "*" || entry.eq_ignore_ascii_case(name))
+}
+
+fn origin_allowed(allowed: &[String], origin: &HeaderValue) -> bool {
+    let origin = origin.to_str().unwrap_or_default();
+    allowed
+        .iter()
+        .any(|entry| entry == "*" || entry.trim_end_matches('/').eq_ignore_ascii_case(origin))
+}
+
+/// Answer a CORS preflight from an allowed origin
+fn preflight_response() -> Response<Full<Bytes>> {
+    let mut response = empty_response(StatusCode::NO_CONTENT);
+    let headers = response.headers_mut();
+    headers.insert(header::ACCESS_CONTROL_ALLOW_METHODS, HeaderValue::from_static("POST, OPTIONS"));
+    headers.insert(
+        header::ACCESS_CONTROL_ALLOW_HEADERS,
+        HeaderValue::from_static("content-type, authorization"),
+    );
+    headers.insert(header::ACCESS_CONTROL_MAX_AGE, HeaderValue::from_static("600"));
+    response
+}
+
+fn rate_limited_response(retry_after: std::time::Duration) -> Response<Full<Bytes>> {
+    let mut response = empty_response(StatusCode::TOO_MANY_REQUESTS);
+    let seconds = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+    response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+    response
+}
+
+pub(super) fn json_response(status: StatusCode, body: Vec<u8>) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(Bytes::from(body)));
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    response
+}
+
+pub(super) fn empty_response(status: StatusCode) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(Bytes::new()));
+    *response.status_mut() = status;
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::tests::test_server;
+    use tokio::sync::Semaphore;
+
+    const LOCALHOST: IpAddr = IpAddr::V4(std::net::Ipv4Addr::LOCALHOST);
+
+    async fn test_server_with_permit() -> (Arc<RpcServer>, Arc<OwnedSemaphorePermit>) {
+        let permit = Arc::new(Semaphore::new(1)).try_acquire_owned().unwrap();
+        (Arc::new(test_server().await), Arc::new(permit))
+    }
+
+    fn post(body: &str) -> Request<Full<Bytes>> {
+        Request::builder()
+            .method(Method::POST)
+            .uri("/")
+            .header(header::HOST, "localhost:9944")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Full::new(Bytes::from(body.to_string())))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_http_routing() {
+        let (server, permit) = test_server_with_permit().await;
+
+        let response = handle_request(&server, post(r#"{"jsonrpc":"2.0","method":"version","id":1}"#), LOCALHOST, permit.clone()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let reply: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(reply["id"], 1);
+
+        let response = handle_request(&server, post(r#"{"jsonrpc":"2.0","method":"version"}"#), LOCALHOST, permit.clone()).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let get = Request::builder().uri("/").header(header::HOST, "localhost").body(Full::new(Bytes::new())).unwrap();
+        assert_eq!(handle_request(&server, get, LOCALHOST, permit.clone()).await.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+        let other = Request::builder()
+            .method(Method::POST)
+            .uri("/other")
+            .body(Full::new(Bytes::new()))
+            .unwrap();
+        assert_eq!(handle_request(&server, other, LOCALHOST, permit.clone()).await.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_admin_authentication() {
+        let (server, permit) = test_server_with_permit().await;
+        let secret = crate::rpc::auth::load_secret(
+            &server.config.rpc.jwt_secret_path(&server.config.data_dir),
+        )
+        .unwrap();
+        let admin_call = |authorization: Option<String>| {
+            let mut request = post(r#"{"jsonrpc":"2.0","method":"admin_pauseValidation","id":1}"#);
+            if let Some(authorization) = authorization {
+                request
+                    .headers_mut()
+                    .insert(header::AUTHORIZATION, HeaderValue::from_str(&authorization).unwrap());
+            }
+            request
+        };
+
+        let response = handle_request(&server, admin_call(Some("Bearer bogus".to_string())), LOCALHOST, permit.clone()).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = handle_request(&server, admin_call(None), LOCALHOST, permit.clone()).await;
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let reply: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(reply["error"]["code"], crate::rpc::FORBIDDEN);
+
+        let token = crate::rpc::auth::issue_token(&secret);
+        let response = handle_request(&server, admin_call(Some(format!("Bearer {}", token))), LOCALHOST, permit).await;
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let reply: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(reply["result"], true);
+    }
+
+    #[tokio::test]
+    async fn test_body_size_limit() {
+        let (server, permit) = test_server_with_permit().await;
+        let oversized = " ".repeat(server.config.rpc.max_request_body_size + 1);
+
+        let response = handle_request(&server, post(&oversized), LOCALHOST, permit).await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn test_host_cors_and_rate_limit() {
+        let server = test_server().await;
+        let mut config = server.config.clone();
+        config.rpc.cors_allowed_origins = vec!["https://app.example".to_string()];
+        config.rpc.allowed_hosts.push("rpc.example".to_string());
+        config.rpc.rate_limit_per_second = 1;
+        config.rpc.rate_limit_burst = 2;
+        let server = Arc::new(
+            RpcServer::new(
+                &config,
+                server.consensus.clone(),
+                server.p2p_network.clone(),
+                server.infrastructure.clone(),
+                server.chain.clone(),
+                server.events.clone(),
+                server.shutdown.clone(),
+            )
+            .await
+            .unwrap(),
+        );
+        let permit = Arc::new(Arc::new(Semaphore::new(1)).try_acquire_owned().unwrap());
+        let version = r#"{"jsonrpc":"2.0","method":"version","id":1}"#;
+        let with_header = |name: header::HeaderName, value: &str| {
+            let mut request = post(version);
+            request.headers_mut().insert(name, HeaderValue::from_str(value).unwrap());
+            request
+        };
+
+        // DNS rebinding: unknown hosts are refused before any work is done
+        let response = handle_request(&server, with_header(header::HOST, "evil.example:9944"), LOCALHOST, permit.clone()).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let mut without_host = post(version);
+        without_host.headers_mut().remove(header::HOST);
+        let response = handle_request(&server, without_host, LOCALHOST, permit.clone()).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN, "a missing Host is not let through");
+        let response = handle_request(&server, with_header(header::ORIGIN, "https://evil.example"), LOCALHOST, permit.clone()).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let mut preflight = with_header(header::ORIGIN, "https://app.example");
+        *preflight.method_mut() = Method::OPTIONS;
+        let response = handle_request(&server, preflight, LOCALHOST, permit.clone()).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN], "https://app.example");
+
+        // Two requests fit the burst, the third is limited
+        let response = handle_request(&server, with_header(header::HOST, "rpc.example"), LOCALHOST, permit.clone()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let response = handle_request(&server, with_header(header::ORIGIN, "https://app.example"), LOCALHOST, permit.clone()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::ACCESS_CONTROL_ALLOW_ORIGIN], "https://app.example");
+        let response = handle_request(&server, post(version), LOCALHOST, permit.clone()).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key(header::RETRY_AFTER));
+
+        let other: IpAddr = "192.0.2.1".parse().unwrap();
+        assert_eq!(handle_request(&server, post(version), other, permit.clone()).await.status(), StatusCode::OK);
+
+        // A batch takes a token per call
+        let batch = |calls: usize| format!("[{}]", vec![version; calls].join(","));
+        let third: IpAddr = "192.0.2.2".parse().unwrap();
+        let response = handle_request(&server, post(&batch(3)), third, permit.clone()).await;
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        let third: IpAddr = "192.0.2.3".parse().unwrap();
+        assert_eq!(handle_request(&server, post(&batch(2)), third, permit).await.status(), StatusCode::OK);
+
+        let status = server.status().await.unwrap();
+        assert_eq!(status.total_requests, 5);
+        assert_eq!(status.rate_limited_requests, 2);
+        assert_eq!(status.methods[0].method, "version");
+        assert_eq!(status.methods[0].calls, 5);
+    }
+
+    #[test]
+    fn test_host_matching() {
+        let allowed = vec!["localhost".to_string(), "::1".to_string()];
+        assert!(host_allowed(&allowed, "localhost:9944"));
+        assert!(host_allowed(&allowed, "LOCALHOST"));
+        assert!(host_allowed(&allowed, "[::1]:9944"));
+        assert!(!host_allowed(&allowed, "localhost.evil.example"));
+        assert!(host_allowed(&["*".to_string()], "anything:1"));
+    }
+}
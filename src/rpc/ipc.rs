@@ -0,0 +1,203 @@
+//! Unix socket IPC transport for the JSON-RPC server
+//!
+//! Serves newline-delimited JSON-RPC payloads on a socket under `data_dir`
+//! that only the node's user can open (mode 0600). The socket is bound in a
+//! private directory and moved into place once restricted, so it is never
+//! reachable with looser permissions, and connections from other users are
+//! refused by their peer credentials. Local tooling uses it without the node
+//! exposing a TCP port, and because access is limited this way, `admin_*`
+//! methods are always available here.
+
+use super::{RpcServer, Transport};
+
+use anyhow::{Context, Result};
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+use std::path::Path;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{broadcast, OwnedSemaphorePermit};
+use tracing::{debug, info, warn};
+
+/// Permissions of the socket file
+const SOCKET_MODE: u32 = 0o600;
+
+/// Permissions of directories created for the socket
+const DIR_MODE: u32 = 0o700;
+
+/// Listen on the IPC socket until shutdown, then remove it
+pub(super) async fn serve(server: Arc<RpcServer>, mut shutdown: broadcast::Receiver<()>) -> Result<()> {
+    let path = server.config.rpc.ipc_socket_path(&server.config.data_dir);
+    let listener = bind(&path).await?;
+    let owner = std::fs::metadata(&path)?.uid();
+    info!("✅ IPC RPC endpoint listening on {}", path.display());
+
+    loop {
+        tokio::select! {
+            result = listener.accept() => {
+                match result {
+                    Ok((stream, _)) if !is_trusted(&stream, owner) => {
+                        warn!("Rejecting IPC connection from another user");
+                    }
+                    Ok((stream, _)) => match server.connection_permits.clone().try_acquire_owned() {
+                        Ok(permit) => {
+                            tokio::spawn(serve_connection(server.clone(), stream, permit));
+                        }
+                        Err(_) => warn!("RPC connection limit reached, rejecting IPC connection"),
+                    },
+                    Err(e) => warn!("Failed to accept IPC connection: {}", e),
+                }
+            }
+
+            _ = shutdown.recv() => break,
+        }
+    }
+
+    if let Err(e) = std::fs::remove_file(&path) {
+        debug!("Failed to remove IPC socket {}: {}", path.display(), e);
+    }
+    Ok(())
+}
+
+/// Bind the socket, replacing a stale file left behind by a previous run
+async fn bind(path: &Path) -> Result<UnixListener> {
+    if path.exists() {
+        if UnixStream::connect(path).await.is_ok() {
+            return Err(anyhow::anyhow!(
+                "IPC socket {} is in use by another node",
+                path.display()
+            ));
+        }
+        std::fs::remove_file(path)
+            .with_context(|| format!("Failed to remove stale IPC socket {}", path.display()))?;
+    }
+
+    let parent = path.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    std::fs::DirBuilder::new()
+        .recursive(true)
+        .mode(DIR_MODE)
+        .create(parent)
+        .with_context(|| format!("Failed to create IPC socket directory {}", parent.display()))?;
+
+    // Bind inside a directory only we can enter, restrict the socket, then
+    // move it to its final path
+    let staging = parent.join(format!(".omne-nexus-ipc-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&staging);
+    std::fs::DirBuilder::new()
+        .mode(DIR_MODE)
+        .create(&staging)
+        .with_context(|| format!("Failed to create {}", staging.display()))?;
+    let staged = staging.join("socket");
+    let result = UnixListener::bind(&staged)
+        .with_context(|| format!("Failed to bind IPC socket {}", path.display()))
+        .and_then(|listener| {
+            std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(SOCKET_MODE))?;
+            std::fs::rename(&staged, path)
+                .with_context(|| format!("Failed to move IPC socket to {}", path.display()))?;
+            Ok(listener)
+        });
+    let _ = std::fs::remove_dir_all(&staging);
+    result
+}
+
+/// Whether the peer runs as the socket owner or root
+fn is_trusted(stream: &UnixStream, owner: u32) -> bool {
+    match stream.peer_cred() {
+        Ok(credentials) => credentials.uid() == owner || credentials.uid() == 0,
+        Err(e) => {
+            debug!("Failed to read IPC peer credentials: {}", e);
+            false
+        }
+    }
+}
+
+/// Answer one payload per line until the client disconnects
+async fn serve_connection(server: Arc<RpcServer>, stream: UnixStream, _permit: OwnedSemaphorePermit) {
+    let limit = server.config.rpc.max_request_body_size;
+    let (reader, mut writer) = stream.into_split();
+    let mut reader = BufReader::new(reader);
+    let mut line = Vec::new();
+
+    loop {
+        line.clear();
+        match (&mut reader).take(limit as u64 + 1).read_until(b'\n', &mut line).await {
+            Ok(0) => break,
+            Ok(_) if line.len() > limit => {
+                debug!("Closing IPC connection: payload too large");
+                break;
+            }
+            Ok(_) => {}
+            Err(e) => {
+                debug!("IPC connection error: {}", e);
+                break;
+            }
+        }
+
+        if line.iter().all(u8::is_ascii_whitespace) {
+            continue;
+        }
+
+        if let Some(reply) = server.handle_payload(&line, Transport::Ipc).await {
+            let mut reply = reply.to_string().into_bytes();
+            reply.push(b'\n');
+            if writer.write_all(&reply).await.is_err() {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::tests::test_server;
+    use crate::rpc::methods::VersionResult;
+    use crate::rpc::FORBIDDEN;
+
+    #[tokio::test]
+    async fn test_ipc_round_trip() {
+        let server = Arc::new(test_server().await);
+        let dir = server.config.data_dir.clone();
+        let path = server.config.rpc.ipc_socket_path(&dir);
+
+        let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
+        let handle = tokio::spawn(serve(server.clone(), shutdown_rx));
+        while !path.exists() {
+            tokio::task::yield_now().await;
+        }
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, SOCKET_MODE);
+        assert!(!std::fs::read_dir(&dir).unwrap().any(|entry| entry.unwrap().file_name().to_string_lossy().starts_with('.')));
+
+        // Directories created for the socket are private
+        let nested = dir.join("run/node.ipc");
+        drop(bind(&nested).await.unwrap());
+        let mode = std::fs::metadata(dir.join("run")).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, DIR_MODE);
+
+        let client = crate::rpc::client::RpcClient::ipc(&path);
+        let version: VersionResult = client.call("version", serde_json::json!([])).await.unwrap();
+        assert_eq!(version.name, env!("CARGO_PKG_NAME"));
+
+        // A second node must not steal a live socket
+        assert!(bind(&path).await.is_err());
+
+        shutdown_tx.send(()).unwrap();
+        handle.await.unwrap().unwrap();
+        assert!(!path.exists());
+        let _ = std::fs::remove_dir_all(&dir);
+
+        // Admin methods need no credentials over IPC
+        let reply = server
+            .handle_payload(br#"{"jsonrpc":"2.0","method":"admin_x","id":1}"#, Transport::Http { admin: false })
+            .await
+            .unwrap();
+        assert_eq!(reply["error"]["code"], FORBIDDEN);
+        let reply = server
+            .handle_payload(br#"{"jsonrpc":"2.0","method":"admin_x","id":1}"#, Transport::Ipc)
+            .await
+            .unwrap();
+        assert_eq!(reply["error"]["code"], crate::rpc::METHOD_NOT_FOUND);
+    }
+}
@@ -0,0 +1,311 @@
+//! JSON-RPC method table and handlers
+//!
+//! Methods are registered once when the server is created. Each handler is an
+//! async function taking typed params and returning a typed result; params
+//! may be passed positionally (array) or by name (object) and are rejected
+//! with `INVALID_PARAMS` when they do not deserialize. The same types
+//! describe each method in the OpenRPC document served by `rpc_discover`.
+
+use super::{JsonRpcError, RpcServer, INVALID_PARAMS};
+use crate::consensus::ConsensusStatus;
+use crate::infrastructure::InfrastructureStatus;
+use crate::p2p::{ConnectedPeer, P2PStatus};
+use crate::pruner::StorageStatus;
+
+use futures::future::BoxFuture;
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+
+/// Result of a method handler
+pub(super) type RpcResult<T> = Result<T, JsonRpcError>;
+
+/// Type-erased handler: raw params in, serialized result out
+type RpcHandler = Box<dyn Fn(Arc<RpcServer>, Value) -> BoxFuture<'static, RpcResult<Value>> + Send + Sync>;
+
+/// Builds the schema of a params or result type
+type SchemaFn = fn(&mut SchemaGenerator) -> Schema;
+
+/// Shape of a method as published by `rpc_discover`
+pub(super) struct MethodSchema {
+    /// Schema of the params struct; its properties are the params in order
+    pub params: SchemaFn,
+    pub result: SchemaFn,
+    /// Server-defined error codes the method may return
+    pub errors: Vec<i32>,
+}
+
+impl MethodSchema {
+    fn new<P: JsonSchema, R: JsonSchema>() -> Self {
+        Self {
+            params: P::json_schema,
+            result: |generator| generator.subschema_for::<R>(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Declare server-defined errors returned by the method
+    pub(super) fn errors(&mut self, codes: &[i32]) -> &mut Self {
+        self.errors.extend_from_slice(codes);
+        self
+    }
+}
+
+struct Method {
+    /// `None` for methods answered outside the table (WebSocket subscriptions)
+    handler: Option<RpcHandler>,
+    schema: MethodSchema,
+}
+
+/// Registered RPC methods by name
+pub(super) struct MethodTable {
+    methods: HashMap<&'static str, Method>,
+}
+
+impl MethodTable {
+    /// Build the table of all methods served by the node
+    pub(super) fn new() -> Self {
+        let mut table = Self {
+            methods: HashMap::new(),
+        };
+
+        // Validator status methods
+        table.register("validator_status", RpcServer::rpc_validator_status);
+        table.register("consensus_status", RpcServer::rpc_consensus_status);
+        table.register("p2p_status", RpcServer::rpc_p2p_status);
+        table.register("storage_status", RpcServer::rpc_storage_status);
+
+        // Network methods
+        table.register("network_info", RpcServer::rpc_network_info);
+        table.register("peer_list", RpcServer::rpc_peer_list);
+
+        // Block, attestation and validator queries
+        super::blocks::register(&mut table);
+
+        // Utility methods
+        super::health::register(&mut table);
+        table.register("version", RpcServer::rpc_version);
+
+        // Operator methods, gated by the dispatcher
+        super::admin::register(&mut table);
+
+        // Subscriptions and schema discovery
+        super::ws::describe(&mut table);
+        super::discover::register(&mut table);
+
+        table
+    }
+
+    /// Register `handler` under `name`
+    pub(super) fn register<P, R, F, Fut>(&mut self, name: &'static str, handler: F) -> &mut MethodSchema
+    where
+        P: DeserializeOwned + JsonSchema + Send + 'static,
+        R: Serialize + JsonSchema + Send + 'static,
+        F: Fn(Arc<RpcServer>, P) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = RpcResult<R>> + Send + 'static,
+    {
+        let handler = move |server: Arc<RpcServer>, params: Value| -> BoxFuture<'static, RpcResult<Value>> {
+            match serde_json::from_value::<P>(params) {
+                Ok(params) => {
+                    let result = handler(server, params);
+                    Box::pin(async move {
+                        let result = result.await?;
+                        Ok(serde_json::to_value(result).map_err(anyhow::Error::from)?)
+                    })
+                }
+                Err(e) => Box::pin(futures::future::ready(Err(JsonRpcError::new(
+                    INVALID_PARAMS,
+                    format!("Invalid params: {}", e),
+                )))),
+            }
+        };
+
+        self.insert(name, Some(Box::new(handler)), MethodSchema::new::<P, R>())
+    }
+
+    /// Publish the schema of a method that is dispatched elsewhere
+    pub(super) fn describe<P: JsonSchema, R: JsonSchema>(&mut self, name: &'static str) -> &mut MethodSchema {
+        self.insert(name, None, MethodSchema::new::<P, R>())
+    }
+
+    fn insert(&mut self, name: &'static str, handler: Option<RpcHandler>, schema: MethodSchema) -> &mut MethodSchema {
+        &mut self.methods.entry(name).insert_entry(Method { handler, schema }).into_mut().schema
+    }
+
+    /// Registered name equal to `method`, if any
+    pub(super) fn name(&self, method: &str) -> Option<&'static str> {
+        self.methods.get_key_value(method).map(|(name, _)| *name)
+    }
+
+    /// Schemas of all methods, sorted by name
+    pub(super) fn schemas(&self) -> Vec<(&'static str, &MethodSchema)> {
+        let mut schemas: Vec<_> = self.methods.iter().map(|(name, method)| (*name, &method.schema)).collect();
+        schemas.sort_by_key(|(name, _)| *name);
+        schemas
+    }
+
+    /// Start a call to `method`, or `None` if no such method is registered
+    pub(super) fn call(
+        &self,
+        server: Arc<RpcServer>,
+        method: &str,
+        params: Value,
+    ) -> Option<BoxFuture<'static, RpcResult<Value>>> {
+        let handler = self.methods.get(method)?.handler.as_ref()?;
+        Some(handler(server, params))
+    }
+}
+
+/// Params of methods that take none
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct NoParams {}
+
+/// Result of `validator_status`
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ValidatorStatusResult {
+    pub is_validator: bool,
+    pub is_active: bool,
+    pub stake: u64,
+    pub uptime_percentage: f64,
+    pub infrastructure: InfrastructureStatus,
+}
+
+/// Result of `network_info`
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct NetworkInfoResult {
+    pub network_name: String,
+    pub network_id: u64,
+    pub genesis_hash: String,
+    pub commerce_block_time: u64,
+    pub security_block_time: u64,
+}
+
+/// Result of `peer_list`
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct PeerListResult {
+    pub peers: Vec<ConnectedPeer>,
+}
+
+/// Result of `version`
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct VersionResult {
+    pub version: String,
+    pub name: String,
+    pub git_commit: String,
+}
+
+impl RpcServer {
+    /// RPC method: validator_status
+    async fn rpc_validator_status(self: Arc<Self>, _params: NoParams) -> RpcResult<ValidatorStatusResult> {
+        let consensus = self.consensus.get_status();
+
+        Ok(ValidatorStatusResult {
+            is_validator: consensus.is_validator,
+            is_active: consensus.is_active,
+            stake: consensus.stake,
+            uptime_percentage: consensus.uptime_percentage,
+            infrastructure: self.infrastructure.status().await?,
+        })
+    }
+
+    /// RPC method: consensus_status
+    async fn rpc_consensus_status(self: Arc<Self>, _params: NoParams) -> RpcResult<ConsensusStatus> {
+        Ok(self.consensus.get_status())
+    }
+
+    /// RPC method: p2p_status
+    async fn rpc_p2p_status(self: Arc<Self>, _params: NoParams) -> RpcResult<P2PStatus> {
+        Ok(self.p2p_network.status().await?)
+    }
+
+    /// RPC method: storage_status
+    async fn rpc_storage_status(self: Arc<Self>, _params: NoParams) -> RpcResult<StorageStatus> {
+        Ok(crate::pruner::storage_status(&self.config.storage, &self.chain)?)
+    }
+
+    /// RPC method: network_info
+    async fn rpc_network_info(self: Arc<Self>, _params: NoParams) -> RpcResult<NetworkInfoResult> {
+        Ok(NetworkInfoResult {
+            network_name: self.config.network.name.clone(),
+            network_id: self.config.network.id,
+            genesis_hash: self.config.network.genesis_hash.clone(),
+            commerce_block_time: self.config.network.chain_spec.commerce_block_time,
+            security_block_time: self.config.network.chain_spec.security_block_time,
+        })
+    }
+
+    /// RPC method: peer_list
+    async fn rpc_peer_list(self: Arc<Self>, _params: NoParams) -> RpcResult<PeerListResult> {
+        Ok(PeerListResult {
+            peers: self.p2p_network.connected_peers().await,
+        })
+    }
+
+    /// RPC method: version
+    async fn rpc_version(self: Arc<Self>, _params: NoParams) -> RpcResult<VersionResult> {
+        Ok(VersionResult {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            name: env!("CARGO_PKG_NAME").to_string(),
+            git_commit: "unknown".to_string(), // TODO: Include git commit hash
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rpc::tests::test_server;
+
+    #[tokio::test]
+    async fn test_typed_params_and_results() {
+        let server = Arc::new(test_server().await);
+
+        let status = server
+            .methods
+            .call(server.clone(), "consensus_status", Value::Array(vec![]))
+            .unwrap()
+            .await
+            .unwrap();
+        let status: ConsensusStatus = serde_json::from_value(status).unwrap();
+        assert_eq!(status.stake, server.config.validator.validator_stake);
+
+        // Positional and named params are equivalent
+        let block = crate::chain::tests::block(crate::events::Layer::Security, 42, 1);
+        server.chain.insert_block(&block).unwrap();
+        for params in [serde_json::json!([42, "security"]), serde_json::json!({ "height": 42, "layer": "security" })] {
+            let result = server
+                .methods
+                .call(server.clone(), "block_by_height", params)
+                .unwrap()
+                .await
+                .unwrap();
+            assert_eq!(result["header"]["hash"], block.header.hash);
+        }
+
+        for params in [serde_json::json!([]), serde_json::json!(["tall"]), serde_json::json!([1, "commerce", 2])] {
+            let error = server
+                .methods
+                .call(server.clone(), "block_by_height", params)
+                .unwrap()
+                .await
+                .unwrap_err();
+            assert_eq!(error.code, INVALID_PARAMS);
+        }
+
+        let error = server
+            .methods
+            .call(server.clone(), "version", serde_json::json!(["unexpected"]))
+            .unwrap()
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, INVALID_PARAMS);
+
+        assert!(server.methods.call(server.clone(), "nope", Value::Array(vec![])).is_none());
+    }
+}
@@ -0,0 +1,125 @@
+//! Per-IP token bucket rate limiting for HTTP and WebSocket callers
+//!
+//! Each remote IP gets a bucket of `rate_limit_burst` tokens that refills at
+//! `rate_limit_per_second`; every call takes one token, so a batch costs as
+//! many tokens as it has calls. IPC is not limited.
+
+use crate::config::RpcConfig;
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Buckets tracked before idle (fully refilled) ones are dropped
+const MAX_TRACKED_IPS: usize = 10_000;
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+struct Buckets {
+    /// Tokens added per second; 0 disables limiting
+    rate: f64,
+    burst: f64,
+    by_ip: HashMap<IpAddr, Bucket>,
+}
+
+/// Token buckets keyed by remote IP
+pub(super) struct RateLimiter {
+    buckets: Mutex<Buckets>,
+}
+
+impl RateLimiter {
+    pub(super) fn new(config: &RpcConfig) -> Self {
+        Self {
+            buckets: Mutex::new(Buckets {
+                rate: config.rate_limit_per_second as f64,
+                burst: config.rate_limit_burst.max(1) as f64,
+                by_ip: HashMap::new(),
+            }),
+        }
+    }
+
+    /// Apply new limits; existing buckets keep their tokens up to the new burst
+    pub(super) fn reconfigure(&self, config: &RpcConfig) {
+        let mut buckets = self.buckets.lock().unwrap();
+        buckets.rate = config.rate_limit_per_second as f64;
+        buckets.burst = config.rate_limit_burst.max(1) as f64;
+    }
+
+    /// Take a token for `ip`, or return how long until one is available
+    pub(super) fn check(&self, ip: IpAddr) -> Result<(), Duration> {
+        self.take(ip, 1)
+    }
+
+    /// Take `tokens` tokens for `ip` at once, or return how long until they
+    /// are available
+    pub(super) fn take(&self, ip: IpAddr, tokens: usize) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap();
+        let Buckets { rate, burst, by_ip } = &mut *buckets;
+        let (rate, burst) = (*rate, *burst);
+        let cost = tokens as f64;
+        if rate <= 0.0 || tokens == 0 {
+            return Ok(());
+        }
+
+        let now = Instant::now();
+        if by_ip.len() >= MAX_TRACKED_IPS && !by_ip.contains_key(&ip) {
+            by_ip.retain(|_, bucket| refill(bucket, now, rate, burst) < burst);
+        }
+
+        let bucket = by_ip.entry(ip).or_insert(Bucket {
+            tokens: burst,
+            updated: now,
+        });
+        bucket.tokens = refill(bucket, now, rate, burst);
+        bucket.updated = now;
+
+        if bucket.tokens >= cost {
+            bucket.tokens -= cost;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((cost - bucket.tokens) / rate))
+        }
+    }
+}
+
+fn refill(bucket: &Bucket, now: Instant, rate: f64, burst: f64) -> f64 {
+    let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+    (bucket.tokens + elapsed * rate).min(burst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket() {
+        let mut config = crate::config::ValidatorConfig::new_for_network("devnet").unwrap().rpc;
+        config.rate_limit_per_second = 1;
+        config.rate_limit_burst = 3;
+        let limiter = RateLimiter::new(&config);
+        let (a, b): (IpAddr, IpAddr) = ("10.0.0.1".parse().unwrap(), "10.0.0.2".parse().unwrap());
+
+        for _ in 0..3 {
+            assert!(limiter.check(a).is_ok());
+        }
+        let retry_after = limiter.check(a).unwrap_err();
+        assert!(retry_after > Duration::ZERO && retry_after <= Duration::from_secs(1));
+
+        // Buckets are per IP; a batch takes a token per call
+        assert!(limiter.take(b, 2).is_ok());
+        assert!(limiter.take(b, 2).is_err());
+        assert!(limiter.check(b).is_ok());
+
+        config.rate_limit_per_second = 0;
+        let unlimited = RateLimiter::new(&config);
+        assert!((0..1000).all(|_| unlimited.check(a).is_ok()));
+
+        // Reloaded limits apply to existing callers
+        limiter.reconfigure(&config);
+        assert!((0..1000).all(|_| limiter.check(a).is_ok()));
+    }
+}
@@ -0,0 +1,93 @@
+//! Request accounting for the JSON-RPC server
+//!
+//! Counts every call across transports, and per registered method the
+//! number of calls, errors and latencies. Snapshots feed `RpcStatus` and the
+//! metrics endpoint.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+#[derive(Default)]
+struct MethodCounters {
+    calls: u64,
+    errors: u64,
+    total_latency: Duration,
+    max_latency: Duration,
+}
+
+/// Counters of one method
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MethodStats {
+    pub method: String,
+    pub calls: u64,
+    pub errors: u64,
+    pub avg_latency_ms: f64,
+    pub max_latency_ms: f64,
+    #[serde(default)]
+    pub total_latency_ms: f64,
+}
+
+#[derive(Default)]
+pub(super) struct RpcStats {
+    total_requests: AtomicU64,
+    total_errors: AtomicU64,
+    rate_limited: AtomicU64,
+    /// Keyed by registered method names only, so the map stays bounded
+    methods: Mutex<HashMap<&'static str, MethodCounters>>,
+}
+
+impl RpcStats {
+    /// Account for one call; `method` is `None` when it was not a known method
+    pub(super) fn record(&self, method: Option<&'static str>, latency: Duration, is_error: bool) {
+        self.total_requests.fetch_add(1, Ordering::Relaxed);
+        if is_error {
+            self.total_errors.fetch_add(1, Ordering::Relaxed);
+        }
+
+        if let Some(method) = method {
+            let mut methods = self.methods.lock().unwrap();
+            let counters = methods.entry(method).or_default();
+            counters.calls += 1;
+            counters.errors += is_error as u64;
+            counters.total_latency += latency;
+            counters.max_latency = counters.max_latency.max(latency);
+        }
+    }
+
+    pub(super) fn record_rate_limited(&self) {
+        self.rate_limited.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(super) fn total_requests(&self) -> u64 {
+        self.total_requests.load(Ordering::Relaxed)
+    }
+
+    pub(super) fn total_errors(&self) -> u64 {
+        self.total_errors.load(Ordering::Relaxed)
+    }
+
+    pub(super) fn rate_limited(&self) -> u64 {
+        self.rate_limited.load(Ordering::Relaxed)
+    }
+
+    /// Per-method counters, sorted by method name
+    pub(super) fn methods(&self) -> Vec<MethodStats> {
+        let methods = self.methods.lock().unwrap();
+        let mut stats: Vec<MethodStats> = methods
+            .iter()
+            .map(|(method, counters)| MethodStats {
+                method: method.to_string(),
+                calls: counters.calls,
+                errors: counters.errors,
+                avg_latency_ms: counters.total_latency.as_secs_f64() * 1000.0 / counters.calls as f64,
+                max_latency_ms: counters.max_latency.as_secs_f64() * 1000.0,
+                total_latency_ms: counters.total_latency.as_secs_f64() * 1000.0,
+            })
+            .collect();
+        stats.sort_by(|a, b| a.method.cmp(&b.method));
+        stats
+    }
+}
@@ -0,0 +1,403 @@
+//! WebSocket transport and event subscriptions for the JSON-RPC server
+//!
+//! WebSocket connections are upgraded from `GET /` on the RPC port and carry
+//! the same JSON-RPC payloads as HTTP. In addition they may call
+//! `subscribe` with a topic name, after which matching node events are
+//! pushed as `subscription` notifications until `unsubscribe` is called or
+//! the connection closes. Notifications are queued per connection up to
+//! `NOTIFICATION_BUFFER`; a client that falls further behind is disconnected
+//! rather than buffered without bound.
+
+use super::http::empty_response;
+use super::methods::MethodTable;
+use super::{JsonRpcResponse, RpcServer, Transport, INVALID_PARAMS, METHOD_NOT_FOUND, RATE_LIMITED};
+use crate::events::{EventBus, Topic};
+
+use futures::{SinkExt, StreamExt};
+use http_body_util::Full;
+use hyper::body::Bytes;
+use hyper::header::{self, HeaderValue};
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use schemars::JsonSchema;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{broadcast, mpsc, Notify, OwnedSemaphorePermit};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::{CloseFrame, Role, WebSocketConfig};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+use tracing::{debug, warn};
+
+/// Subscriptions a single connection may hold at once
+const MAX_SUBSCRIPTIONS_PER_CONNECTION: usize = 64;
+
+/// Notifications queued for a connection before it counts as lagging
+const NOTIFICATION_BUFFER: usize = 256;
+
+/// Method name used for pushed subscription notifications
+const SUBSCRIPTION_NOTIFICATION: &str = "subscription";
+
+/// Params of `subscribe`
+#[derive(JsonSchema)]
+#[allow(dead_code)] // Only describes the params; they are matched positionally in `handle_subscription_call`
+struct SubscribeParams {
+    topic: Topic,
+}
+
+/// Params of `unsubscribe`
+#[derive(JsonSchema)]
+#[allow(dead_code)] // Only describes the params; they are matched positionally in `handle_subscription_call`
+struct UnsubscribeParams {
+    /// Id returned by `subscribe`
+    subscription: String,
+}
+
+/// Publish the subscription methods, which are answered per connection
+/// rather than through the method table
+pub(super) fn describe(table: &mut MethodTable) {
+    table.describe::<SubscribeParams, String>("subscribe");
+    table.describe::<UnsubscribeParams, bool>("unsubscribe");
+}
+
+/// Per-connection subscription state
+pub(super) struct WsSession {
+    events: EventBus,
+    outgoing: mpsc::Sender<Value>,
+    subscriptions: Mutex<HashMap<String, JoinHandle<()>>>,
+    /// Signalled when a subscription could not keep up with its events
+    lagged: Arc<Notify>,
+    /// Whether the handshake carried valid admin credentials
+    admin: bool,
+}
+
+impl WsSession {
+    pub(super) fn new(events: EventBus, outgoing: mpsc::Sender<Value>, admin: bool) -> Self {
+        Self {
+            events,
+            outgoing,
+            subscriptions: Mutex::new(HashMap::new()),
+            lagged: Arc::new(Notify::new()),
+            admin,
+        }
+    }
+
+    pub(super) fn is_admin(&self) -> bool {
+        self.admin
+    }
+
+    /// Start forwarding events of `topic`, returning the subscription id
+    fn subscribe(&self, topic: Topic) -> Result<String, (i32, String)> {
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        if subscriptions.len() >= MAX_SUBSCRIPTIONS_PER_CONNECTION {
+            return Err((
+                INVALID_PARAMS,
+                format!("Too many subscriptions (max {})", MAX_SUBSCRIPTIONS_PER_CONNECTION),
+            ));
+        }
+
+        let id = format!("{:#018x}", rand::random::<u64>());
+        let mut receiver = self.events.subscribe();
+        let outgoing = self.outgoing.clone();
+        let lagged = self.lagged.clone();
+        let subscription = id.clone();
+
+        let task = tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) if topic.matches(&event) => {
+                        let notification = json!({
+                            "jsonrpc": "2.0",
+                            "method": SUBSCRIPTION_NOTIFICATION,
+                            "params": {
+                                "subscription": subscription,
+                                "result": event,
+                            },
+                        });
+                        match outgoing.try_send(notification) {
+                            Ok(()) => {}
+                            Err(mpsc::error::TrySendError::Full(_)) => {
+                                warn!("Subscription {} lagged, closing its connection", subscription);
+                                lagged.notify_one();
+                                break;
+                            }
+                            Err(mpsc::error::TrySendError::Closed(_)) => break,
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Subscription {} lagged, dropped {} events; closing its connection", subscription, skipped);
+                        lagged.notify_one();
+                        break;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+
+        subscriptions.insert(id.clone(), task);
+        Ok(id)
+    }
+
+    /// Stop a subscription; returns whether it existed
+    fn unsubscribe(&self, id: &str) -> bool {
+        match self.subscriptions.lock().unwrap().remove(id) {
+            Some(task) => {
+                task.abort();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl Drop for WsSession {
+    fn drop(&mut self) {
+        for (_, task) in self.subscriptions.lock().unwrap().drain() {
+            task.abort();
+        }
+    }
+}
+
+/// Handle `subscribe`/`unsubscribe`; `None` for any other method
+pub(super) fn handle_subscription_call(
+    session: Option<&WsSession>,
+    method: &str,
+    params: &[Value],
+) -> Option<Result<Value, (i32, String)>> {
+    if method != "subscribe" && method != "unsubscribe" {
+        return None;
+    }
+
+    let Some(session) = session else {
+        return Some(Err((
+            METHOD_NOT_FOUND,
+            "Subscriptions are only available over WebSocket".to_string(),
+        )));
+    };
+
+    Some(match (method, params) {
+        ("subscribe", [topic]) => serde_json::from_value::<Topic>(topic.clone())
+            .map_err(|_| (INVALID_PARAMS, format!("Unknown subscription topic: {}", topic)))
+            .and_then(|topic| session.subscribe(topic))
+            .map(Value::String),
+        ("unsubscribe", [Value::String(id)]) => Ok(Value::Bool(session.unsubscribe(id))),
+        ("subscribe", _) => Err((INVALID_PARAMS, "Expected a single topic parameter".to_string())),
+        _ => Err((INVALID_PARAMS, "Expected a single subscription id parameter".to_string())),
+    })
+}
+
+/// Whether the request is a WebSocket handshake
+pub(super) fn is_upgrade_request<B>(request: &Request<B>) -> bool {
+    let has_token = |name: header::HeaderName, token: &str| {
+        request.headers().get_all(name).iter().any(|value| {
+            value
+                .to_str()
+                .map(|v| v.split(',').any(|part| part.trim().eq_ignore_ascii_case(token)))
+                .unwrap_or(false)
+        })
+    };
+
+    request.method() == Method::GET
+        && has_token(header::CONNECTION, "upgrade")
+        && has_token(header::UPGRADE, "websocket")
+}
+
+/// Answer a WebSocket handshake and serve the upgraded connection
+pub(super) fn upgrade<B>(
+    server: Arc<RpcServer>,
+    mut request: Request<B>,
+    remote: IpAddr,
+    permit: Arc<OwnedSemaphorePermit>,
+    admin: bool,
+) -> Response<Full<Bytes>> {
+    let version_ok = request
+        .headers()
+        .get(header::SEC_WEBSOCKET_VERSION)
+        .is_some_and(|version| version == "13");
+    let Some(key) = request.headers().get(header::SEC_WEBSOCKET_KEY).filter(|_| version_ok) else {
+        return empty_response(StatusCode::BAD_REQUEST);
+    };
+    let accept = derive_accept_key(key.as_bytes());
+
+    let on_upgrade = hyper::upgrade::on(&mut request);
+    tokio::spawn(async move {
+        match on_upgrade.await {
+            Ok(upgraded) => {
+                // Bound frames and messages by the request limit before they are buffered
+                let mut config = WebSocketConfig::default();
+                config.max_message_size = Some(server.config.rpc.max_request_body_size);
+                config.max_frame_size = Some(server.config.rpc.max_request_body_size);
+                let stream =
+                    WebSocketStream::from_raw_socket(TokioIo::new(upgraded), Role::Server, Some(config)).await;
+                serve_session(server, stream, remote, admin).await;
+            }
+            Err(e) => debug!("WebSocket upgrade failed: {}", e),
+        }
+        drop(permit);
+    });
+
+    let mut response = empty_response(StatusCode::SWITCHING_PROTOCOLS);
+    let headers = response.headers_mut();
+    headers.insert(header::CONNECTION, HeaderValue::from_static("upgrade"));
+    headers.insert(header::UPGRADE, HeaderValue::from_static("websocket"));
+    if let Ok(accept) = HeaderValue::from_str(&accept) {
+        headers.insert(header::SEC_WEBSOCKET_ACCEPT, accept);
+    }
+    response
+}
+
+/// Pump requests, replies and notifications over an upgraded connection
+async fn serve_session<S>(server: Arc<RpcServer>, stream: WebSocketStream<S>, remote: IpAddr, admin: bool)
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+{
+    let (mut sink, mut incoming) = stream.split();
+    let (outgoing, mut notifications) = mpsc::channel(NOTIFICATION_BUFFER);
+    let session = WsSession::new(server.events.clone(), outgoing, admin);
+    let lagged = session.lagged.clone();
+
+    loop {
+        tokio::select! {
+            message = incoming.next() => {
+                let payload = match message {
+                    Some(Ok(Message::Text(text))) => Bytes::from(text),
+                    Some(Ok(Message::Binary(data))) => Bytes::from(data),
+                    Some(Ok(Message::Close(_))) | None => break,
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => {
+                        debug!("WebSocket connection error: {}", e);
+                        break;
+                    }
+                };
+
+                if payload.len() > server.config.rpc.max_request_body_size {
+                    debug!("Closing WebSocket connection: message too large");
+                    break;
+                }
+
+                if server.rate_limiter.check(remote).is_err() {
+                    server.stats.record_rate_limited();
+                    let reply = json!(JsonRpcResponse::error(None, RATE_LIMITED, "Rate limit exceeded"));
+                    if sink.send(Message::Text(reply.to_string())).await.is_err() {
+                        break;
+                    }
+                    continue;
+                }
+
+                let reply = match server.parse_payload(&payload) {
+                    // The message took a token on arrival; every further call in a batch takes one more
+                    Ok(payload) => match server.rate_limiter.take(remote, payload.calls() - 1) {
+                        Ok(()) => server.dispatch(payload, Transport::WebSocket(&session)).await,
+                        Err(_) => {
+                            server.stats.record_rate_limited();
+                            Some(json!(JsonRpcResponse::error(None, RATE_LIMITED, "Rate limit exceeded")))
+                        }
+                    },
+                    Err(reply) => Some(reply),
+                };
+                if let Some(reply) = reply {
+                    if sink.send(Message::Text(reply.to_string())).await.is_err() {
+                        break;
+                    }
+                }
+            }
+            Some(notification) = notifications.recv() => {
+                if sink.send(Message::Text(notification.to_string())).await.is_err() {
+                    break;
+                }
+            }
+            _ = lagged.notified() => {
+                debug!("Closing WebSocket connection: subscriber lagging");
+                let frame = CloseFrame {
+                    code: CloseCode::Policy,
+                    reason: "Subscriber lagging".into(),
+                };
+                let _ = sink.send(Message::Close(Some(frame))).await;
+                break;
+            }
+        }
+    }
+
+    let _ = sink.close().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::NodeEvent;
+    use crate::rpc::tests::test_server;
+
+    #[tokio::test]
+    async fn test_subscriptions() {
+        let server = Arc::new(test_server().await);
+        let (outgoing, mut notifications) = mpsc::channel(NOTIFICATION_BUFFER);
+        let session = WsSession::new(server.events.clone(), outgoing, false);
+
+        let reply = server
+            .handle_payload(
+                br#"{"jsonrpc":"2.0","method":"subscribe","params":["peers"],"id":1}"#,
+                Transport::WebSocket(&session),
+            )
+            .await
+            .unwrap();
+        let id = reply["result"].as_str().unwrap().to_string();
+
+        // Events of other topics are not forwarded
+        server.events.publish(NodeEvent::MissedSlot {
+            layer: crate::events::Layer::Commerce,
+            slot: 1,
+        });
+        server.events.publish(NodeEvent::PeerConnected { peer_id: "peer".to_string() });
+
+        let notification = notifications.recv().await.unwrap();
+        assert_eq!(notification["method"], SUBSCRIPTION_NOTIFICATION);
+        assert_eq!(notification["params"]["subscription"], id);
+        assert_eq!(notification["params"]["result"]["type"], "peer_connected");
+        assert_eq!(notification["params"]["result"]["peer_id"], "peer");
+
+        let payload = format!(r#"{{"jsonrpc":"2.0","method":"unsubscribe","params":["{}"],"id":2}}"#, id);
+        let reply = server.handle_payload(payload.as_bytes(), Transport::WebSocket(&session)).await.unwrap();
+        assert_eq!(reply["result"], true);
+        let reply = server.handle_payload(payload.as_bytes(), Transport::WebSocket(&session)).await.unwrap();
+        assert_eq!(reply["result"], false);
+
+        let reply = server
+            .handle_payload(
+                br#"{"jsonrpc":"2.0","method":"subscribe","params":["blocks"],"id":3}"#,
+                Transport::WebSocket(&session),
+            )
+            .await
+            .unwrap();
+        assert_eq!(reply["error"]["code"], INVALID_PARAMS);
+
+        // Plain HTTP calls have no session to deliver notifications to
+        let reply = server
+            .handle_payload(br#"{"jsonrpc":"2.0","method":"subscribe","params":["peers"],"id":4}"#, Transport::Http { admin: false })
+            .await
+            .unwrap();
+        assert_eq!(reply["error"]["code"], METHOD_NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_lagging_subscriber_is_dropped() {
+        let server = Arc::new(test_server().await);
+        let (outgoing, mut notifications) = mpsc::channel(1);
+        let session = WsSession::new(server.events.clone(), outgoing, false);
+        session.subscribe(Topic::Peers).unwrap();
+
+        // The first event fills the queue; the second finds it full
+        for peer in ["a", "b"] {
+            server.events.publish(NodeEvent::PeerConnected { peer_id: peer.to_string() });
+        }
+        tokio::time::timeout(std::time::Duration::from_secs(5), session.lagged.notified())
+            .await
+            .unwrap();
+        assert_eq!(notifications.recv().await.unwrap()["params"]["result"]["peer_id"], "a");
+        assert!(notifications.try_recv().is_err());
+    }
+}
@@ -0,0 +1,185 @@
+//! Slashing protection database
+//!
+//! Records every block and attestation this validator signs under
+//! `data_dir/slashing_protection`, separate from the node database so it
+//! can be kept when chain data is wiped. Signing requests that could be
+//! slashable are refused: a second block for a recorded slot, a slot at or
+//! below the highest signed one, or an attestation whose source or target
+//! does not move past the last signed one. Re-signing the same message is
+//! allowed.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+const BLOCKS_TREE: &str = "signed_blocks";
+const ATTESTATIONS_TREE: &str = "signed_attestations";
+
+/// Highest slot and epochs signed so far
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Watermark {
+    pub block_slot: Option<u64>,
+    pub source_epoch: Option<u64>,
+    pub target_epoch: Option<u64>,
+}
+
+impl Watermark {
+    /// Whether this history covers signatures beyond `other`'s, so replacing
+    /// it with `other` would forget them
+    pub fn is_ahead_of(&self, other: &Watermark) -> bool {
+        self.block_slot > other.block_slot
+            || self.source_epoch > other.source_epoch
+            || self.target_epoch > other.target_epoch
+    }
+}
+
+/// Signed attestation as recorded by target epoch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SignedAttestation {
+    source_epoch: u64,
+    signing_root: Vec<u8>,
+}
+
+pub struct SlashingProtection {
+    db: sled::Db,
+    blocks: sled::Tree,
+    attestations: sled::Tree,
+}
+
+impl SlashingProtection {
+    /// Open or create the database at `path`
+    pub fn open(path: &Path) -> Result<Self> {
+        let db = sled::open(path)
+            .with_context(|| format!("Failed to open slashing protection database at {}", path.display()))?;
+        Ok(Self {
+            blocks: db.open_tree(BLOCKS_TREE)?,
+            attestations: db.open_tree(ATTESTATIONS_TREE)?,
+            db,
+        })
+    }
+
+    /// Record a block proposal for `slot`, refusing a slashable one
+    pub fn sign_block(&self, slot: u64, signing_root: &[u8]) -> Result<()> {
+        if let Some(existing) = self.blocks.get(slot.to_be_bytes())? {
+            if existing == signing_root {
+                return Ok(());
+            }
+            return Err(anyhow::anyhow!("Refusing to sign a second block for slot {}", slot));
+        }
+        if let Some(highest) = self.watermark()?.block_slot {
+            if slot <= highest {
+                return Err(anyhow::anyhow!(
+                    "Refusing to sign a block for slot {} at or below signed slot {}",
+                    slot,
+                    highest
+                ));
+            }
+        }
+
+        self.blocks.insert(slot.to_be_bytes(), signing_root)?;
+        self.db.flush()?;
+        Ok(())
+    }
+
+    /// Record an attestation from `source_epoch` to `target_epoch`, refusing
+    /// a slashable one
+    pub fn sign_attestation(&self, source_epoch: u64, target_epoch: u64, signing_root: &[u8]) -> Result<()> {
+        if let Some(existing) = self.attestations.get(target_epoch.to_be_bytes())? {
+            let existing: SignedAttestation = bincode::deserialize(&existing)?;
+            if existing.source_epoch == source_epoch && existing.signing_root == signing_root {
+                return Ok(());
+            }
+            return Err(anyhow::anyhow!(
+                "Refusing to sign a second attestation for target epoch {}",
+                target_epoch
+            ));
+        }
+
+        let watermark = self.watermark()?;
+        if watermark.source_epoch.is_some_and(|highest| source_epoch < highest)
+            || watermark.target_epoch.is_some_and(|highest| target_epoch <= highest)
+        {
+            return Err(anyhow::anyhow!(
+                "Refusing to sign an attestation {} → {} behind signed {:?} → {:?}",
+                source_epoch,
+                target_epoch,
+                watermark.source_epoch,
+                watermark.target_epoch
+            ));
+        }
+
+        let record = SignedAttestation {
+            source_epoch,
+            signing_root: signing_root.to_vec(),
+        };
+        self.attestations
+            .insert(target_epoch.to_be_bytes(), bincode::serialize(&record)?)?;
+        self.db.flush()?;
+        Ok(())
+    }
+
+    /// Highest slot and epochs signed so far
+    pub fn watermark(&self) -> Result<Watermark> {
+        let block_slot = match self.blocks.last()? {
+            Some((key, _)) => Some(decode_u64(&key)?),
+            None => None,
+        };
+        let (source_epoch, target_epoch) = match self.attestations.last()? {
+            Some((key, value)) => {
+                let attestation: SignedAttestation = bincode::deserialize(&value)?;
+                (Some(attestation.source_epoch), Some(decode_u64(&key)?))
+            }
+            None => (None, None),
+        };
+
+        Ok(Watermark {
+            block_slot,
+            source_epoch,
+            target_epoch,
+        })
+    }
+
+    /// Write pending changes to disk
+    pub fn flush(&self) -> Result<()> {
+        self.db.flush()?;
+        Ok(())
+    }
+}
+
+fn decode_u64(bytes: &[u8]) -> Result<u64> {
+    Ok(u64::from_be_bytes(
+        bytes.try_into().context("Malformed slashing protection key")?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_refuses_slashable_signatures() {
+        let dir = std::env::temp_dir().join(format!("omne-slashing-{}", rand::random::<u32>()));
+        let protection = SlashingProtection::open(&dir).unwrap();
+
+        protection.sign_block(10, b"a").unwrap();
+        protection.sign_block(10, b"a").unwrap();
+        assert!(protection.sign_block(10, b"b").is_err(), "double proposal");
+        assert!(protection.sign_block(9, b"c").is_err(), "below the watermark");
+
+        protection.sign_attestation(1, 2, b"a").unwrap();
+        assert!(protection.sign_attestation(1, 2, b"b").is_err(), "double vote");
+        assert!(protection.sign_attestation(0, 3, b"c").is_err(), "source moved back");
+        protection.sign_attestation(2, 3, b"d").unwrap();
+
+        let watermark = protection.watermark().unwrap();
+        assert_eq!(
+            watermark,
+            Watermark { block_slot: Some(10), source_epoch: Some(2), target_epoch: Some(3) }
+        );
+        assert!(watermark.is_ahead_of(&Watermark::default()));
+        assert!(!Watermark::default().is_ahead_of(&watermark));
+
+        drop(protection);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,216 @@
+//! `omne-nexus status`: summarize a running node over RPC
+//!
+//! Collects health, consensus, P2P and validator status from the node and
+//! prints them as a table or as JSON. One-shot runs report failure through
+//! the exit status; `--watch` keeps refreshing until interrupted.
+
+use crate::consensus::ConsensusStatus;
+use crate::p2p::P2PStatus;
+use crate::pruner::StorageStatus;
+use crate::rpc::client::RpcClient;
+use crate::rpc::{HealthResult, ValidatorStatusResult};
+use crate::utils::format_bytes;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::fmt::Write;
+use std::time::Duration;
+
+/// Everything `status` shows about a node
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StatusReport {
+    pub endpoint: String,
+    pub healthy: bool,
+    pub health: HealthResult,
+    pub consensus: ConsensusStatus,
+    pub p2p: P2PStatus,
+    pub validator: ValidatorStatusResult,
+    pub storage: StorageStatus,
+}
+
+/// Query all status methods of the node behind `client`
+pub async fn fetch(client: &RpcClient) -> Result<StatusReport> {
+    let health: HealthResult = client.call("health", json!([])).await?;
+    let consensus = client.call("consensus_status", json!([])).await?;
+    let p2p = client.call("p2p_status", json!([])).await?;
+    let validator = client.call("validator_status", json!([])).await?;
+    let storage = client.call("storage_status", json!([])).await?;
+
+    Ok(StatusReport {
+        endpoint: client.endpoint().to_string(),
+        healthy: health.ready,
+        health,
+        consensus,
+        p2p,
+        validator,
+        storage,
+    })
+}
+
+/// Print the node status once, or every `watch` interval until Ctrl+C
+///
+/// Returns whether the node was healthy at the last check. A one-shot run
+/// fails if the node cannot be reached; in watch mode errors are shown and
+/// polling continues.
+pub async fn run(client: &RpcClient, json: bool, watch: Option<Duration>) -> Result<bool> {
+    let Some(interval) = watch else {
+        let report = fetch(client).await?;
+        print_report(&report, json)?;
+        return Ok(report.healthy);
+    };
+
+    let mut ticker = tokio::time::interval(interval);
+    let mut healthy = false;
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = tokio::signal::ctrl_c() => return Ok(healthy),
+        }
+
+        if !json {
+            // Clear the screen and move the cursor home
+            print!("\x1b[2J\x1b[H");
+        }
+        match fetch(client).await {
+            Ok(report) => {
+                healthy = report.healthy;
+                print_report(&report, json)?;
+            }
+            Err(e) => {
+                healthy = false;
+                if json {
+                    println!("{}", json!({ "endpoint": client.endpoint().to_string(), "error": e.to_string() }));
+                } else {
+                    println!("❌ {} unreachable: {:#}", client.endpoint(), e);
+                }
+            }
+        }
+    }
+}
+
+fn print_report(report: &StatusReport, json: bool) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string(report)?);
+    } else {
+        print!("{}", render(report));
+    }
+    Ok(())
+}
+
+/// Render the report as a human-readable table
+pub fn render(report: &StatusReport) -> String {
+    let mut out = String::new();
+    let consensus = &report.consensus;
+    let p2p = &report.p2p;
+    let infra = &report.validator.infrastructure;
+
+    let _ = writeln!(
+        out,
+        "Omne Nexus node at {}: {}",
+        report.endpoint,
+        if report.healthy { "✅ healthy" } else { "❌ unhealthy" }
+    );
+    for check in report.health.failing() {
+        let _ = writeln!(out, "  ❌ {}: {}", check.name, check.detail);
+    }
+
+    section(&mut out, "Consensus", &[
+        ("Role", if consensus.is_validator { "validator" } else { "observer" }.to_string()),
+        ("Active", yes_no(consensus.is_active)),
+        ("Validation", if consensus.validation_paused { "paused" } else { "running" }.to_string()),
+        ("Commerce", format!("epoch {}, height {}", consensus.commerce_epoch, consensus.commerce_height)),
+        ("Security", format!("epoch {}, height {}", consensus.security_epoch, consensus.security_height)),
+        ("Uptime", format!("{:.2}%", consensus.uptime_percentage)),
+    ]);
+
+    section(&mut out, "Sync", &[
+        ("Syncing", yes_no(report.health.sync.is_syncing)),
+        (
+            "Best known head",
+            format!("{} ({} blocks behind)", report.health.sync.highest_height, report.health.sync.blocks_behind),
+        ),
+    ]);
+
+    section(&mut out, "Finality", &[
+        ("Finalized epoch", consensus.finalized_epoch.to_string()),
+        ("Finalized height", consensus.finalized_height.to_string()),
+        (
+            "Finality lag",
+            format!("{} blocks", consensus.security_height.saturating_sub(consensus.finalized_height)),
+        ),
+    ]);
+
+    section(&mut out, "P2P", &[
+        ("Peer ID", p2p.local_peer_id.clone()),
+        ("Connected peers", format!("{} (max {})", p2p.connected_peers, p2p.max_peers)),
+        ("Reachability", label(&p2p.reachability)),
+        ("Sentry mode", label(&p2p.sentry_mode)),
+        ("Listening", list(&p2p.listening_addresses)),
+        ("External", list(&p2p.external_addresses)),
+    ]);
+
+    section(&mut out, "Storage", &[
+        ("Disk usage", format_bytes(report.storage.size_on_disk)),
+        ("Pruning", match report.storage.prune_before_epoch {
+            Some(epoch) => format!("{}, keeping epoch {} onwards", label(&report.storage.pruning), epoch),
+            None => label(&report.storage.pruning),
+        }),
+        (
+            "Oldest block",
+            report.storage.oldest_commerce_height.map_or("-".to_string(), |height| height.to_string()),
+        ),
+    ]);
+
+    section(&mut out, "Stake", &[
+        ("Stake", format!("{} OGT", report.validator.stake)),
+        ("Validator", yes_no(report.validator.is_validator)),
+    ]);
+
+    section(&mut out, "Infrastructure", &[
+        ("OON", enabled(infra.oon_enabled)),
+        ("OMP", format!("{}, {} requests served", enabled(infra.services.omp_enabled), infra.services.omp_requests_served)),
+        (
+            "ORC-20 relayer",
+            format!("{}, {} txs relayed", enabled(infra.services.orc20_relayer_enabled), infra.services.orc20_txs_relayed),
+        ),
+        (
+            "Paymaster",
+            format!("{}, {} txs sponsored", enabled(infra.services.paymaster_enabled), infra.services.paymaster_txs_sponsored),
+        ),
+        ("Total revenue", infra.services.total_revenue.to_string()),
+    ]);
+
+    out
+}
+
+fn section(out: &mut String, title: &str, rows: &[(&str, String)]) {
+    let _ = writeln!(out, "\n{}", title);
+    for (key, value) in rows {
+        let _ = writeln!(out, "  {:<20}{}", key, value);
+    }
+}
+
+fn yes_no(value: bool) -> String {
+    if value { "yes" } else { "no" }.to_string()
+}
+
+fn enabled(value: bool) -> String {
+    if value { "enabled" } else { "disabled" }.to_string()
+}
+
+fn list(values: &[String]) -> String {
+    if values.is_empty() {
+        "-".to_string()
+    } else {
+        values.join(", ")
+    }
+}
+
+/// Serialized name of a unit enum variant, e.g. `public`
+fn label<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
@@ -0,0 +1,351 @@
+//! Node database
+//!
+//! A sled database under `data_dir/db` whose trees are exposed as typed
+//! tables of bincode-encoded values. Writes spanning several tables go
+//! through a `WriteBatch` committed in one transaction, so readers never
+//! observe half of an update. The schema version is recorded in the default
+//! tree; older databases are migrated on open and newer ones refused.
+
+mod migrations;
+
+pub use migrations::Migration;
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sled::Transactional;
+use std::marker::PhantomData;
+use std::path::Path;
+use tracing::info;
+
+/// Layout version written by this build
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Key of the schema version in the default tree
+const SCHEMA_VERSION_KEY: &[u8] = b"schema_version";
+
+/// Handle to the node database
+#[derive(Clone)]
+pub struct Store {
+    db: sled::Db,
+}
+
+impl Store {
+    /// Open or create the database at `path`, applying pending migrations
+    pub fn open(path: &Path) -> Result<Self> {
+        let store = Self::open_unmigrated(path)?;
+        store.migrate(false)?;
+        Ok(store)
+    }
+
+    /// Open the database at `path` without migrating it, for inspection
+    pub fn open_unmigrated(path: &Path) -> Result<Self> {
+        let db = sled::open(path)
+            .with_context(|| format!("Failed to open node database at {}", path.display()))?;
+        Self::init(db)
+    }
+
+    /// In-memory database removed on drop, for tests
+    #[cfg(test)]
+    pub fn temporary() -> Self {
+        Self::init(sled::Config::new().temporary(true).open().unwrap()).unwrap()
+    }
+
+    fn init(db: sled::Db) -> Result<Self> {
+        let store = Self { db };
+        let is_new = store.db.get(SCHEMA_VERSION_KEY)?.is_none() && store.db.tree_names().len() == 1;
+        if is_new {
+            store.set_schema_version(SCHEMA_VERSION)?;
+        }
+
+        let version = store.schema_version()?;
+        if version > SCHEMA_VERSION {
+            return Err(anyhow::anyhow!(
+                "Database schema version {} is newer than version {} supported by this build; upgrade omne-nexus",
+                version,
+                SCHEMA_VERSION
+            ));
+        }
+        Ok(store)
+    }
+
+    /// Schema version of the data; 0 for databases predating versioning
+    pub fn schema_version(&self) -> Result<u32> {
+        match self.db.get(SCHEMA_VERSION_KEY)? {
+            Some(bytes) => {
+                let bytes: [u8; 4] = bytes
+                    .as_ref()
+                    .try_into()
+                    .context("Malformed schema version")?;
+                Ok(u32::from_be_bytes(bytes))
+            }
+            None => Ok(0),
+        }
+    }
+
+    fn set_schema_version(&self, version: u32) -> Result<()> {
+        self.db.insert(SCHEMA_VERSION_KEY, &version.to_be_bytes())?;
+        Ok(())
+    }
+
+    /// Migrations needed to bring the data to `SCHEMA_VERSION`
+    pub fn pending_migrations(&self) -> Result<Vec<&'static Migration>> {
+        let version = self.schema_version()?;
+        Ok(migrations::MIGRATIONS
+            .iter()
+            .filter(|migration| migration.from >= version && migration.from < SCHEMA_VERSION)
+            .collect())
+    }
+
+    /// Apply pending migrations in order, or only list them with `dry_run`
+    pub fn migrate(&self, dry_run: bool) -> Result<Vec<&'static Migration>> {
+        let pending = self.pending_migrations()?;
+        if dry_run {
+            return Ok(pending);
+        }
+
+        for migration in &pending {
+            info!(
+                "🗄️  Migrating database schema {} → {}: {}",
+                migration.from,
+                migration.to(),
+                migration.description
+            );
+            migration
+                .apply(&self.db)
+                .with_context(|| format!("Migration from schema version {} failed", migration.from))?;
+            self.set_schema_version(migration.to())?;
+            self.db.flush()?;
+        }
+        Ok(pending)
+    }
+
+    /// Entries and bytes (keys plus values) of every tree, by name
+    pub fn table_sizes(&self) -> Result<Vec<(String, usize, u64)>> {
+        let mut sizes = Vec::new();
+        for name in self.db.tree_names() {
+            let tree = self.db.open_tree(&name)?;
+            let mut bytes = 0u64;
+            for entry in tree.iter() {
+                let (key, value) = entry?;
+                bytes += (key.len() + value.len()) as u64;
+            }
+            sizes.push((String::from_utf8_lossy(&name).into_owned(), tree.len(), bytes));
+        }
+        sizes.sort();
+        Ok(sizes)
+    }
+
+    /// Bytes the database occupies on disk
+    pub fn size_on_disk(&self) -> Result<u64> {
+        Ok(self.db.size_on_disk()?)
+    }
+
+    /// Rewrite the database at `path` into fresh files, reclaiming space
+    /// left by deleted and overwritten entries; returns the size before and after
+    pub fn compact(path: &Path) -> Result<(u64, u64)> {
+        let compacted = path.with_extension("compact");
+        let previous = path.with_extension("previous");
+        let _ = std::fs::remove_dir_all(&compacted);
+
+        let before = {
+            let store = Self::open_unmigrated(path)?;
+            let target = sled::open(&compacted)
+                .with_context(|| format!("Failed to create {}", compacted.display()))?;
+            target.import(store.db.export());
+            target.flush()?;
+            store.size_on_disk()?
+        };
+
+        std::fs::rename(path, &previous)
+            .with_context(|| format!("Failed to move {} aside", path.display()))?;
+        std::fs::rename(&compacted, path)
+            .with_context(|| format!("Failed to move compacted database to {}", path.display()))?;
+        std::fs::remove_dir_all(&previous)?;
+
+        let after = Self::open_unmigrated(path)?.size_on_disk()?;
+        Ok((before, after))
+    }
+
+    /// Underlying database, for stores that manage their own trees
+    pub fn db(&self) -> &sled::Db {
+        &self.db
+    }
+
+    /// Typed table backed by the tree `name`
+    pub fn table<V>(&self, name: &str) -> Result<Table<V>> {
+        let tree = self
+            .db
+            .open_tree(name)
+            .with_context(|| format!("Failed to open {} tree", name))?;
+        Ok(Table {
+            tree,
+            _value: PhantomData,
+        })
+    }
+
+    /// Apply every write in `batch` atomically
+    pub fn commit(&self, batch: WriteBatch) -> Result<()> {
+        if batch.trees.is_empty() {
+            return Ok(());
+        }
+
+        batch
+            .trees
+            .as_slice()
+            .transaction(|trees| {
+                for (tree, writes) in trees.iter().zip(&batch.writes) {
+                    tree.apply_batch(writes)?;
+                }
+                Ok(())
+            })
+            .map_err(|e: sled::transaction::TransactionError<()>| {
+                anyhow::anyhow!("Failed to commit write batch: {:?}", e)
+            })?;
+        Ok(())
+    }
+}
+
+/// A tree holding bincode-encoded values of type `V`
+pub struct Table<V> {
+    tree: sled::Tree,
+    _value: PhantomData<fn() -> V>,
+}
+
+impl<V: Serialize + DeserializeOwned> Table<V> {
+    pub fn get(&self, key: impl AsRef<[u8]>) -> Result<Option<V>> {
+        match self.tree.get(key)? {
+            Some(value) => Ok(Some(bincode::deserialize(&value)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn insert(&self, key: impl AsRef<[u8]>, value: &V) -> Result<()> {
+        self.tree.insert(key.as_ref(), bincode::serialize(value)?)?;
+        Ok(())
+    }
+
+    #[allow(dead_code)] // Part of the table API; nothing deletes single entries yet
+    pub fn remove(&self, key: impl AsRef<[u8]>) -> Result<()> {
+        self.tree.remove(key)?;
+        Ok(())
+    }
+
+    /// Values whose keys start with `prefix`, in key order
+    pub fn values_with_prefix(&self, prefix: impl AsRef<[u8]>) -> Result<Vec<V>> {
+        self.tree
+            .scan_prefix(prefix)
+            .values()
+            .map(|value| Ok(bincode::deserialize(&value?)?))
+            .collect()
+    }
+
+    /// Value with the greatest key starting with `prefix`
+    pub fn last_with_prefix(&self, prefix: impl AsRef<[u8]>) -> Result<Option<V>> {
+        match self.tree.scan_prefix(prefix).next_back() {
+            Some(entry) => Ok(Some(bincode::deserialize(&entry?.1)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Value with the greatest key
+    pub fn last(&self) -> Result<Option<V>> {
+        self.last_with_prefix([])
+    }
+
+    #[allow(dead_code)] // Part of the table API; only tests count entries
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    #[allow(dead_code)] // Pairs with `len`
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+
+    /// Keys starting with `prefix`, in order
+    pub fn keys_with_prefix(&self, prefix: impl AsRef<[u8]>) -> Result<Vec<sled::IVec>> {
+        self.tree
+            .scan_prefix(prefix)
+            .keys()
+            .map(|key| Ok(key?))
+            .collect()
+    }
+
+    /// Entries whose keys start with `prefix`, in key order
+    pub fn iter_prefix(&self, prefix: impl AsRef<[u8]>) -> impl Iterator<Item = Result<(sled::IVec, V)>> {
+        self.tree.scan_prefix(prefix).map(|entry| {
+            let (key, value) = entry?;
+            Ok((key, bincode::deserialize(&value)?))
+        })
+    }
+
+    /// Every entry in key order
+    pub fn iter(&self) -> impl Iterator<Item = Result<(sled::IVec, V)>> {
+        self.iter_prefix([])
+    }
+}
+
+/// Inserts and removals across tables, committed together by `Store::commit`
+#[derive(Default)]
+pub struct WriteBatch {
+    trees: Vec<sled::Tree>,
+    writes: Vec<sled::Batch>,
+}
+
+impl WriteBatch {
+    pub fn insert<V: Serialize>(&mut self, table: &Table<V>, key: impl AsRef<[u8]>, value: &V) -> Result<()> {
+        let value = bincode::serialize(value)?;
+        self.writes_for(&table.tree).insert(key.as_ref(), value);
+        Ok(())
+    }
+
+    pub fn remove<V>(&mut self, table: &Table<V>, key: impl AsRef<[u8]>) {
+        self.writes_for(&table.tree).remove(key.as_ref());
+    }
+
+    fn writes_for(&mut self, tree: &sled::Tree) -> &mut sled::Batch {
+        let index = match self.trees.iter().position(|t| t.name() == tree.name()) {
+            Some(index) => index,
+            None => {
+                self.trees.push(tree.clone());
+                self.writes.push(sled::Batch::default());
+                self.trees.len() - 1
+            }
+        };
+        &mut self.writes[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_and_schema_version() {
+        let dir = std::env::temp_dir().join(format!("omne-store-{}", rand::random::<u32>()));
+        let store = Store::open(&dir).unwrap();
+        assert_eq!(store.schema_version().unwrap(), SCHEMA_VERSION);
+        assert!(store.pending_migrations().unwrap().is_empty());
+
+        let numbers: Table<u64> = store.table("numbers").unwrap();
+        let names: Table<String> = store.table("names").unwrap();
+        numbers.insert("stale", &0).unwrap();
+
+        let mut batch = WriteBatch::default();
+        batch.insert(&numbers, "one", &1).unwrap();
+        batch.insert(&names, "one", &"one".to_string()).unwrap();
+        batch.remove(&numbers, "stale");
+        store.commit(batch).unwrap();
+
+        assert_eq!(numbers.get("one").unwrap(), Some(1));
+        assert_eq!(names.get("one").unwrap().as_deref(), Some("one"));
+        assert!(numbers.get("stale").unwrap().is_none());
+
+        // A database from a newer build is refused
+        store.db().insert(SCHEMA_VERSION_KEY, &(SCHEMA_VERSION + 1).to_be_bytes()).unwrap();
+        drop((numbers, names, store));
+        assert!(Store::open(&dir).is_err());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,128 @@
+//! Schema migrations
+//!
+//! Each migration upgrades the database from one schema version to the
+//! next. `Store::open` applies pending ones in order at startup, recording
+//! the new version after each, so an interrupted upgrade resumes from the
+//! last completed step. `start --migrate-dry-run` and `db migrate --dry-run`
+//! list the pending ones without applying them.
+
+use anyhow::{Context, Result};
+
+/// One upgrade step from `from` to `from + 1`
+pub struct Migration {
+    pub from: u32,
+    pub description: &'static str,
+    apply: fn(&sled::Db) -> Result<()>,
+}
+
+impl Migration {
+    pub fn to(&self) -> u32 {
+        self.from + 1
+    }
+
+    pub(super) fn apply(&self, db: &sled::Db) -> Result<()> {
+        (self.apply)(db)
+    }
+}
+
+/// Every migration, ordered by `from`
+pub(super) static MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: 0,
+        description: "Record the schema version of databases created before it was kept",
+        apply: v0_record_version,
+    },
+    Migration {
+        from: 1,
+        description: "Store block index entries as hex hashes and keep finalized checkpoints by epoch",
+        apply: v1_checkpoint_history,
+    },
+];
+
+/// Unversioned databases have the version 1 layout; recording the version
+/// is all that changes
+fn v0_record_version(_db: &sled::Db) -> Result<()> {
+    Ok(())
+}
+
+/// Up to version 1, the block index held raw hash bytes and the only
+/// finalized checkpoint lived under `finalized` in the `chain_meta` tree.
+fn v1_checkpoint_history(db: &sled::Db) -> Result<()> {
+    const LEGACY_META_TREE: &[u8] = b"chain_meta";
+    const LEGACY_FINALIZED_KEY: &[u8] = b"finalized";
+
+    let index = db.open_tree("block_index")?;
+    for entry in index.iter() {
+        let (key, value) = entry?;
+        // Entries already in the new layout are bincode strings, not 32 raw bytes
+        if value.len() == crate::chain::HASH_LEN {
+            let hash = format!("0x{}", hex::encode(&value));
+            index.insert(key, bincode::serialize(&hash)?)?;
+        }
+    }
+
+    if db.tree_names().iter().any(|name| name == LEGACY_META_TREE) {
+        let meta = db.open_tree(LEGACY_META_TREE)?;
+        if let Some(value) = meta.get(LEGACY_FINALIZED_KEY)? {
+            let checkpoint: crate::chain::Checkpoint =
+                bincode::deserialize(&value).context("Malformed legacy finalized checkpoint")?;
+            db.open_tree("finalized_checkpoints")?
+                .insert(checkpoint.epoch.to_be_bytes(), value)?;
+        }
+        db.drop_tree(LEGACY_META_TREE)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::chain::tests::{block, hash};
+    use crate::chain::{ChainStore, Checkpoint, HASH_LEN};
+    use crate::events::Layer;
+    use crate::store::{Store, SCHEMA_VERSION};
+
+    #[test]
+    fn test_migrate_legacy_database() {
+        // Unversioned, and stamped version 1 with the same layout
+        for version in [None, Some(1u32)] {
+            migrate_legacy_database(version);
+        }
+    }
+
+    fn migrate_legacy_database(version: Option<u32>) {
+        let dir = std::env::temp_dir().join(format!("omne-migrate-{}", rand::random::<u32>()));
+
+        {
+            let db = sled::open(&dir).unwrap();
+            if let Some(version) = version {
+                db.insert("schema_version", &version.to_be_bytes()).unwrap();
+            }
+            let block = block(Layer::Commerce, 5, 1);
+            let hash_bytes = crate::chain::parse_hex(&hash(1), HASH_LEN).unwrap();
+            db.open_tree("blocks").unwrap().insert(&hash_bytes, bincode::serialize(&block).unwrap()).unwrap();
+            let mut key = vec![0];
+            key.extend(5u64.to_be_bytes());
+            db.open_tree("block_index").unwrap().insert(key, hash_bytes).unwrap();
+            let checkpoint = Checkpoint { epoch: 4, height: 2, block_hash: hash(1) };
+            db.open_tree("chain_meta").unwrap().insert("finalized", bincode::serialize(&checkpoint).unwrap()).unwrap();
+        }
+
+        let store = Store::open_unmigrated(&dir).unwrap();
+        let from = version.unwrap_or(0);
+        assert_eq!(store.schema_version().unwrap(), from);
+        assert_eq!(store.migrate(true).unwrap().len() as u32, SCHEMA_VERSION - from);
+        assert_eq!(store.schema_version().unwrap(), from, "dry run must not migrate");
+        drop(store);
+
+        let store = Store::open(&dir).unwrap();
+        assert_eq!(store.schema_version().unwrap(), SCHEMA_VERSION);
+        let chain = ChainStore::open(&store).unwrap();
+        assert_eq!(chain.latest_block(Layer::Commerce).unwrap().unwrap().header.hash, hash(1));
+        assert_eq!(chain.finalized_checkpoint().unwrap().unwrap().epoch, 4);
+        assert!(chain.verify().unwrap().is_empty());
+        assert!(!store.db().tree_names().iter().any(|name| name == &b"chain_meta"[..]));
+
+        drop((chain, store));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -3,10 +3,17 @@
 //! This module contains the core validator node logic that orchestrates
 //! consensus participation, P2P networking, and RPC services.
 
-use crate::config::ValidatorConfig;
+use crate::chain::ChainStore;
+use crate::config::{ConfigSource, ValidatorConfig};
 use crate::consensus::PoVERAValidator;
+use crate::events::EventBus;
+use crate::metrics::MetricsServer;
 use crate::p2p::P2PNetwork;
+use crate::pruner::Pruner;
+use crate::reload::ConfigReloader;
+use crate::slashing::SlashingProtection;
 use crate::rpc::RpcServer;
+use crate::store::Store;
 use crate::infrastructure::InfrastructureServices;
 
 use anyhow::{Result, Context};
@@ -27,39 +34,28 @@ pub struct ValidatorNode {
     rpc_server: Arc<RpcServer>,
     /// Infrastructure services manager
     infrastructure: Arc<InfrastructureServices>,
+    /// Prometheus metrics server, if enabled
+    metrics_server: Option<Arc<MetricsServer>>,
+    /// Deletes history behind finality in pruned mode
+    pruner: Arc<Pruner>,
+    /// Applies config.toml changes on SIGHUP and `admin_reloadConfig`
+    reloader: Arc<ConfigReloader>,
     /// Shutdown signal broadcaster
     shutdown_tx: broadcast::Sender<()>,
 }
 
 impl ValidatorNode {
-    /// Create a new validator node
-    pub async fn new(config: ValidatorConfig) -> Result<Self> {
+    /// Create a new validator node; `source` is what `config` was loaded
+    /// from, used again on reload
+    pub async fn new(config: ValidatorConfig, source: ConfigSource) -> Result<Self> {
         info!("🔧 Initializing Omne Validator Node");
         info!("   Network: {} (ID: {})", config.network.name, config.network.id);
         info!("   Data Directory: {}", config.data_dir.display());
         info!("   Validator Mode: {}", config.validator.is_validator);
         
         if config.validator.is_validator {
+            // The stake was checked against the network minimum by `ValidatorConfig::validate`
             info!("   Validator Stake: {} OGT", config.validator.validator_stake);
-            
-            // Validate dynamic stake requirements - BREAKTHROUGH OPTIMIZATION
-            let network_utilization = 0.5; // Default assumption - should be fetched from network
-            let validator_count = 50; // Default assumption - should be fetched from network
-            let required_stake = calculate_dynamic_stake_requirement(
-                network_utilization,
-                validator_count,
-                config.network.chain_spec.min_validator_stake
-            );
-            
-            if config.validator.validator_stake < required_stake {
-                return Err(anyhow::anyhow!(
-                    "Validator stake {} OGT is below dynamic minimum required {} OGT (based on network utilization: {:.1}%, {} validators)",
-                    config.validator.validator_stake,
-                    required_stake,
-                    network_utilization * 100.0,
-                    validator_count
-                ));
-            }
         }
         
         if config.oon.enable_oon {
@@ -72,36 +68,84 @@ impl ValidatorNode {
         // Create shutdown channel
         let (shutdown_tx, _) = broadcast::channel(1);
 
+        // Event bus shared by all components
+        let events = EventBus::new();
+
+        // Open the node database
+        let store = Store::open(&config.data_dir.join("db"))?;
+        let chain = Arc::new(ChainStore::open(&store)?);
+        let pruner = Arc::new(Pruner::new(&config.storage, chain.clone()));
+
+        // Open the slashing protection database
+        let slashing_protection = if config.validator.is_validator && config.validator.slashing_protection.enabled {
+            Some(Arc::new(SlashingProtection::open(&config.data_dir.join("slashing_protection"))?))
+        } else {
+            None
+        };
+
         // Initialize consensus validator
         let consensus = Arc::new(
-            PoVERAValidator::new(&config).await
+            PoVERAValidator::new(&config, chain.clone(), slashing_protection, events.clone()).await
                 .context("Failed to initialize consensus validator")?
         );
 
         // Initialize P2P network
         let p2p_network = Arc::new(
-            P2PNetwork::new(&config, consensus.clone()).await
+            P2PNetwork::new(&config, consensus.clone(), store.db(), events.clone()).await
                 .context("Failed to initialize P2P network")?
         );
 
-        // Initialize RPC server
-        let rpc_server = Arc::new(
-            RpcServer::new(&config, consensus.clone(), p2p_network.clone()).await
-                .context("Failed to initialize RPC server")?
-        );
-
         // Initialize infrastructure services
         let infrastructure = Arc::new(
             InfrastructureServices::new(&config).await
                 .context("Failed to initialize infrastructure services")?
         );
 
+        // Initialize RPC server
+        let rpc_server = Arc::new(
+            RpcServer::new(
+                &config,
+                consensus.clone(),
+                p2p_network.clone(),
+                infrastructure.clone(),
+                chain,
+                events.clone(),
+                shutdown_tx.clone(),
+            ).await
+                .context("Failed to initialize RPC server")?
+        );
+
+        // Initialize configuration reload
+        let reloader = Arc::new(ConfigReloader::new(
+            source,
+            &config,
+            p2p_network.clone(),
+            rpc_server.clone(),
+            infrastructure.clone(),
+        )?);
+        rpc_server.set_reloader(&reloader);
+
+        // Initialize metrics server
+        let metrics_server = if config.metrics.enabled {
+            Some(Arc::new(MetricsServer::new(
+                &config,
+                consensus.clone(),
+                p2p_network.clone(),
+                rpc_server.clone(),
+            )?))
+        } else {
+            None
+        };
+
         Ok(Self {
             config,
             consensus,
             p2p_network,
             rpc_server,
             infrastructure,
+            metrics_server,
+            pruner,
+            reloader,
             shutdown_tx,
         })
     }
@@ -156,9 +200,44 @@ impl ValidatorNode {
             })
         };
 
+        // Start metrics server
+        if let Some(metrics_server) = self.metrics_server.clone() {
+            let shutdown_rx = self.shutdown_tx.subscribe();
+            tokio::spawn(async move {
+                if let Err(e) = metrics_server.start(shutdown_rx).await {
+                    error!("Metrics server error: {}", e);
+                }
+            });
+        }
+
+        // Start history pruning
+        {
+            let pruner = self.pruner.clone();
+            let shutdown_rx = self.shutdown_tx.subscribe();
+            tokio::spawn(async move {
+                if let Err(e) = pruner.start(shutdown_rx).await {
+                    error!("Pruner error: {}", e);
+                }
+            });
+        }
+
+        // Start configuration reload on SIGHUP
+        {
+            let reloader = self.reloader.clone();
+            let shutdown_rx = self.shutdown_tx.subscribe();
+            tokio::spawn(async move {
+                if let Err(e) = reloader.start(shutdown_rx).await {
+                    error!("Configuration reload error: {}", e);
+                }
+            });
+        }
+
         info!("✅ Validator node started successfully");
         info!("   P2P Port: {}", self.config.p2p.port);
         info!("   RPC Port: {}", self.config.rpc.port);
+        if self.config.metrics.enabled {
+            info!("   Metrics Port: {}", self.config.metrics.port);
+        }
         
         if self.config.validator.is_validator {
             info!("🏛️  Validator is active and participating in consensus");
@@ -208,8 +287,9 @@ impl ValidatorNode {
     }
 
     /// Get validator node status
+    #[allow(dead_code)] // Nothing asks for the combined status yet
     pub async fn status(&self) -> Result<ValidatorStatus> {
-        let consensus_status = self.consensus.status().await?;
+        let consensus_status = self.consensus.get_status();
         let p2p_status = self.p2p_network.status().await?;
         let rpc_status = self.rpc_server.status().await?;
         let infrastructure_status = self.infrastructure.status().await?;
@@ -224,22 +304,9 @@ impl ValidatorNode {
     }
 }
 
-/// Dynamic stake calculation function - BREAKTHROUGH OPTIMIZATION
-/// Replaces static minimum stake requirements with network-responsive calculation
-fn calculate_dynamic_stake_requirement(
-    network_utilization: f64,
-    validator_count: u32,
-    base_stake: u64
-) -> u64 {
-    let utilization_factor = (0.5_f64).max((2.0_f64).min(1.0 + network_utilization));
-    let validator_density = (0.8_f64).max((1.5_f64).min(validator_count as f64 / 100.0));
-    
-    let dynamic_stake = base_stake as f64 * utilization_factor * validator_density;
-    (15_u64).max((150_u64).min(dynamic_stake as u64)) // Hard stability limits: 15-150 OGT
-}
-
 /// Validator node status information
 #[derive(Debug, Clone)]
+#[allow(dead_code)] // Returned by `status` only
 pub struct ValidatorStatus {
     pub consensus: crate::consensus::ConsensusStatus,
     pub p2p: crate::p2p::P2PStatus,
@@ -247,4 +314,3 @@ pub struct ValidatorStatus {
     pub infrastructure: crate::infrastructure::InfrastructureStatus,
     pub config: ValidatorConfig,
 }
-}